@@ -3,15 +3,24 @@
 Contains Twilio account related functionality.
 
 */
+pub mod auth_tokens;
+pub mod keys;
+pub mod usage;
 
 use std::fmt;
 
 use reqwest::Method;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 
 use crate::{Client, TwilioError};
 
+use self::{
+    auth_tokens::AuthTokens,
+    keys::{Key, Keys},
+    usage::Usage,
+};
+
 /// Holds account related functions accessible
 /// on the client.
 pub struct Accounts<'a> {
@@ -54,6 +63,15 @@ impl fmt::Display for Account {
     }
 }
 
+/// The balance of an account.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Balance {
+    pub account_sid: String,
+    #[serde(deserialize_with = "decimal")]
+    pub balance: f64,
+    pub currency: String,
+}
+
 /// Possible Account statuses.
 #[derive(
     AsRefStr,
@@ -107,6 +125,37 @@ pub struct CreateParams {
 }
 
 impl<'a> Accounts<'a> {
+    /// Auth token rotation for the account the client acts on.
+    pub fn auth_tokens(&self) -> AuthTokens<'a> {
+        AuthTokens {
+            client: self.client,
+        }
+    }
+
+    /// General API Key actions for the account the client acts on.
+    pub fn keys(&self) -> Keys<'a> {
+        Keys {
+            client: self.client,
+        }
+    }
+
+    /// Usage Records and Triggers for the account the client acts on.
+    pub fn usage(&self) -> Usage<'a> {
+        Usage {
+            client: self.client,
+        }
+    }
+
+    /// Actions relating to a known API Key.
+    ///
+    /// Takes in the SID of the Key to perform actions against.
+    pub fn key<'b>(&self, sid: &'b str) -> Key<'a, 'b> {
+        Key {
+            client: self.client,
+            sid,
+        }
+    }
+
     /// [Gets an Account](https://www.twilio.com/docs/iam/api/account#fetch-an-account-resource)
     ///
     /// Takes in an optional `sid` argument otherwise will default to the current config
@@ -117,7 +166,7 @@ impl<'a> Accounts<'a> {
                 Method::GET,
                 &format!(
                     "https://api.twilio.com/2010-04-01/Accounts/{}.json",
-                    sid.unwrap_or_else(|| &self.client.config.account_sid)
+                    sid.unwrap_or_else(|| self.client.account_sid())
                 ),
                 None,
                 None,
@@ -197,6 +246,19 @@ impl<'a> Accounts<'a> {
             .await
     }
 
+    /// [Gets the balance](https://www.twilio.com/docs/usage/api/balance) of the account the
+    /// client acts on.
+    pub async fn balance(&self) -> Result<Balance, TwilioError> {
+        self.client
+            .send_request::<Balance, ()>(
+                Method::GET,
+                &self.client.account_url("Balance.json"),
+                None,
+                None,
+            )
+            .await
+    }
+
     /// [Updates an account resource](https://www.twilio.com/docs/iam/api/account#update-an-account-resource)
     /// under the authenticated Twilio account.
     ///
@@ -229,3 +291,24 @@ impl<'a> Accounts<'a> {
             .await
     }
 }
+
+/// Twilio returns amounts such as usage and balances as either numbers or decimal strings,
+/// and sometimes null.
+fn decimal<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Decimal {
+        Number(f64),
+        Text(String),
+    }
+
+    match Option::<Decimal>::deserialize(deserializer)? {
+        None => Ok(0.0),
+        Some(Decimal::Number(value)) => Ok(value),
+        Some(Decimal::Text(value)) if value.is_empty() => Ok(0.0),
+        Some(Decimal::Text(value)) => value.parse().map_err(serde::de::Error::custom),
+    }
+}
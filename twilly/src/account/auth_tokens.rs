@@ -0,0 +1,80 @@
+/*!
+
+Contains Twilio auth token rotation functionality.
+
+A secondary auth token is created alongside the primary, checked, and then
+promoted to replace the primary. The previous primary token stops working as
+soon as the secondary is promoted.
+
+*/
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{Client, TwilioError};
+
+/// A primary auth token, as returned once a secondary token is promoted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthTokenPromotion {
+    pub account_sid: String,
+    pub auth_token: String,
+    pub date_created: String,
+    pub date_updated: String,
+    pub url: String,
+}
+
+/// A secondary auth token.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SecondaryAuthToken {
+    pub account_sid: String,
+    pub secondary_auth_token: String,
+    pub date_created: String,
+    pub date_updated: String,
+    pub url: String,
+}
+
+/// Holds auth token related functions for the account the client acts on.
+pub struct AuthTokens<'a> {
+    pub client: &'a Client,
+}
+
+impl<'a> AuthTokens<'a> {
+    /// [Creates a secondary auth token](https://www.twilio.com/docs/iam/api/secondary-auth-token#create-a-secondaryauthtoken-resource)
+    ///
+    /// Replaces any existing secondary token.
+    pub async fn create_secondary(&self) -> Result<SecondaryAuthToken, TwilioError> {
+        self.client
+            .send_request::<SecondaryAuthToken, ()>(
+                Method::POST,
+                "https://accounts.twilio.com/v1/AuthTokens/Secondary",
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Deletes the secondary auth token](https://www.twilio.com/docs/iam/api/secondary-auth-token#delete-a-secondaryauthtoken-resource)
+    pub async fn delete_secondary(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                "https://accounts.twilio.com/v1/AuthTokens/Secondary",
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Promotes the secondary auth token](https://www.twilio.com/docs/iam/api/authtokenpromotion-resource#update-an-authtokenpromotion-resource)
+    /// to primary. The previous primary token is invalidated immediately.
+    pub async fn promote_secondary(&self) -> Result<AuthTokenPromotion, TwilioError> {
+        self.client
+            .send_request::<AuthTokenPromotion, ()>(
+                Method::POST,
+                "https://accounts.twilio.com/v1/AuthTokens/Promote",
+                None,
+                None,
+            )
+            .await
+    }
+}
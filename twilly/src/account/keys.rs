@@ -0,0 +1,190 @@
+/*!
+
+Contains Twilio API Key related functionality.
+
+Keys are created as either standard keys, which can access every API except
+managing accounts and keys, or restricted keys limited by a permissions
+policy. The secret is only returned when a key is created.
+
+*/
+
+use std::fmt;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{Client, TwilioError};
+
+/// Represents a page of API Keys from the Twilio API.
+#[derive(Deserialize)]
+pub struct KeyPage {
+    keys: Vec<ApiKey>,
+    next_page_uri: Option<String>,
+}
+
+/// An API Key, without its secret.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub sid: String,
+    pub friendly_name: String,
+    pub date_created: String,
+    pub date_updated: String,
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.friendly_name, self.sid)
+    }
+}
+
+/// A newly created API Key. The secret cannot be retrieved again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewApiKey {
+    pub sid: String,
+    pub friendly_name: String,
+    pub date_created: String,
+    pub date_updated: String,
+    pub secret: String,
+}
+
+/// Parameters for creating or updating a standard Key.
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct KeyParams {
+    pub friendly_name: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+struct RestrictedKeyParams<'a> {
+    account_sid: &'a str,
+    friendly_name: &'a str,
+    key_type: &'static str,
+    policy: &'a str,
+}
+
+/// Holds API Key functions for the account the client acts on.
+pub struct Keys<'a> {
+    pub client: &'a Client,
+}
+
+impl<'a> Keys<'a> {
+    /// [Creates a standard Key](https://www.twilio.com/docs/iam/api/keys#create-a-new-api-key)
+    ///
+    /// Takes in an optional `friendly_name`. The returned secret should be stored straight
+    /// away as it is not available again.
+    pub async fn create(&self, friendly_name: Option<&str>) -> Result<NewApiKey, TwilioError> {
+        let params = KeyParams {
+            friendly_name: friendly_name.map(|friendly_name| friendly_name.to_string()),
+        };
+
+        self.client
+            .send_request::<NewApiKey, KeyParams>(
+                Method::POST,
+                &self.client.account_url("Keys.json"),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Creates a restricted Key](https://www.twilio.com/docs/iam/api-keys/restricted-api-keys)
+    ///
+    /// `policy` is the JSON permissions policy, e.g.
+    /// `{"allow": ["/twilio/messaging/messages/read"]}`.
+    pub async fn create_restricted(
+        &self,
+        friendly_name: &str,
+        policy: &str,
+    ) -> Result<NewApiKey, TwilioError> {
+        let params = RestrictedKeyParams {
+            account_sid: self.client.account_sid(),
+            friendly_name,
+            key_type: "restricted",
+            policy,
+        };
+
+        self.client
+            .send_request::<NewApiKey, RestrictedKeyParams>(
+                Method::POST,
+                "https://iam.twilio.com/v1/Keys",
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Keys](https://www.twilio.com/docs/iam/api/keys#read-multiple-key-resources)
+    ///
+    /// Keys will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ApiKey>, TwilioError> {
+        let mut keys_page = self
+            .client
+            .send_request::<KeyPage, ()>(
+                Method::GET,
+                &self.client.account_url("Keys.json?PageSize=50"),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ApiKey> = keys_page.keys;
+
+        while let Some(next_page_uri) = keys_page.next_page_uri {
+            keys_page = self
+                .client
+                .send_request::<KeyPage, ()>(
+                    Method::GET,
+                    &format!("https://api.twilio.com{}", next_page_uri),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut keys_page.keys);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Holds functions for a known API Key.
+pub struct Key<'a, 'b> {
+    pub client: &'a Client,
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Key<'a, 'b> {
+    fn url(&self) -> String {
+        self.client.account_url(&format!("Keys/{}.json", self.sid))
+    }
+
+    /// [Gets a Key](https://www.twilio.com/docs/iam/api/keys#fetch-a-key-resource)
+    pub async fn get(&self) -> Result<ApiKey, TwilioError> {
+        self.client
+            .send_request::<ApiKey, ()>(Method::GET, &self.url(), None, None)
+            .await
+    }
+
+    /// [Updates a Key](https://www.twilio.com/docs/iam/api/keys#update-a-key-resource)
+    ///
+    /// Only the friendly name can be changed.
+    pub async fn update(&self, friendly_name: &str) -> Result<ApiKey, TwilioError> {
+        let params = KeyParams {
+            friendly_name: Some(friendly_name.to_string()),
+        };
+
+        self.client
+            .send_request::<ApiKey, KeyParams>(Method::POST, &self.url(), Some(&params), None)
+            .await
+    }
+
+    /// [Deletes a Key](https://www.twilio.com/docs/iam/api/keys#delete-a-key-resource)
+    ///
+    /// Requests signed with the Key fail immediately afterwards.
+    pub async fn delete(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(Method::DELETE, &self.url(), None, None)
+            .await
+    }
+}
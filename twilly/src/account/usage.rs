@@ -0,0 +1,396 @@
+/*!
+
+Contains Twilio account usage functionality.
+
+Usage Records report what an account used and spent per usage category, e.g.
+`sms` or `calls-inbound`, over a period. Usage Triggers call a webhook once
+usage of a category reaches a threshold.
+
+Records can be rolled up into spend per category with `summarize_by_category`,
+including across several accounts.
+
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{Client, TwilioError};
+
+use super::decimal;
+
+/// Represents a page of Usage Records from the Twilio API.
+#[derive(Deserialize)]
+pub struct UsageRecordPage {
+    usage_records: Vec<UsageRecord>,
+    next_page_uri: Option<String>,
+}
+
+/// Represents a page of Usage Triggers from the Twilio API.
+#[derive(Deserialize)]
+pub struct UsageTriggerPage {
+    usage_triggers: Vec<UsageTrigger>,
+    next_page_uri: Option<String>,
+}
+
+/// Usage of a single category over a period.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub account_sid: String,
+    pub category: String,
+    pub description: String,
+    pub start_date: String,
+    pub end_date: String,
+    #[serde(default, deserialize_with = "decimal")]
+    pub count: f64,
+    pub count_unit: Option<String>,
+    #[serde(default, deserialize_with = "decimal")]
+    pub usage: f64,
+    pub usage_unit: Option<String>,
+    #[serde(default, deserialize_with = "decimal")]
+    pub price: f64,
+    pub price_unit: Option<String>,
+}
+
+/// A Usage Trigger resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UsageTrigger {
+    pub sid: String,
+    pub account_sid: String,
+    pub friendly_name: Option<String>,
+    pub usage_category: String,
+    pub trigger_by: String,
+    pub trigger_value: String,
+    pub current_value: Option<String>,
+    pub recurring: Option<String>,
+    pub callback_url: String,
+    pub callback_method: String,
+    pub date_fired: Option<String>,
+    pub date_created: String,
+    pub date_updated: String,
+}
+
+/// Period Usage Records are reported over. Besides `Total`, records are split into one per
+/// category for each day, month or year in the range, or cover a fixed recent period.
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, EnumString, PartialEq)]
+pub enum Period {
+    /// A single record per category covering the requested date range.
+    #[strum(to_string = "Total")]
+    Total,
+    Daily,
+    Monthly,
+    Yearly,
+    Today,
+    #[default]
+    #[strum(to_string = "This month")]
+    ThisMonth,
+    #[strum(to_string = "Last month")]
+    LastMonth,
+}
+
+impl Period {
+    fn path(&self) -> &'static str {
+        match self {
+            Period::Total => "Records.json",
+            Period::Daily => "Records/Daily.json",
+            Period::Monthly => "Records/Monthly.json",
+            Period::Yearly => "Records/Yearly.json",
+            Period::Today => "Records/Today.json",
+            Period::ThisMonth => "Records/ThisMonth.json",
+            Period::LastMonth => "Records/LastMonth.json",
+        }
+    }
+}
+
+/// Possible filters when listing Usage Records.
+#[skip_serializing_none]
+#[derive(Default, Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct ListParams {
+    /// Only include this usage category, e.g. `sms`.
+    pub category: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// Whether subaccount usage is added to the account's. Twilio includes it by default.
+    pub include_subaccounts: Option<bool>,
+}
+
+/// What a Usage Trigger's threshold is measured in.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerBy {
+    Count,
+    Usage,
+    Price,
+}
+
+/// How often a Usage Trigger resets so it can fire again.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Recurring {
+    Daily,
+    Monthly,
+    Yearly,
+    #[strum(to_string = "Never")]
+    Alltime,
+}
+
+/// Parameters for creating a Usage Trigger.
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct CreateTriggerParams {
+    pub callback_url: String,
+    /// Usage of the category at which the callback is made, measured by `trigger_by`.
+    pub trigger_value: String,
+    pub usage_category: String,
+    pub callback_method: Option<String>,
+    pub friendly_name: Option<String>,
+    pub recurring: Option<Recurring>,
+    pub trigger_by: Option<TriggerBy>,
+}
+
+/// Spend on a usage category, summed across records.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CategorySpend {
+    pub category: String,
+    pub description: String,
+    pub usage: f64,
+    pub usage_unit: Option<String>,
+    pub price: f64,
+    pub price_unit: Option<String>,
+}
+
+/// Holds usage functions for the account the client acts on.
+pub struct Usage<'a> {
+    pub client: &'a Client,
+}
+
+impl<'a> Usage<'a> {
+    /// [Lists Usage Records](https://www.twilio.com/docs/usage/api/usage-record#read-multiple-usagerecord-resources)
+    /// over the given `period`.
+    ///
+    /// Records will be _eagerly_ paged until all retrieved.
+    pub async fn records(
+        &self,
+        period: Period,
+        params: &ListParams,
+    ) -> Result<Vec<UsageRecord>, TwilioError> {
+        let mut records_page = self
+            .client
+            .send_request::<UsageRecordPage, ListParams>(
+                Method::GET,
+                &self
+                    .client
+                    .account_url(&format!("Usage/{}?PageSize=1000", period.path())),
+                Some(params),
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<UsageRecord> = records_page.usage_records;
+
+        while let Some(next_page_uri) = records_page.next_page_uri {
+            records_page = self
+                .client
+                .send_request::<UsageRecordPage, ()>(
+                    Method::GET,
+                    &format!("https://api.twilio.com{}", next_page_uri),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut records_page.usage_records);
+        }
+
+        Ok(results)
+    }
+
+    /// [Creates a Usage Trigger](https://www.twilio.com/docs/usage/api/usage-trigger#create-a-usagetrigger-resource)
+    pub async fn create_trigger(
+        &self,
+        params: CreateTriggerParams,
+    ) -> Result<UsageTrigger, TwilioError> {
+        self.client
+            .send_request::<UsageTrigger, CreateTriggerParams>(
+                Method::POST,
+                &self.client.account_url("Usage/Triggers.json"),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Usage Triggers](https://www.twilio.com/docs/usage/api/usage-trigger#read-multiple-usagetrigger-resources)
+    ///
+    /// Triggers will be _eagerly_ paged until all retrieved.
+    pub async fn triggers(&self) -> Result<Vec<UsageTrigger>, TwilioError> {
+        let mut triggers_page = self
+            .client
+            .send_request::<UsageTriggerPage, ()>(
+                Method::GET,
+                &self.client.account_url("Usage/Triggers.json?PageSize=50"),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<UsageTrigger> = triggers_page.usage_triggers;
+
+        while let Some(next_page_uri) = triggers_page.next_page_uri {
+            triggers_page = self
+                .client
+                .send_request::<UsageTriggerPage, ()>(
+                    Method::GET,
+                    &format!("https://api.twilio.com{}", next_page_uri),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut triggers_page.usage_triggers);
+        }
+
+        Ok(results)
+    }
+
+    /// [Deletes a Usage Trigger](https://www.twilio.com/docs/usage/api/usage-trigger#delete-a-usagetrigger-resource)
+    pub async fn delete_trigger(&self, sid: &str) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                &self
+                    .client
+                    .account_url(&format!("Usage/Triggers/{}.json", sid)),
+                None,
+                None,
+            )
+            .await
+    }
+}
+
+/// Sums usage and spend per category, e.g. across the records of several accounts or days.
+/// Categories without spend are left out and the rest are ordered by spend, highest first.
+///
+/// Twilio also reports rollups: `totalprice` and parent categories such as `sms` alongside
+/// their children, e.g. `sms-outbound`. These are left out so no spend is counted twice and
+/// the rows add up to the total. A parent is kept when none of its children are present.
+pub fn summarize_by_category(records: &[UsageRecord]) -> Vec<CategorySpend> {
+    let reported: BTreeSet<&str> = records
+        .iter()
+        .map(|record| record.category.as_str())
+        .collect();
+    let is_rollup = |category: &str| {
+        category == "totalprice"
+            || reported
+                .range(category..)
+                .map_while(|other| other.strip_prefix(category))
+                .any(|rest| rest.starts_with('-'))
+    };
+
+    let mut categories: BTreeMap<&str, CategorySpend> = BTreeMap::new();
+    for record in records.iter().filter(|record| !is_rollup(&record.category)) {
+        let spend = categories
+            .entry(&record.category)
+            .or_insert_with(|| CategorySpend {
+                category: record.category.clone(),
+                description: record.description.clone(),
+                usage: 0.0,
+                usage_unit: record.usage_unit.clone(),
+                price: 0.0,
+                price_unit: record.price_unit.clone(),
+            });
+        spend.usage += record.usage;
+        spend.price += record.price;
+    }
+
+    let mut summary: Vec<CategorySpend> = categories
+        .into_values()
+        .filter(|spend| spend.price != 0.0)
+        .collect();
+    summary.sort_by(|a, b| b.price.total_cmp(&a.price));
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(account_sid: &str, category: &str, usage: &str, price: &str) -> UsageRecord {
+        serde_json::from_value(serde_json::json!({
+            "account_sid": account_sid,
+            "category": category,
+            "description": category,
+            "start_date": "2024-01-01",
+            "end_date": "2024-01-31",
+            "count": "1",
+            "count_unit": "messages",
+            "usage": usage,
+            "usage_unit": "segments",
+            "price": price,
+            "price_unit": "usd",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_decimal_amounts() {
+        let record: UsageRecord = serde_json::from_value(serde_json::json!({
+            "account_sid": "ACxx",
+            "category": "sms",
+            "description": "SMS",
+            "start_date": "2024-01-01",
+            "end_date": "2024-01-31",
+            "count": null,
+            "count_unit": null,
+            "usage": 12,
+            "usage_unit": "segments",
+            "price": "-0.0079",
+            "price_unit": "usd",
+        }))
+        .unwrap();
+
+        assert_eq!(0.0, record.count);
+        assert_eq!(12.0, record.usage);
+        assert_eq!(-0.0079, record.price);
+    }
+
+    #[test]
+    fn summarizes_spend_per_category() {
+        let summary = summarize_by_category(&[
+            record("AC1", "sms", "10", "0.5"),
+            record("AC2", "sms", "30", "1.5"),
+            record("AC1", "calls", "5", "3"),
+            record("AC2", "phonenumbers", "1", "0"),
+        ]);
+
+        assert_eq!(2, summary.len());
+        assert_eq!("calls", summary[0].category);
+        assert_eq!("sms", summary[1].category);
+        assert_eq!(40.0, summary[1].usage);
+        assert_eq!(2.0, summary[1].price);
+    }
+
+    #[test]
+    fn leaves_rollups_out_of_summary() {
+        let summary = summarize_by_category(&[
+            record("AC1", "totalprice", "0", "5.5"),
+            record("AC1", "sms", "30", "2.5"),
+            record("AC1", "sms-outbound", "20", "2"),
+            record("AC1", "sms-inbound", "10", "0.5"),
+            record("AC1", "calls", "5", "3"),
+        ]);
+
+        let categories: Vec<&str> = summary
+            .iter()
+            .map(|spend| spend.category.as_str())
+            .collect();
+        assert_eq!(vec!["calls", "sms-outbound", "sms-inbound"], categories);
+        assert_eq!(5.5, summary.iter().map(|spend| spend.price).sum::<f64>());
+    }
+}
@@ -224,7 +224,7 @@ impl<'a> Conversations<'a> {
     }
 
     /// Participant Conversation related functions.
-    pub fn participant_conversations(&self) -> ParticipantConversations {
+    pub fn participant_conversations(&self) -> ParticipantConversations<'_> {
         ParticipantConversations {
             client: self.client,
         }
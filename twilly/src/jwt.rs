@@ -0,0 +1,272 @@
+/*!
+
+Contains Twilio Access Token generation.
+
+[Access Tokens](https://www.twilio.com/docs/iam/access-tokens) are short-lived JWTs
+handed to front-end clients so they can use the Sync, Conversations, Voice and
+Video SDKs. They are signed with an API Key secret and carry the identity of the
+user along with a grant for each product they may use.
+
+*/
+
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+
+use crate::{ErrorKind, TwilioError};
+
+/// The longest TTL Twilio accepts for an Access Token (24 hours).
+pub const MAX_TTL: Duration = Duration::from_secs(86_400);
+
+/// An Access Token to be signed into a JWT with `to_jwt()`.
+#[derive(Clone, Debug)]
+pub struct AccessToken {
+    /// Twilio account SID, begins with AC...
+    pub account_sid: String,
+    /// SID of the API Key used to sign the token, begins with SK...
+    pub api_key_sid: String,
+    pub api_key_secret: String,
+    /// Identity of the user the token is issued to.
+    pub identity: String,
+    /// How long the token is valid for. Defaults to one hour.
+    pub ttl: Duration,
+    pub grants: Grants,
+}
+
+/// The products an Access Token gives access to.
+#[derive(Clone, Debug, Default)]
+pub struct Grants {
+    pub sync: Option<SyncGrant>,
+    pub chat: Option<ChatGrant>,
+    pub voice: Option<VoiceGrant>,
+    pub video: Option<VideoGrant>,
+}
+
+/// Access to a Sync Service.
+#[derive(Clone, Debug)]
+pub struct SyncGrant {
+    /// SID of the Sync Service, begins with IS...
+    pub service_sid: String,
+    pub endpoint_id: Option<String>,
+}
+
+/// Access to a Conversations Service.
+#[derive(Clone, Debug)]
+pub struct ChatGrant {
+    /// SID of the Conversations Service, begins with IS...
+    pub service_sid: String,
+    pub endpoint_id: Option<String>,
+    pub push_credential_sid: Option<String>,
+}
+
+/// Access to Programmable Voice.
+#[derive(Clone, Debug, Default)]
+pub struct VoiceGrant {
+    /// Whether the identity can receive incoming calls.
+    pub incoming_allow: bool,
+    /// SID of the TwiML App used for outgoing calls, begins with AP...
+    pub outgoing_application_sid: Option<String>,
+    /// Parameters passed to the TwiML App on outgoing calls.
+    pub outgoing_application_params: Option<Value>,
+    pub push_credential_sid: Option<String>,
+}
+
+/// Access to Programmable Video.
+#[derive(Clone, Debug, Default)]
+pub struct VideoGrant {
+    /// Name or SID of the only Room the identity may join. Any Room if not provided.
+    pub room: Option<String>,
+}
+
+impl AccessToken {
+    /// Creates an Access Token valid for one hour with no grants.
+    pub fn new(
+        account_sid: &str,
+        api_key_sid: &str,
+        api_key_secret: &str,
+        identity: &str,
+    ) -> AccessToken {
+        AccessToken {
+            account_sid: account_sid.to_string(),
+            api_key_sid: api_key_sid.to_string(),
+            api_key_secret: api_key_secret.to_string(),
+            identity: identity.to_string(),
+            ttl: Duration::from_secs(3600),
+            grants: Grants::default(),
+        }
+    }
+
+    /// Signs the token, returning the encoded JWT.
+    pub fn to_jwt(&self) -> Result<String, TwilioError> {
+        self.validate()?;
+
+        let now = chrono::Utc::now();
+        let header = json!({ "typ": "JWT", "alg": "HS256", "cty": "twilio-fpa;v=1" });
+        let claims = json!({
+            "jti": format!("{}-{}", self.api_key_sid, now.timestamp_nanos_opt().unwrap_or_default()),
+            "iss": self.api_key_sid,
+            "sub": self.account_sid,
+            "iat": now.timestamp(),
+            "exp": now.timestamp() + self.ttl.as_secs() as i64,
+            "grants": self.grants_claim(),
+        });
+
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_key_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(signing_input.as_bytes());
+
+        Ok(format!(
+            "{}.{}",
+            signing_input,
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    // Checks the token can be signed and will be accepted by Twilio.
+    fn validate(&self) -> Result<(), TwilioError> {
+        let error = if !self.api_key_sid.starts_with("SK") {
+            "API Key SID must start with SK"
+        } else if self.api_key_secret.is_empty() {
+            "API Key secret must be provided"
+        } else if self.identity.is_empty() {
+            "Identity must be provided"
+        } else if self.ttl.is_zero() || self.ttl > MAX_TTL {
+            "TTL must be between 1 second and 24 hours"
+        } else {
+            return Ok(());
+        };
+
+        Err(TwilioError {
+            kind: ErrorKind::ValidationError(String::from(error)),
+        })
+    }
+
+    // Builds the `grants` claim in the shape expected by Twilio.
+    fn grants_claim(&self) -> Value {
+        let mut grants = Map::new();
+        grants.insert(String::from("identity"), json!(self.identity));
+
+        if let Some(sync) = &self.grants.sync {
+            let mut grant = json!({ "service_sid": sync.service_sid });
+            insert_optional(&mut grant, "endpoint_id", &sync.endpoint_id);
+            grants.insert(String::from("data_sync"), grant);
+        }
+
+        if let Some(chat) = &self.grants.chat {
+            let mut grant = json!({ "service_sid": chat.service_sid });
+            insert_optional(&mut grant, "endpoint_id", &chat.endpoint_id);
+            insert_optional(&mut grant, "push_credential_sid", &chat.push_credential_sid);
+            grants.insert(String::from("chat"), grant);
+        }
+
+        if let Some(voice) = &self.grants.voice {
+            let mut grant = json!({});
+            if voice.incoming_allow {
+                grant["incoming"] = json!({ "allow": true });
+            }
+            if let Some(application_sid) = &voice.outgoing_application_sid {
+                grant["outgoing"] = json!({ "application_sid": application_sid });
+                if let Some(params) = &voice.outgoing_application_params {
+                    grant["outgoing"]["params"] = params.clone();
+                }
+            }
+            insert_optional(
+                &mut grant,
+                "push_credential_sid",
+                &voice.push_credential_sid,
+            );
+            grants.insert(String::from("voice"), grant);
+        }
+
+        if let Some(video) = &self.grants.video {
+            let mut grant = json!({});
+            insert_optional(&mut grant, "room", &video.room);
+            grants.insert(String::from("video"), grant);
+        }
+
+        Value::Object(grants)
+    }
+}
+
+fn insert_optional(grant: &mut Value, field: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        grant[field] = json!(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_claims(jwt: &str) -> Value {
+        let claims = jwt.split('.').nth(1).unwrap();
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn signs_token_with_grants() {
+        let mut token = AccessToken::new("ACxx", "SKxx", "secret", "alice");
+        token.grants.sync = Some(SyncGrant {
+            service_sid: String::from("ISsync"),
+            endpoint_id: None,
+        });
+        token.grants.voice = Some(VoiceGrant {
+            incoming_allow: true,
+            outgoing_application_sid: Some(String::from("APxx")),
+            ..Default::default()
+        });
+        token.grants.video = Some(VideoGrant::default());
+
+        let jwt = token.to_jwt().unwrap();
+        assert_eq!(3, jwt.split('.').count());
+
+        let claims = decode_claims(&jwt);
+        assert_eq!("SKxx", claims["iss"]);
+        assert_eq!("ACxx", claims["sub"]);
+        assert_eq!(
+            3600,
+            claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap()
+        );
+        assert_eq!("alice", claims["grants"]["identity"]);
+        assert_eq!("ISsync", claims["grants"]["data_sync"]["service_sid"]);
+        assert_eq!(true, claims["grants"]["voice"]["incoming"]["allow"]);
+        assert_eq!(
+            "APxx",
+            claims["grants"]["voice"]["outgoing"]["application_sid"]
+        );
+        assert_eq!(json!({}), claims["grants"]["video"]);
+        assert!(claims["grants"].get("chat").is_none());
+    }
+
+    #[test]
+    fn signature_verifies_with_secret() {
+        let jwt = AccessToken::new("ACxx", "SKxx", "secret", "alice")
+            .to_jwt()
+            .unwrap();
+        let (signing_input, signature) = jwt.rsplit_once('.').unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(signing_input.as_bytes());
+        assert!(mac
+            .verify_slice(&URL_SAFE_NO_PAD.decode(signature).unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_tokens() {
+        let mut token = AccessToken::new("ACxx", "ACxx", "secret", "alice");
+        assert!(token.to_jwt().is_err());
+
+        token.api_key_sid = String::from("SKxx");
+        token.ttl = MAX_TTL + Duration::from_secs(1);
+        assert!(token.to_jwt().is_err());
+    }
+}
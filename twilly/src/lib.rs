@@ -16,25 +16,37 @@ Interaction is done via a Twilio client that can be created via the constructor.
 parameter is a `TwilioConfig` struct of an account SID & auth token pair.
 
 ```
+# let config = twilly::TwilioConfig::default();
 let twilio = twilly::Client::new(&config);
 ```
 
 To retrieve accounts from the client:
 
-```
-twilio.accounts().list(Some(&friendly_name), None);
+```no_run
+# async fn run() -> Result<(), twilly::TwilioError> {
+# let twilio = twilly::Client::new(&twilly::TwilioConfig::default());
+# let friendly_name = String::new();
+twilio.accounts().list(Some(&friendly_name), None).await?;
+# Ok(())
+# }
 ```
 
 To delete a conversation:
 
-```
-twilio.conversations().delete(&conversation_sid);
+```no_run
+# async fn run() -> Result<(), twilly::TwilioError> {
+# let twilio = twilly::Client::new(&twilly::TwilioConfig::default());
+# let conversation_sid = String::new();
+twilio.conversations().delete(&conversation_sid).await?;
+# Ok(())
+# }
 ```
 
 */
 
 pub mod account;
 pub mod conversation;
+pub mod jwt;
 pub mod participant_conversation;
 pub mod serverless;
 pub mod sync;
@@ -88,6 +100,9 @@ impl TwilioConfig {
 /// Twilio's API.
 pub struct Client {
     pub config: TwilioConfig,
+    /// Account requests act on. This is the config account unless the client was
+    /// created with `for_subaccount`.
+    account_sid: String,
     client: reqwest::Client,
 }
 
@@ -104,8 +119,10 @@ impl fmt::Display for TwilioError {
     }
 }
 
-/// A list of possible errors from the Twilio client.
+/// A list of possible errors from the Twilio client. More may be added, so matches need a
+/// wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// Validation error related to incoming arguments.
     ValidationError(String),
@@ -115,6 +132,14 @@ pub enum ErrorKind {
     TwilioError(TwilioApiError),
     /// Unable to parse request or response body
     ParsingError(reqwest::Error),
+    /// Error on a real-time (WebSocket) connection.
+    ConnectionError(String),
+    /// Unable to read or write a local file
+    IoError(std::io::Error),
+    /// An operation did not finish within the time allowed
+    TimeoutError(String),
+    /// A Serverless Build failed
+    BuildError(String),
 }
 
 impl ErrorKind {
@@ -125,6 +150,10 @@ impl ErrorKind {
             }
             ErrorKind::NetworkError(error) => format!("Network error reaching Twilio: {}", &error),
             ErrorKind::ParsingError(error) => format!("Unable to parse response: {}", &error),
+            ErrorKind::ConnectionError(error) => format!("Real-time connection error: {}", error),
+            ErrorKind::IoError(error) => format!("Unable to access local file: {}", error),
+            ErrorKind::TimeoutError(error) => format!("Timed out: {}", error),
+            ErrorKind::BuildError(error) => format!("Build failed: {}", error),
             ErrorKind::TwilioError(error) => {
                 format!("Error: {}", &error)
             }
@@ -182,10 +211,37 @@ impl Client {
     pub fn new(config: &TwilioConfig) -> Self {
         Self {
             config: config.clone(),
+            account_sid: config.account_sid.clone(),
             client: reqwest::Client::new(),
         }
     }
 
+    /// Create a client that acts on a subaccount using this client's credentials.
+    ///
+    /// Requests authenticate with the subaccount SID and the parent auth token, which Twilio
+    /// accepts for any subaccount of the parent. This scopes 2010 API paths and every other
+    /// product, e.g. Conversations and Sync, to the subaccount.
+    pub fn for_subaccount(&self, account_sid: &str) -> Self {
+        Self {
+            config: self.config.clone(),
+            account_sid: account_sid.to_string(),
+            client: self.client.clone(),
+        }
+    }
+
+    /// SID of the account requests act on.
+    pub fn account_sid(&self) -> &str {
+        &self.account_sid
+    }
+
+    /// URL of a 2010 API resource under the account the client acts on, e.g. `Keys.json`.
+    pub(crate) fn account_url(&self, resource: &str) -> String {
+        format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{}/{}",
+            self.account_sid, resource
+        )
+    }
+
     /// Dispatches a request to Twilio and handles parsing the response.
     ///
     /// The function takes two generics `T` and `U`. `T` is the expected response
@@ -262,6 +318,48 @@ impl Client {
         }
     }
 
+    /// Dispatches a multipart/form-data POST to Twilio and handles parsing the response. This is
+    /// used for uploading file content, such as Serverless Function Versions.
+    ///
+    /// Result follows the same behaviour as `send_request`.
+    async fn send_multipart_request<T>(
+        &self,
+        url: &str,
+        form: reqwest::multipart::Form,
+    ) -> Result<T, TwilioError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let response = self
+            .client
+            .post(url)
+            .basic_auth(&self.account_sid, Some(&self.config.auth_token))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|error| TwilioError {
+                kind: ErrorKind::NetworkError(error),
+            })?;
+
+        match response.status().is_success() {
+            true => response.json::<T>().await.map_err(|error| TwilioError {
+                kind: ErrorKind::ParsingError(error),
+            }),
+            false => {
+                let parsed_twilio_error = response.json::<TwilioApiError>();
+
+                match parsed_twilio_error.await {
+                    Ok(twilio_error) => Err(TwilioError {
+                        kind: ErrorKind::TwilioError(twilio_error),
+                    }),
+                    Err(error) => Err(TwilioError {
+                        kind: ErrorKind::ParsingError(error),
+                    }),
+                }
+            }
+        }
+    }
+
     // @INTERNAL
     // Helper function for `send_request`. Not designed to be used independently.
     async fn send_http_request<T>(
@@ -278,7 +376,7 @@ impl Client {
             Method::GET => {
                 self.client
                     .request(method, url)
-                    .basic_auth(&self.config.account_sid, Some(&self.config.auth_token))
+                    .basic_auth(&self.account_sid, Some(&self.config.auth_token))
                     .headers(headers.unwrap_or_default())
                     .query(&params)
                     .send()
@@ -287,7 +385,7 @@ impl Client {
             _ => {
                 self.client
                     .request(method, url)
-                    .basic_auth(&self.config.account_sid, Some(&self.config.auth_token))
+                    .basic_auth(&self.account_sid, Some(&self.config.auth_token))
                     .headers(headers.unwrap_or_default())
                     .form(&params)
                     .send()
@@ -300,21 +398,21 @@ impl Client {
     }
 
     /// Account related functions.
-    pub fn accounts(&self) -> Accounts {
+    pub fn accounts(&self) -> Accounts<'_> {
         Accounts { client: self }
     }
 
     /// Conversation related functions.
-    pub fn conversations(&self) -> Conversations {
+    pub fn conversations(&self) -> Conversations<'_> {
         Conversations { client: self }
     }
 
     /// Sync related functions.
-    pub fn sync(&self) -> Sync {
+    pub fn sync(&self) -> Sync<'_> {
         Sync { client: self }
     }
 
-    pub fn serverless(&self) -> Serverless {
+    pub fn serverless(&self) -> Serverless<'_> {
         Serverless { client: self }
     }
 }
@@ -356,4 +454,26 @@ mod tests {
         assert_eq!(account_sid, config.account_sid);
         assert_eq!(auth_token, config.auth_token);
     }
+
+    #[test]
+    fn subaccount_client_keeps_parent_credentials() {
+        let config = TwilioConfig::build(
+            String::from("AC11111111111111111111111111111111"),
+            String::from("11111111111111111111111111111111"),
+        );
+        let parent = Client::new(&config);
+        let subaccount = parent.for_subaccount("AC22222222222222222222222222222222");
+
+        assert_eq!(parent.account_sid(), "AC11111111111111111111111111111111");
+        assert_eq!(
+            subaccount.account_sid(),
+            "AC22222222222222222222222222222222"
+        );
+        assert_eq!(subaccount.config.account_sid, config.account_sid);
+        assert_eq!(subaccount.config.auth_token, config.auth_token);
+        assert_eq!(
+            subaccount.account_url("Balance.json"),
+            "https://api.twilio.com/2010-04-01/Accounts/AC22222222222222222222222222222222/Balance.json"
+        );
+    }
 }
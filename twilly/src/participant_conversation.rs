@@ -27,7 +27,7 @@ pub struct ParticipantConversationPage {
 }
 
 /// Participant conversation details.
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticipantConversation {
     pub account_sid: String,
     pub chat_service_sid: String,
@@ -47,7 +47,7 @@ pub struct ParticipantConversation {
     pub links: Links,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticipantMessagingBinding {
     pub address: String,
     pub proxy_address: String,
@@ -60,7 +60,7 @@ pub struct ParticipantMessagingBinding {
 
 /// Resources _linked_ to a participants conversation. These can be used to retrieve
 /// sub resources directly.
-#[derive(Clone, Default, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Links {
     pub participant: String,
     pub conversation: String,
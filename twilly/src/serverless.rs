@@ -3,12 +3,23 @@
 Contains Twilio Serverless related functionality.
 
 */
+pub mod assets;
+pub mod builds;
 pub mod environments;
+pub mod functions;
+pub mod project;
 pub mod services;
+pub mod upload;
 
-use crate::Client;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
 
-use self::services::{Service, Services};
+use crate::{Client, TwilioError};
+
+use self::{
+    project::{DeployEvent, DeployOptions, DeployOutcome, Project},
+    services::{Service, Services},
+};
 
 /// Holds Serverless related functions accessible
 /// on the client.
@@ -16,11 +27,27 @@ pub struct Serverless<'a> {
     pub client: &'a Client,
 }
 
+/// Who can access a Function or Asset Version once deployed.
+#[derive(
+    Clone, Copy, Debug, Default, Display, EnumIter, EnumString, PartialEq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Visibility {
+    /// Accessible by anyone with the URL.
+    Public,
+    /// Requires a valid Twilio signature on the request.
+    #[default]
+    Protected,
+    /// Only accessible from other Functions within the Service.
+    Private,
+}
+
 impl<'a> Serverless<'a> {
     /// Actions relating to a known Function Service.
     ///
     /// Takes in the SID of the Service to perform actions against.
-    pub fn service<'b: 'a>(&'a self, sid: &'b str) -> Service {
+    pub fn service<'b: 'a>(&'a self, sid: &'b str) -> Service<'a, 'b> {
         Service {
             client: self.client,
             sid,
@@ -28,9 +55,25 @@ impl<'a> Serverless<'a> {
     }
 
     /// General Function Service actions.
-    pub fn services(&'a self) -> Services {
+    pub fn services(&'a self) -> Services<'a> {
         Services {
             client: self.client,
         }
     }
+
+    /// Deploys a local Serverless project in one go. See the `project` module for the
+    /// expected layout and what is done.
+    ///
+    /// `on_event` is called as each step progresses.
+    pub async fn deploy<F>(
+        &self,
+        project: &Project,
+        options: DeployOptions,
+        on_event: F,
+    ) -> Result<DeployOutcome, TwilioError>
+    where
+        F: FnMut(DeployEvent),
+    {
+        project::deploy(self.client, project, options, on_event).await
+    }
 }
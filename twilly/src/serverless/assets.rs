@@ -0,0 +1,362 @@
+/*!
+
+Contains Twilio Serverless Asset related functionality.
+
+Like Functions, an Asset is a named container with its content held in Asset Versions.
+A whole local directory can be uploaded with `Assets::upload_directory`, which skips
+files that have not changed since the last upload.
+
+*/
+
+pub mod versions;
+
+use std::{fmt, path::Path};
+
+use crate::{Client, PageMeta, TwilioError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use versions::{AssetVersion, CreateParams, Version, Versions};
+
+use super::{
+    upload::{read_directory, LocalFile, Manifest, ManifestEntry},
+    Visibility,
+};
+
+/// Represents a page of Serverless Assets from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct AssetPage {
+    assets: Vec<ServerlessAsset>,
+    meta: PageMeta,
+}
+
+/// A Serverless Asset resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessAsset {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub friendly_name: String,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+    pub url: String,
+    pub links: Links,
+}
+
+/// Resources _linked_ to an Asset.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Links {
+    pub asset_versions: String,
+}
+
+/// Parameters for creating or updating an Asset.
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct CreateOrUpdateParams {
+    pub friendly_name: String,
+}
+
+/// The outcome of `Assets::upload_directory`.
+#[derive(Debug)]
+pub struct DirectoryUpload {
+    /// Manifest describing every file in the directory, to be passed to the next upload.
+    pub manifest: Manifest,
+    /// Versions created for new or changed files.
+    pub uploaded: Vec<AssetVersion>,
+    /// Paths of files that matched the previous manifest and were not uploaded.
+    pub unchanged: Vec<String>,
+}
+
+/// A failed `Assets::upload_directory`, with what was done before the failure.
+#[derive(Debug)]
+pub struct DirectoryUploadError {
+    pub error: TwilioError,
+    /// The files handled before the failure. Its manifest also keeps the `previous` entries
+    /// of files not reached, so saving it lets the next upload skip everything that's done.
+    pub partial: DirectoryUpload,
+}
+
+impl fmt::Display for DirectoryUploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl From<DirectoryUploadError> for TwilioError {
+    fn from(failed: DirectoryUploadError) -> TwilioError {
+        failed.error
+    }
+}
+
+pub struct Assets<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+}
+
+impl<'a, 'b> Assets<'a, 'b> {
+    /// [Creates an Asset](https://www.twilio.com/docs/serverless/api/resource/asset#create-an-asset-resource)
+    ///
+    /// Creates an Asset in the Serverless Service provided to the `service()` argument.
+    pub async fn create(
+        &self,
+        params: CreateOrUpdateParams,
+    ) -> Result<ServerlessAsset, TwilioError> {
+        self.client
+            .send_request::<ServerlessAsset, CreateOrUpdateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets",
+                    self.service_sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Assets](https://www.twilio.com/docs/serverless/api/resource/asset#read-multiple-asset-resources)
+    ///
+    /// Lists Assets of the Serverless Service provided to the `service()` argument.
+    ///
+    /// Assets will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ServerlessAsset>, TwilioError> {
+        let mut assets_page = self
+            .client
+            .send_request::<AssetPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets?PageSize=50",
+                    self.service_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ServerlessAsset> = assets_page.assets;
+
+        while (assets_page.meta.next_page_url).is_some() {
+            assets_page = self
+                .client
+                .send_request::<AssetPage, ()>(
+                    Method::GET,
+                    &assets_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut assets_page.assets);
+        }
+
+        Ok(results)
+    }
+
+    /// Uploads every file below `directory` as an Asset, using the file's path relative to
+    /// the directory as both the Asset's friendly name and its URL path. Hidden files are
+    /// skipped.
+    ///
+    /// Files whose content hash matches the entry in `previous` are not uploaded again and
+    /// keep their existing Version. Existing Assets are reused where the friendly name
+    /// matches so repeated uploads do not create duplicates.
+    ///
+    /// Uploading stops at the first failure. The error holds the Versions created up to
+    /// then, see `DirectoryUploadError`.
+    pub async fn upload_directory(
+        &self,
+        directory: &Path,
+        visibility: Visibility,
+        previous: &Manifest,
+    ) -> Result<DirectoryUpload, DirectoryUploadError> {
+        // Nothing is uploaded yet, so the previous manifest still describes what's deployed.
+        let unstarted = |error| DirectoryUploadError {
+            error,
+            partial: DirectoryUpload {
+                manifest: previous.clone(),
+                uploaded: Vec::new(),
+                unchanged: Vec::new(),
+            },
+        };
+        let files = read_directory(directory).map_err(unstarted)?;
+        let existing = self.list().await.map_err(unstarted)?;
+
+        let mut result = DirectoryUpload {
+            manifest: Manifest::new(),
+            uploaded: Vec::new(),
+            unchanged: Vec::new(),
+        };
+
+        let mut files = files.into_iter();
+        while let Some(file) = files.next() {
+            let path = file.path.clone();
+            if let Err(error) = self
+                .upload_file(file, visibility, previous, &existing, &mut result)
+                .await
+            {
+                // Files not reached still have the Versions described in `previous`.
+                for path in std::iter::once(path).chain(files.map(|file| file.path)) {
+                    if let Some(entry) = previous.get(&path) {
+                        result.manifest.insert(path, entry.clone());
+                    }
+                }
+                return Err(DirectoryUploadError {
+                    error,
+                    partial: result,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    // Uploads a single file of `upload_directory`, adding it to the result.
+    async fn upload_file(
+        &self,
+        file: LocalFile,
+        visibility: Visibility,
+        previous: &Manifest,
+        existing: &[ServerlessAsset],
+        result: &mut DirectoryUpload,
+    ) -> Result<(), TwilioError> {
+        let previous_entry = previous
+            .get(&file.path)
+            .filter(|entry| existing.iter().any(|asset| asset.sid == entry.resource_sid));
+
+        if let Some(entry) = previous_entry.filter(|entry| entry.hash == file.hash) {
+            result.manifest.insert(file.path.clone(), entry.clone());
+            result.unchanged.push(file.path);
+            return Ok(());
+        }
+
+        let asset_sid = match previous_entry
+            .map(|entry| entry.resource_sid.clone())
+            .or_else(|| {
+                existing
+                    .iter()
+                    .find(|asset| asset.friendly_name == file.path)
+                    .map(|asset| asset.sid.clone())
+            }) {
+            Some(sid) => sid,
+            None => {
+                self.create(CreateOrUpdateParams {
+                    friendly_name: file.path.clone(),
+                })
+                .await?
+                .sid
+            }
+        };
+
+        let version = Asset {
+            client: self.client,
+            service_sid: self.service_sid,
+            sid: &asset_sid,
+        }
+        .versions()
+        .create(CreateParams {
+            path: file.path.clone(),
+            visibility,
+            content: file.content,
+            content_type: file.content_type,
+        })
+        .await?;
+
+        result.manifest.insert(
+            file.path,
+            ManifestEntry {
+                hash: file.hash,
+                resource_sid: asset_sid,
+                version_sid: version.sid.clone(),
+            },
+        );
+        result.uploaded.push(version);
+        Ok(())
+    }
+}
+
+pub struct Asset<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    /// SID of the Asset resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Asset<'a, 'b> {
+    /// [Gets an Asset](https://www.twilio.com/docs/serverless/api/resource/asset#fetch-an-asset-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and fetches the Asset
+    /// provided to the `asset()` argument.
+    pub async fn get(&self) -> Result<ServerlessAsset, TwilioError> {
+        self.client
+            .send_request::<ServerlessAsset, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Updates an Asset](https://www.twilio.com/docs/serverless/api/resource/asset#update-an-asset-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and updates the Asset
+    /// provided to the `asset()` argument.
+    pub async fn update(
+        &self,
+        params: CreateOrUpdateParams,
+    ) -> Result<ServerlessAsset, TwilioError> {
+        self.client
+            .send_request::<ServerlessAsset, CreateOrUpdateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets/{}",
+                    self.service_sid, self.sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Deletes an Asset](https://www.twilio.com/docs/serverless/api/resource/asset#delete-an-asset-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and deletes the Asset
+    /// provided to the `asset()` argument. Assets that are part of an active Build cannot be
+    /// deleted.
+    pub async fn delete(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// Actions relating to a known Asset Version.
+    ///
+    /// Takes in the SID of the Asset Version to perform actions against.
+    pub fn version(&'a self, sid: &'b str) -> Version<'a, 'b> {
+        Version {
+            client: self.client,
+            service_sid: self.service_sid,
+            asset_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Asset Version actions.
+    pub fn versions(&'a self) -> Versions<'a, 'b> {
+        Versions {
+            client: self.client,
+            service_sid: self.service_sid,
+            asset_sid: self.sid,
+        }
+    }
+}
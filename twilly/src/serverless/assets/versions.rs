@@ -0,0 +1,146 @@
+/*!
+
+Contains Twilio Serverless Asset Version related functionality.
+
+Asset Versions are immutable uploads of an Asset's content, created through the separate
+`serverless-upload.twilio.com` domain as a multipart upload.
+
+*/
+
+use crate::{
+    serverless::{upload::version_form, Visibility},
+    Client, PageMeta, TwilioError,
+};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Represents a page of Asset Versions from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct AssetVersionPage {
+    asset_versions: Vec<AssetVersion>,
+    meta: PageMeta,
+}
+
+/// A Serverless Asset Version resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AssetVersion {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub asset_sid: String,
+    /// URL path the Asset is served from once deployed, e.g. `/audio/hold.mp3`.
+    pub path: String,
+    pub visibility: Visibility,
+    pub date_created: String,
+    pub url: String,
+}
+
+/// Parameters for uploading an Asset Version.
+#[derive(Clone, Debug)]
+pub struct CreateParams {
+    /// URL path the Asset is served from, must begin with `/`.
+    pub path: String,
+    pub visibility: Visibility,
+    /// The Asset's content.
+    pub content: Vec<u8>,
+    /// MIME type of the content, e.g. `audio/mpeg`.
+    pub content_type: String,
+}
+
+pub struct Versions<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub asset_sid: &'b str,
+}
+
+impl<'a, 'b> Versions<'a, 'b> {
+    /// [Creates an Asset Version](https://www.twilio.com/docs/serverless/api/resource/asset-version#create-an-asset-version-resource)
+    ///
+    /// Uploads the provided content as a new Version of the Asset provided to the `asset()`
+    /// argument.
+    pub async fn create(&self, params: CreateParams) -> Result<AssetVersion, TwilioError> {
+        let form = version_form(
+            params.path,
+            params.visibility,
+            params.content,
+            &params.content_type,
+        )?;
+
+        self.client
+            .send_multipart_request::<AssetVersion>(
+                &format!(
+                    "https://serverless-upload.twilio.com/v1/Services/{}/Assets/{}/Versions",
+                    self.service_sid, self.asset_sid
+                ),
+                form,
+            )
+            .await
+    }
+
+    /// [Lists Asset Versions](https://www.twilio.com/docs/serverless/api/resource/asset-version#read-multiple-asset-version-resources)
+    ///
+    /// Lists Versions of the Asset provided to the `asset()` argument, newest first.
+    ///
+    /// Versions will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<AssetVersion>, TwilioError> {
+        let mut versions_page = self
+            .client
+            .send_request::<AssetVersionPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets/{}/Versions?PageSize=50",
+                    self.service_sid, self.asset_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<AssetVersion> = versions_page.asset_versions;
+
+        while (versions_page.meta.next_page_url).is_some() {
+            versions_page = self
+                .client
+                .send_request::<AssetVersionPage, ()>(
+                    Method::GET,
+                    &versions_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut versions_page.asset_versions);
+        }
+
+        Ok(results)
+    }
+}
+
+pub struct Version<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub asset_sid: &'b str,
+    /// SID of the Asset Version resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Version<'a, 'b> {
+    /// [Gets an Asset Version](https://www.twilio.com/docs/serverless/api/resource/asset-version#fetch-an-asset-version-resource)
+    ///
+    /// Targets the Asset provided to the `asset()` argument and fetches the Version provided to
+    /// the `version()` argument.
+    pub async fn get(&self) -> Result<AssetVersion, TwilioError> {
+        self.client
+            .send_request::<AssetVersion, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Assets/{}/Versions/{}",
+                    self.service_sid, self.asset_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+}
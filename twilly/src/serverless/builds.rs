@@ -0,0 +1,512 @@
+/*!
+
+Contains Twilio Serverless Build related functionality.
+
+A Build bundles a set of Function and Asset Versions together with their npm
+dependencies. Builds are created asynchronously so `Build::wait` can be used to poll
+until Twilio has finished with it, after which it can be deployed to an Environment.
+
+*/
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{Client, ErrorKind, PageMeta, TwilioError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use super::Visibility;
+
+/// Represents a page of Serverless Builds from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct BuildPage {
+    builds: Vec<ServerlessBuild>,
+    meta: PageMeta,
+}
+
+/// A Serverless Build resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessBuild {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub status: BuildStatus,
+    #[serde(default)]
+    pub function_versions: Option<Vec<BuildVersion>>,
+    #[serde(default)]
+    pub asset_versions: Option<Vec<BuildVersion>>,
+    #[serde(default)]
+    pub dependencies: Option<Vec<Dependency>>,
+    /// Node.js runtime the Build targets, e.g. `node18`.
+    pub runtime: Option<String>,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+    pub url: String,
+}
+
+/// Possible statuses of a Build.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum BuildStatus {
+    Building,
+    Completed,
+    Failed,
+}
+
+/// A Function or Asset Version included in a Build.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuildVersion {
+    pub sid: String,
+    /// SID of the Function or Asset the Version belongs to.
+    #[serde(alias = "function_sid", alias = "asset_sid")]
+    pub resource_sid: String,
+    pub path: String,
+    pub visibility: Visibility,
+    pub date_created: String,
+}
+
+/// An npm dependency installed into a Build.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub version: String,
+}
+
+impl FromStr for Dependency {
+    type Err = TwilioError;
+
+    /// Parses a dependency written as `name@version`, e.g. `@twilio/runtime-handler@1.3.0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().rsplit_once('@') {
+            Some((name, version)) if !name.is_empty() && !version.is_empty() => Ok(Dependency {
+                name: name.to_string(),
+                version: version.to_string(),
+            }),
+            _ => Err(TwilioError {
+                kind: ErrorKind::ValidationError(format!(
+                    "Dependency '{}' must be in the form name@version",
+                    s.trim()
+                )),
+            }),
+        }
+    }
+}
+
+/// A difference in the Versions of two Builds, matched up by path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VersionChange {
+    Added {
+        path: String,
+        sid: String,
+    },
+    Removed {
+        path: String,
+        sid: String,
+    },
+    Changed {
+        path: String,
+        from_sid: String,
+        to_sid: String,
+    },
+}
+
+impl fmt::Display for VersionChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionChange::Added { path, sid } => write!(f, "+ {} ({})", path, sid),
+            VersionChange::Removed { path, sid } => write!(f, "- {} ({})", path, sid),
+            VersionChange::Changed {
+                path,
+                from_sid,
+                to_sid,
+            } => write!(f, "~ {} ({} -> {})", path, from_sid, to_sid),
+        }
+    }
+}
+
+/// The differences between two Builds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BuildDiff {
+    pub functions: Vec<VersionChange>,
+    pub assets: Vec<VersionChange>,
+    /// Whether the npm dependencies differ.
+    pub dependencies_changed: bool,
+}
+
+impl BuildDiff {
+    /// Compares the Function and Asset Versions of `from` and `to`.
+    pub fn between(from: &ServerlessBuild, to: &ServerlessBuild) -> BuildDiff {
+        BuildDiff {
+            functions: diff_versions(
+                from.function_versions.as_deref().unwrap_or_default(),
+                to.function_versions.as_deref().unwrap_or_default(),
+            ),
+            assets: diff_versions(
+                from.asset_versions.as_deref().unwrap_or_default(),
+                to.asset_versions.as_deref().unwrap_or_default(),
+            ),
+            dependencies_changed: from.dependencies.as_deref().unwrap_or_default()
+                != to.dependencies.as_deref().unwrap_or_default(),
+        }
+    }
+
+    /// Whether the Builds contain exactly the same Versions and dependencies.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.assets.is_empty() && !self.dependencies_changed
+    }
+}
+
+/// The status of a Build as returned by the status endpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessBuildStatus {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub status: BuildStatus,
+    pub url: String,
+}
+
+/// Parameters for creating a Build.
+#[derive(Clone, Debug, Default)]
+pub struct CreateParams {
+    /// SIDs of the Function Versions to include, begin with ZN...
+    pub function_versions: Vec<String>,
+    /// SIDs of the Asset Versions to include, begin with ZN...
+    pub asset_versions: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+    /// Node.js runtime to target. Twilio's default is used if not provided.
+    pub runtime: Option<String>,
+}
+
+impl CreateParams {
+    // Twilio expects each Version SID as a repeated form field and the
+    // dependencies as a JSON array, which a struct can't express.
+    fn form_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs: Vec<(&'static str, String)> = Vec::new();
+        pairs.extend(
+            self.function_versions
+                .iter()
+                .map(|sid| ("FunctionVersions", sid.clone())),
+        );
+        pairs.extend(
+            self.asset_versions
+                .iter()
+                .map(|sid| ("AssetVersions", sid.clone())),
+        );
+        if !self.dependencies.is_empty() {
+            pairs.push((
+                "Dependencies",
+                serde_json::to_string(&self.dependencies)
+                    .expect("Dependencies should serialize to JSON"),
+            ));
+        }
+        if let Some(runtime) = &self.runtime {
+            pairs.push(("Runtime", runtime.clone()));
+        }
+        pairs
+    }
+}
+
+pub struct Builds<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+}
+
+impl<'a, 'b> Builds<'a, 'b> {
+    /// [Creates a Build](https://www.twilio.com/docs/serverless/api/resource/build#create-a-build-resource)
+    ///
+    /// Starts a Build of the provided Versions and dependencies. The Build is returned while
+    /// still `building`; see `Build::wait` to wait for it to finish.
+    pub async fn create(&self, params: CreateParams) -> Result<ServerlessBuild, TwilioError> {
+        self.client
+            .send_request::<ServerlessBuild, [(&str, String)]>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Builds",
+                    self.service_sid
+                ),
+                Some(params.form_pairs().as_slice()),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Builds](https://www.twilio.com/docs/serverless/api/resource/build#read-multiple-build-resources)
+    ///
+    /// Lists Builds of the Serverless Service provided to the `service()` argument.
+    ///
+    /// Builds will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ServerlessBuild>, TwilioError> {
+        let mut builds_page = self
+            .client
+            .send_request::<BuildPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Builds?PageSize=50",
+                    self.service_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ServerlessBuild> = builds_page.builds;
+
+        while (builds_page.meta.next_page_url).is_some() {
+            builds_page = self
+                .client
+                .send_request::<BuildPage, ()>(
+                    Method::GET,
+                    &builds_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut builds_page.builds);
+        }
+
+        Ok(results)
+    }
+}
+
+pub struct Build<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    /// SID of the Build resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Build<'a, 'b> {
+    /// [Gets a Build](https://www.twilio.com/docs/serverless/api/resource/build#fetch-a-build-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and fetches the Build
+    /// provided to the `build()` argument.
+    pub async fn get(&self) -> Result<ServerlessBuild, TwilioError> {
+        self.client
+            .send_request::<ServerlessBuild, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Builds/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Gets a Build's status](https://www.twilio.com/docs/serverless/api/resource/build/build-status)
+    ///
+    /// Fetches only the status of the Build provided to the `build()` argument.
+    pub async fn status(&self) -> Result<ServerlessBuildStatus, TwilioError> {
+        self.client
+            .send_request::<ServerlessBuildStatus, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Builds/{}/Status",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Deletes a Build](https://www.twilio.com/docs/serverless/api/resource/build#delete-a-build-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and deletes the Build
+    /// provided to the `build()` argument. Builds that are currently deployed cannot be deleted.
+    pub async fn delete(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Builds/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// Polls the Build's status every `interval` until it is `completed` or `failed`,
+    /// calling `on_poll` with the status and time elapsed after each check.
+    ///
+    /// A failed Build is still returned as `Ok`, check the `status` of the result. An error
+    /// is returned if the Build is still `building` once `timeout` has passed.
+    pub async fn wait<F>(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+        mut on_poll: F,
+    ) -> Result<ServerlessBuild, TwilioError>
+    where
+        F: FnMut(BuildStatus, Duration),
+    {
+        let started = Instant::now();
+
+        loop {
+            let status = self.status().await?.status;
+            on_poll(status, started.elapsed());
+
+            if status != BuildStatus::Building {
+                return self.get().await;
+            }
+
+            if started.elapsed() + interval > timeout {
+                return Err(TwilioError {
+                    kind: ErrorKind::TimeoutError(format!(
+                        "Build {} was still building after {} seconds",
+                        self.sid,
+                        timeout.as_secs()
+                    )),
+                });
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+// Matches up Versions by path and lists those that were added, removed or replaced.
+fn diff_versions(from: &[BuildVersion], to: &[BuildVersion]) -> Vec<VersionChange> {
+    let mut changes = Vec::new();
+
+    for version in to {
+        match from.iter().find(|previous| previous.path == version.path) {
+            Some(previous) if previous.sid == version.sid => {}
+            Some(previous) => changes.push(VersionChange::Changed {
+                path: version.path.clone(),
+                from_sid: previous.sid.clone(),
+                to_sid: version.sid.clone(),
+            }),
+            None => changes.push(VersionChange::Added {
+                path: version.path.clone(),
+                sid: version.sid.clone(),
+            }),
+        }
+    }
+
+    changes.extend(
+        from.iter()
+            .filter(|previous| !to.iter().any(|version| version.path == previous.path))
+            .map(|previous| VersionChange::Removed {
+                path: previous.path.clone(),
+                sid: previous.sid.clone(),
+            }),
+    );
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_params_form_pairs() {
+        let params = CreateParams {
+            function_versions: vec![String::from("ZN1"), String::from("ZN2")],
+            asset_versions: vec![String::from("ZN3")],
+            dependencies: vec![Dependency {
+                name: String::from("axios"),
+                version: String::from("1.6.0"),
+            }],
+            runtime: None,
+        };
+
+        assert_eq!(
+            vec![
+                ("FunctionVersions", String::from("ZN1")),
+                ("FunctionVersions", String::from("ZN2")),
+                ("AssetVersions", String::from("ZN3")),
+                (
+                    "Dependencies",
+                    String::from(r#"[{"name":"axios","version":"1.6.0"}]"#)
+                ),
+            ],
+            params.form_pairs()
+        );
+    }
+
+    #[test]
+    fn parses_dependency() {
+        let dependency: Dependency = "@twilio/runtime-handler@1.3.0".parse().unwrap();
+        assert_eq!("@twilio/runtime-handler", dependency.name);
+        assert_eq!("1.3.0", dependency.version);
+        assert!("axios".parse::<Dependency>().is_err());
+        assert!("@1.0.0".parse::<Dependency>().is_err());
+    }
+
+    #[test]
+    fn parses_build_versions() {
+        let build: ServerlessBuild = serde_json::from_str(
+            r#"{
+                "sid": "ZB1", "account_sid": "AC1", "service_sid": "ZS1", "status": "completed",
+                "function_versions": [{
+                    "sid": "ZN1", "account_sid": "AC1", "service_sid": "ZS1", "function_sid": "ZH1",
+                    "path": "/hello", "visibility": "public", "date_created": "2024-01-01T00:00:00Z"
+                }],
+                "asset_versions": null, "dependencies": null, "runtime": "node18",
+                "date_created": "2024-01-01T00:00:00Z", "date_updated": null, "url": ""
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(BuildStatus::Completed, build.status);
+        let function_version = &build.function_versions.unwrap()[0];
+        assert_eq!("ZH1", function_version.resource_sid);
+        assert_eq!(Visibility::Public, function_version.visibility);
+    }
+
+    #[test]
+    fn diffs_build_versions() {
+        let version = |sid: &str, path: &str| BuildVersion {
+            sid: sid.to_string(),
+            resource_sid: String::from("ZH1"),
+            path: path.to_string(),
+            visibility: Visibility::Public,
+            date_created: String::new(),
+        };
+
+        let changes = diff_versions(
+            &[
+                version("ZN1", "/same"),
+                version("ZN2", "/changed"),
+                version("ZN3", "/gone"),
+            ],
+            &[
+                version("ZN1", "/same"),
+                version("ZN4", "/changed"),
+                version("ZN5", "/new"),
+            ],
+        );
+
+        assert_eq!(
+            vec![
+                VersionChange::Changed {
+                    path: String::from("/changed"),
+                    from_sid: String::from("ZN2"),
+                    to_sid: String::from("ZN4"),
+                },
+                VersionChange::Added {
+                    path: String::from("/new"),
+                    sid: String::from("ZN5"),
+                },
+                VersionChange::Removed {
+                    path: String::from("/gone"),
+                    sid: String::from("ZN3"),
+                },
+            ],
+            changes
+        );
+    }
+}
@@ -4,13 +4,17 @@ Contains Twilio Serverless Environment related functionality.
 
 */
 
+pub mod deployments;
 pub mod logs;
+pub mod variables;
 
-use crate::{Client, PageMeta, TwilioError};
+use crate::{Client, ErrorKind, PageMeta, TwilioError};
+use deployments::{Deployment, Deployments, ServerlessDeployment};
 use logs::{Log, Logs};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use variables::{Variable, Variables};
 
 /// Represents a page of Serverless Environments from the Twilio API.
 #[allow(dead_code)]
@@ -26,7 +30,8 @@ pub struct ServerlessEnvironment {
     pub sid: String,
     pub account_sid: String,
     pub service_sid: String,
-    pub build_sid: String,
+    /// SID of the Build currently deployed, if any.
+    pub build_sid: Option<String>,
     pub unique_name: String,
     /// URL-friendly name which forms part of the domain (unless production).
     pub domain_suffix: Option<String>,
@@ -167,10 +172,88 @@ impl<'a, 'b> Environment<'a, 'b> {
             .await
     }
 
+    /// Deploys an existing Build to the Environment. Any Build of the Service can be used, such
+    /// as one from the Environment's deployment history or one running elsewhere.
+    pub async fn redeploy(&self, build_sid: &str) -> Result<ServerlessDeployment, TwilioError> {
+        self.deployments()
+            .create(deployments::CreateParams {
+                build_sid: Some(build_sid.to_string()),
+            })
+            .await
+    }
+
+    /// Promotes the Build currently running in this Environment to the target Environment of
+    /// the same Service.
+    pub async fn promote_to(
+        &self,
+        target_environment_sid: &str,
+    ) -> Result<ServerlessDeployment, TwilioError> {
+        let Some(build_sid) = self.get().await?.build_sid else {
+            return Err(TwilioError {
+                kind: ErrorKind::ValidationError(format!(
+                    "Environment {} has no Build deployed to promote",
+                    self.sid
+                )),
+            });
+        };
+
+        Environment {
+            client: self.client,
+            service_sid: self.service_sid,
+            sid: target_environment_sid,
+        }
+        .redeploy(&build_sid)
+        .await
+    }
+
+    /// Rolls the Environment back to the most recent previously deployed Build that differs from
+    /// the one currently running.
+    pub async fn rollback(&self) -> Result<ServerlessDeployment, TwilioError> {
+        let current = self.get().await?.build_sid;
+        let mut history = self.deployments().list().await?;
+        history.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+
+        let Some(build_sid) = history
+            .into_iter()
+            .filter_map(|deployment| deployment.build_sid)
+            .find(|build_sid| Some(build_sid) != current.as_ref())
+        else {
+            return Err(TwilioError {
+                kind: ErrorKind::ValidationError(format!(
+                    "Environment {} has no previous Build to roll back to",
+                    self.sid
+                )),
+            });
+        };
+
+        self.redeploy(&build_sid).await
+    }
+
+    /// Functions relating to a known Environment Deployment.
+    ///
+    /// Takes in the SID of the Deployment to perform actions against.
+    pub fn deployment(&'a self, sid: &'b str) -> Deployment<'a, 'b> {
+        Deployment {
+            client: self.client,
+            service_sid: self.service_sid,
+            environment_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Deployment functions.
+    pub fn deployments(&'a self) -> Deployments<'a, 'b> {
+        Deployments {
+            client: self.client,
+            service_sid: self.service_sid,
+            environment_sid: self.sid,
+        }
+    }
+
     /// Functions relating to a known Environment Log.
     ///
     /// Takes in the key of the Sync List Item to perform actions against.
-    pub fn log(&'a self, sid: &'b str) -> Log {
+    pub fn log(&'a self, sid: &'b str) -> Log<'a, 'b> {
         Log {
             client: self.client,
             service_sid: self.service_sid,
@@ -180,11 +263,32 @@ impl<'a, 'b> Environment<'a, 'b> {
     }
 
     /// General Log functions.
-    pub fn logs(&'a self) -> Logs {
+    pub fn logs(&'a self) -> Logs<'a, 'b> {
         Logs {
             client: self.client,
             service_sid: self.service_sid,
             environment_sid: self.sid,
         }
     }
+
+    /// Functions relating to a known Environment Variable.
+    ///
+    /// Takes in the SID of the Variable to perform actions against.
+    pub fn variable(&'a self, sid: &'b str) -> Variable<'a, 'b> {
+        Variable {
+            client: self.client,
+            service_sid: self.service_sid,
+            environment_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Variable functions.
+    pub fn variables(&'a self) -> Variables<'a, 'b> {
+        Variables {
+            client: self.client,
+            service_sid: self.service_sid,
+            environment_sid: self.sid,
+        }
+    }
 }
@@ -0,0 +1,134 @@
+/*!
+
+Contains Twilio Serverless Deployment related functionality.
+
+*/
+
+use crate::{Client, PageMeta, TwilioError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Represents a page of Serverless Deployments from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct DeploymentPage {
+    deployments: Vec<ServerlessDeployment>,
+    meta: PageMeta,
+}
+
+/// A Serverless Deployment resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessDeployment {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub environment_sid: String,
+    /// SID of the Build that was deployed.
+    pub build_sid: Option<String>,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+    pub url: String,
+}
+
+/// Parameters for creating a Deployment.
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct CreateParams {
+    /// SID of the Build to deploy. Begins with ZB...
+    pub build_sid: Option<String>,
+}
+
+pub struct Deployments<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub environment_sid: &'b str,
+}
+
+impl<'a, 'b> Deployments<'a, 'b> {
+    /// [Creates a Deployment](https://www.twilio.com/docs/serverless/api/resource/deployment#create-a-deployment-resource)
+    ///
+    /// Deploys the Build to the Environment provided to `environment()`, replacing whatever
+    /// was previously deployed there.
+    pub async fn create(&self, params: CreateParams) -> Result<ServerlessDeployment, TwilioError> {
+        self.client
+            .send_request::<ServerlessDeployment, CreateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Deployments",
+                    self.service_sid, self.environment_sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Deployments](https://www.twilio.com/docs/serverless/api/resource/deployment#read-multiple-deployment-resources)
+    ///
+    /// Lists the deployment history of the Environment provided to `environment()`, newest
+    /// first.
+    ///
+    /// Deployments will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ServerlessDeployment>, TwilioError> {
+        let mut deployments_page = self
+            .client
+            .send_request::<DeploymentPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Deployments?PageSize=50",
+                    self.service_sid, self.environment_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ServerlessDeployment> = deployments_page.deployments;
+
+        while (deployments_page.meta.next_page_url).is_some() {
+            deployments_page = self
+                .client
+                .send_request::<DeploymentPage, ()>(
+                    Method::GET,
+                    &deployments_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut deployments_page.deployments);
+        }
+
+        Ok(results)
+    }
+}
+
+pub struct Deployment<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub environment_sid: &'b str,
+    /// SID of the Deployment resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Deployment<'a, 'b> {
+    /// [Gets a Deployment](https://www.twilio.com/docs/serverless/api/resource/deployment#fetch-a-deployment-resource)
+    ///
+    /// Targets the Environment provided to `environment()` and fetches the Deployment provided to
+    /// the `deployment()` argument.
+    pub async fn get(&self) -> Result<ServerlessDeployment, TwilioError> {
+        self.client
+            .send_request::<ServerlessDeployment, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Deployments/{}",
+                    self.service_sid, self.environment_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+}
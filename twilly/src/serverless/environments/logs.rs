@@ -2,13 +2,33 @@
 
 Contains Twilio Serverless Environment Logs related functionality.
 
+Besides listing a fixed window, `Logs::query` narrows the results with a `LogFilter`
+and `Logs::tail` follows an Environment's logs as they are written. Either can be
+passed to `group_by_request` to read each invocation's logs together. For large
+windows `Logs::stream` fetches pages as they are consumed, which pairs with the
+writers in `export`.
+
 */
 
+pub mod export;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream};
+use regex::Regex;
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 
+/// How far behind the newest log seen each tail poll looks, to catch logs that Twilio
+/// makes available late. Logs already seen in this window are de-duplicated by SID.
+pub const TAIL_OVERLAP: Duration = Duration::from_secs(30);
+
 /// Represents a page of Serverless Environments from the Twilio API.
 #[allow(dead_code)]
 #[derive(Deserialize)]
@@ -18,7 +38,7 @@ pub struct LogsPage {
 }
 
 /// A Serverless Environment Log resource.
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct ServerlessLog {
     pub sid: String,
     pub account_sid: String,
@@ -57,6 +77,48 @@ pub enum Level {
     Error,
 }
 
+/// Narrows down the logs returned by `Logs::query` and `Logs::tail`.
+///
+/// Function SIDs are passed to Twilio so fewer logs are fetched, the rest of the filter is
+/// applied once the logs are retrieved. Empty or `None` fields don't filter anything.
+#[derive(Clone, Debug, Default)]
+pub struct LogFilter {
+    /// Only logs of these levels are kept.
+    pub levels: Vec<Level>,
+    /// Only logs from these Functions are kept. See `Functions::sids_by_name` to look these
+    /// up from friendly names.
+    pub function_sids: Vec<String>,
+    /// Only logs from this request (begins with RQ...) are kept.
+    pub request_sid: Option<String>,
+    /// Only logs whose message matches are kept.
+    pub message: Option<Regex>,
+}
+
+impl LogFilter {
+    /// Whether the log passes the filter.
+    pub fn matches(&self, log: &ServerlessLog) -> bool {
+        (self.levels.is_empty() || self.levels.contains(&log.level))
+            && (self.function_sids.is_empty() || self.function_sids.contains(&log.function_sid))
+            && self
+                .request_sid
+                .as_ref()
+                .map_or(true, |request_sid| &log.request_sid == request_sid)
+            && self
+                .message
+                .as_ref()
+                .map_or(true, |message| message.is_match(&log.message))
+    }
+}
+
+/// The logs written during a single request, see `group_by_request`.
+#[derive(Debug)]
+pub struct RequestLogs {
+    pub request_sid: String,
+    pub function_sid: String,
+    /// Logs of the request, oldest first.
+    pub logs: Vec<ServerlessLog>,
+}
+
 /// Arguments for listing Serverless Logs
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
@@ -124,6 +186,247 @@ impl<'a, 'b> Logs<'a, 'b> {
 
         Ok(results)
     }
+
+    /// Lists Logs of the Environment between `start_date` and `end_date` (defaulting as with
+    /// `list`) that pass `filter`.
+    ///
+    /// Twilio only filters by a single Function so one request is made per Function in the
+    /// filter, or a single request for all Functions if none are given. The logs of several
+    /// Functions are merged newest first, the order Twilio lists them in.
+    pub async fn query(
+        &self,
+        filter: &LogFilter,
+        start_date: Option<chrono::DateTime<chrono::Utc>>,
+        end_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<ServerlessLog>, TwilioError> {
+        let mut logs = match filter.function_sids.is_empty() {
+            true => self.list(None, start_date, end_date).await?,
+            false => {
+                let mut logs = Vec::new();
+                for function_sid in &filter.function_sids {
+                    logs.append(
+                        &mut self
+                            .list(Some(function_sid.clone()), start_date, end_date)
+                            .await?,
+                    );
+                }
+                if filter.function_sids.len() > 1 {
+                    sort_newest_first(&mut logs);
+                }
+                logs
+            }
+        };
+
+        logs.retain(|log| filter.matches(log));
+        Ok(logs)
+    }
+
+    /// Lists Logs as `query` does but only fetches each page once the previous one has been
+    /// consumed, so any number of logs can be processed without holding them all in memory.
+    ///
+    /// Unlike `query`, the logs of several Functions are not merged: every log of the first
+    /// Function in the filter is yielded before any of the next.
+    ///
+    /// The stream ends after the first error.
+    pub fn stream(
+        &self,
+        filter: LogFilter,
+        start_date: Option<chrono::DateTime<chrono::Utc>>,
+        end_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> BoxStream<'a, Result<ServerlessLog, TwilioError>> {
+        let first_pages: VecDeque<Option<String>> = match filter.function_sids.is_empty() {
+            true => VecDeque::from([None]),
+            false => filter.function_sids.iter().cloned().map(Some).collect(),
+        };
+        let state = StreamState {
+            client: self.client,
+            base_url: format!(
+                "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Logs?PageSize=500",
+                self.service_sid, self.environment_sid
+            ),
+            start_date: start_date.map(|sd| sd.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            end_date: end_date.map(|ed| ed.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            filter,
+            first_pages,
+            next_page_url: None,
+            buffer: VecDeque::new(),
+        };
+
+        Box::pin(stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(log) = state.buffer.pop_front() {
+                    match state.filter.matches(&log) {
+                        true => return Some((Ok(log), state)),
+                        false => continue,
+                    }
+                }
+
+                let page = if let Some(next_page_url) = state.next_page_url.take() {
+                    state
+                        .client
+                        .send_request::<LogsPage, ()>(Method::GET, &next_page_url, None, None)
+                        .await
+                } else if let Some(function_sid) = state.first_pages.pop_front() {
+                    let params = ListParams {
+                        function_sid,
+                        start_date: state.start_date.clone(),
+                        end_date: state.end_date.clone(),
+                    };
+                    state
+                        .client
+                        .send_request::<LogsPage, ListParams>(
+                            Method::GET,
+                            &state.base_url,
+                            Some(&params),
+                            None,
+                        )
+                        .await
+                } else {
+                    return None;
+                };
+
+                match page {
+                    Ok(page) => {
+                        state.buffer = page.logs.into();
+                        state.next_page_url = page.meta.next_page_url;
+                    }
+                    Err(error) => {
+                        state.first_pages.clear();
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Follows the Environment's logs, polling every `interval` and yielding each new log
+    /// that passes `filter` in the order they were written. Only logs written after the
+    /// call are returned.
+    ///
+    /// When the filter has exactly one Function it is passed to Twilio, otherwise every log
+    /// is fetched and filtered locally.
+    ///
+    /// Each poll asks for logs from just before the newest log seen (see `TAIL_OVERLAP`) and
+    /// drops any already yielded, so no log is returned twice. A failed poll yields the error
+    /// and the next poll carries on from the same point. The stream never ends by itself.
+    ///
+    /// The starting point comes from the local clock while later ones come from Twilio's
+    /// timestamps, so logs written around the time of the call may be missed or included if
+    /// the two clocks differ.
+    pub fn tail(
+        &self,
+        filter: LogFilter,
+        interval: Duration,
+    ) -> BoxStream<'a, Result<ServerlessLog, TwilioError>> {
+        let function_sid = match filter.function_sids.as_slice() {
+            [function_sid] => Some(function_sid.clone()),
+            _ => None,
+        };
+        let now = Utc::now();
+        let state = TailState {
+            client: self.client,
+            service_sid: self.service_sid.to_string(),
+            environment_sid: self.environment_sid.to_string(),
+            function_sid,
+            filter,
+            interval,
+            start: now,
+            watermark: now,
+            seen: HashMap::new(),
+            pending: VecDeque::new(),
+            polled: false,
+        };
+
+        Box::pin(stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(log) = state.pending.pop_front() {
+                    return Some((Ok(log), state));
+                }
+
+                if state.polled {
+                    tokio::time::sleep(state.interval).await;
+                }
+                state.polled = true;
+
+                let since = state.watermark
+                    - chrono::Duration::from_std(TAIL_OVERLAP).expect("Overlap fits in a Duration");
+                let logs = Logs {
+                    client: state.client,
+                    service_sid: &state.service_sid,
+                    environment_sid: &state.environment_sid,
+                }
+                .list(state.function_sid.clone(), Some(since), None)
+                .await;
+
+                match logs {
+                    Ok(logs) => state.record(logs),
+                    Err(error) => return Some((Err(error), state)),
+                }
+            }
+        }))
+    }
+}
+
+// Progress of a `Logs::stream` between pages.
+struct StreamState<'a> {
+    client: &'a Client,
+    base_url: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    filter: LogFilter,
+    /// Function SIDs (or `None` for all) whose first page is still to be fetched.
+    first_pages: VecDeque<Option<String>>,
+    next_page_url: Option<String>,
+    /// Logs of the current page not yet yielded.
+    buffer: VecDeque<ServerlessLog>,
+}
+
+// Progress of a `Logs::tail` stream between polls.
+struct TailState<'a> {
+    client: &'a Client,
+    service_sid: String,
+    environment_sid: String,
+    function_sid: Option<String>,
+    filter: LogFilter,
+    interval: Duration,
+    /// When tailing began, older logs are dropped.
+    start: DateTime<Utc>,
+    /// Creation time of the newest log seen.
+    watermark: DateTime<Utc>,
+    /// Logs seen within the overlap window, by SID.
+    seen: HashMap<String, DateTime<Utc>>,
+    /// Logs waiting to be yielded, oldest first.
+    pending: VecDeque<ServerlessLog>,
+    polled: bool,
+}
+
+impl TailState<'_> {
+    // Queues the logs not seen before and moves the watermark forward.
+    fn record(&mut self, mut logs: Vec<ServerlessLog>) {
+        logs.sort_by(|a, b| a.date_created.cmp(&b.date_created));
+
+        for log in logs {
+            let Ok(date_created) = DateTime::parse_from_rfc3339(&log.date_created) else {
+                continue;
+            };
+            let date_created = date_created.with_timezone(&Utc);
+            if date_created < self.start {
+                continue;
+            }
+            if self.seen.insert(log.sid.clone(), date_created).is_some() {
+                continue;
+            }
+
+            self.watermark = self.watermark.max(date_created);
+            if self.filter.matches(&log) {
+                self.pending.push_back(log);
+            }
+        }
+
+        let cutoff = self.watermark
+            - chrono::Duration::from_std(TAIL_OVERLAP).expect("Overlap fits in a Duration");
+        self.seen.retain(|_, date_created| *date_created >= cutoff);
+    }
 }
 
 pub struct Log<'a, 'b> {
@@ -153,3 +456,170 @@ impl<'a, 'b> Log<'a, 'b> {
             .await
     }
 }
+
+// Orders logs newest first. Logs written at the same time keep their order.
+fn sort_newest_first(logs: &mut [ServerlessLog]) {
+    logs.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+}
+
+/// Groups logs by the request that wrote them so each invocation can be read together.
+///
+/// Groups are ordered by their first log and the logs within each group oldest first.
+pub fn group_by_request(mut logs: Vec<ServerlessLog>) -> Vec<RequestLogs> {
+    logs.sort_by(|a, b| a.date_created.cmp(&b.date_created));
+
+    let mut groups: Vec<RequestLogs> = Vec::new();
+    for log in logs {
+        match groups
+            .iter_mut()
+            .find(|group| group.request_sid == log.request_sid)
+        {
+            Some(group) => group.logs.push(log),
+            None => groups.push(RequestLogs {
+                request_sid: log.request_sid.clone(),
+                function_sid: log.function_sid.clone(),
+                logs: vec![log],
+            }),
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(sid: &str, level: Level, date_created: &str) -> ServerlessLog {
+        ServerlessLog {
+            sid: sid.to_string(),
+            level,
+            date_created: date_created.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tail_state<'a>(client: &'a Client, filter: LogFilter, start: &str) -> TailState<'a> {
+        let start = DateTime::parse_from_rfc3339(start)
+            .unwrap()
+            .with_timezone(&Utc);
+        TailState {
+            client,
+            service_sid: String::new(),
+            environment_sid: String::new(),
+            function_sid: None,
+            filter,
+            interval: Duration::from_secs(1),
+            start,
+            watermark: start,
+            seen: HashMap::new(),
+            pending: VecDeque::new(),
+            polled: true,
+        }
+    }
+
+    #[test]
+    fn tail_deduplicates_and_filters() {
+        let client = Client::new(&crate::TwilioConfig::default());
+        let mut state = tail_state(
+            &client,
+            LogFilter {
+                levels: vec![Level::Warn, Level::Error],
+                ..Default::default()
+            },
+            "2024-01-01T00:00:00Z",
+        );
+
+        state.record(vec![
+            log("NO2", Level::Error, "2024-01-01T00:00:02Z"),
+            log("NO1", Level::Warn, "2024-01-01T00:00:01Z"),
+            log("NO3", Level::Info, "2024-01-01T00:00:03Z"),
+        ]);
+        state.record(vec![
+            log("NO2", Level::Error, "2024-01-01T00:00:02Z"),
+            log("NO4", Level::Error, "2024-01-01T00:01:00Z"),
+        ]);
+
+        let sids: Vec<&str> = state.pending.iter().map(|log| log.sid.as_str()).collect();
+        assert_eq!(vec!["NO1", "NO2", "NO4"], sids);
+        assert_eq!("2024-01-01T00:01:00+00:00", state.watermark.to_rfc3339());
+        // Only logs within the overlap of the newest are remembered.
+        assert_eq!(1, state.seen.len());
+    }
+
+    #[test]
+    fn tail_drops_logs_from_before_start() {
+        let client = Client::new(&crate::TwilioConfig::default());
+        let mut state = tail_state(&client, LogFilter::default(), "2024-01-01T00:00:10Z");
+
+        // The first poll looks back over the overlap, before tailing began.
+        state.record(vec![
+            log("NO1", Level::Info, "2024-01-01T00:00:00Z"),
+            log("NO2", Level::Info, "2024-01-01T00:00:09Z"),
+            log("NO3", Level::Info, "2024-01-01T00:00:10Z"),
+            log("NO4", Level::Info, "2024-01-01T00:00:11Z"),
+        ]);
+
+        let sids: Vec<&str> = state.pending.iter().map(|log| log.sid.as_str()).collect();
+        assert_eq!(vec!["NO3", "NO4"], sids);
+    }
+
+    #[test]
+    fn filters_logs() {
+        let mut matching = log("NO1", Level::Error, "2024-01-01T00:00:00Z");
+        matching.function_sid = String::from("ZH1");
+        matching.request_sid = String::from("RQ1");
+        matching.message = String::from("Payment 42 declined");
+
+        let filter = LogFilter {
+            levels: vec![Level::Error],
+            function_sids: vec![String::from("ZH1"), String::from("ZH2")],
+            request_sid: Some(String::from("RQ1")),
+            message: Some(Regex::new(r"Payment \d+ declined").unwrap()),
+        };
+        assert!(filter.matches(&matching));
+        assert!(LogFilter::default().matches(&matching));
+
+        let mut other_request = matching.clone();
+        other_request.request_sid = String::from("RQ2");
+        assert!(!filter.matches(&other_request));
+
+        let mut other_message = matching.clone();
+        other_message.message = String::from("Payment accepted");
+        assert!(!filter.matches(&other_message));
+    }
+
+    #[test]
+    fn merges_logs_of_several_functions() {
+        // As listed by Twilio for each of two Functions in turn.
+        let mut logs = vec![
+            log("NO3", Level::Info, "2024-01-01T00:00:03Z"),
+            log("NO1", Level::Info, "2024-01-01T00:00:01Z"),
+            log("NO4", Level::Info, "2024-01-01T00:00:04Z"),
+            log("NO2", Level::Info, "2024-01-01T00:00:02Z"),
+        ];
+
+        sort_newest_first(&mut logs);
+        let sids: Vec<&str> = logs.iter().map(|log| log.sid.as_str()).collect();
+        assert_eq!(vec!["NO4", "NO3", "NO2", "NO1"], sids);
+    }
+
+    #[test]
+    fn groups_logs_by_request() {
+        let mut logs = vec![
+            log("NO3", Level::Info, "2024-01-01T00:00:03Z"),
+            log("NO1", Level::Info, "2024-01-01T00:00:01Z"),
+            log("NO2", Level::Error, "2024-01-01T00:00:02Z"),
+        ];
+        logs[0].request_sid = String::from("RQ1");
+        logs[1].request_sid = String::from("RQ1");
+        logs[2].request_sid = String::from("RQ2");
+
+        let groups = group_by_request(logs);
+        assert_eq!(2, groups.len());
+        assert_eq!("RQ1", groups[0].request_sid);
+        let sids: Vec<&str> = groups[0].logs.iter().map(|log| log.sid.as_str()).collect();
+        assert_eq!(vec!["NO1", "NO3"], sids);
+        assert_eq!("RQ2", groups[1].request_sid);
+    }
+}
@@ -0,0 +1,249 @@
+/*!
+
+Contains export of Serverless Logs to files.
+
+Logs are written one at a time as they arrive so a long export, such as one fed by
+`Logs::stream`, never has to be held in memory.
+
+*/
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use super::ServerlessLog;
+use crate::{ErrorKind, TwilioError};
+
+/// Columns written to a CSV export, in the order of `ServerlessLog`'s fields.
+pub const CSV_COLUMNS: [&str; 12] = [
+    "sid",
+    "account_sid",
+    "service_sid",
+    "environment_sid",
+    "build_sid",
+    "deployment_sid",
+    "function_sid",
+    "request_sid",
+    "level",
+    "message",
+    "date_created",
+    "url",
+];
+
+/// Formats logs can be exported in.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq)]
+pub enum ExportFormat {
+    #[strum(to_string = "CSV")]
+    Csv,
+    /// Newline delimited JSON, one log per line.
+    #[strum(to_string = "NDJSON")]
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// File extension for the format, including `.gz` when compressed.
+    pub fn extension(&self, compress: bool) -> &'static str {
+        match (self, compress) {
+            (ExportFormat::Csv, false) => "csv",
+            (ExportFormat::Csv, true) => "csv.gz",
+            (ExportFormat::Ndjson, false) => "ndjson",
+            (ExportFormat::Ndjson, true) => "ndjson.gz",
+        }
+    }
+}
+
+/// Writes logs to `W` in the chosen format, optionally gzip compressed.
+///
+/// `finish` must be called once all logs are written to flush the output and complete the
+/// compressed stream.
+pub struct LogExport<W: Write> {
+    output: Output<W>,
+    written: u64,
+}
+
+enum Output<W: Write> {
+    Csv(Box<csv::Writer<Encoder<W>>>),
+    Ndjson(Encoder<W>),
+}
+
+enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Plain(writer) => writer.write(buf),
+            Encoder::Gzip(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Plain(writer) => writer.flush(),
+            Encoder::Gzip(writer) => writer.flush(),
+        }
+    }
+}
+
+impl<W: Write> Encoder<W> {
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Plain(mut writer) => writer.flush().map(|_| writer),
+            Encoder::Gzip(writer) => writer.finish(),
+        }
+    }
+}
+
+impl<W: Write> LogExport<W> {
+    /// Starts an export into `writer`. For CSV the header row is written straight away.
+    pub fn new(writer: W, format: ExportFormat, compress: bool) -> Result<Self, TwilioError> {
+        let encoder = match compress {
+            true => Encoder::Gzip(GzEncoder::new(writer, Compression::default())),
+            false => Encoder::Plain(writer),
+        };
+
+        let output = match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(encoder);
+                writer.write_record(CSV_COLUMNS).map_err(csv_error)?;
+                Output::Csv(Box::new(writer))
+            }
+            ExportFormat::Ndjson => Output::Ndjson(encoder),
+        };
+
+        Ok(LogExport { output, written: 0 })
+    }
+
+    /// Writes a single log.
+    pub fn write(&mut self, log: &ServerlessLog) -> Result<(), TwilioError> {
+        match &mut self.output {
+            Output::Csv(writer) => writer.serialize(log).map_err(csv_error)?,
+            Output::Ndjson(writer) => {
+                serde_json::to_writer(&mut *writer, log).map_err(|error| TwilioError {
+                    kind: ErrorKind::IoError(error.into()),
+                })?;
+                writer.write_all(b"\n").map_err(io_error)?;
+            }
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of logs written so far.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Flushes the export, returning the underlying writer.
+    pub fn finish(self) -> Result<W, TwilioError> {
+        let encoder = match self.output {
+            Output::Csv(writer) => writer
+                .into_inner()
+                .map_err(|error| io_error(error.into_error()))?,
+            Output::Ndjson(encoder) => encoder,
+        };
+
+        encoder.finish().map_err(io_error)
+    }
+}
+
+/// A file name for an export of the Environment's logs taken at `at`, e.g.
+/// `ZE00000000000000000000000000000000-20240101T093000Z.csv.gz`.
+pub fn export_file_name(
+    environment_sid: &str,
+    format: ExportFormat,
+    compress: bool,
+    at: DateTime<Utc>,
+) -> String {
+    format!(
+        "{}-{}.{}",
+        environment_sid,
+        at.format("%Y%m%dT%H%M%SZ"),
+        format.extension(compress)
+    )
+}
+
+fn io_error(error: io::Error) -> TwilioError {
+    TwilioError {
+        kind: ErrorKind::IoError(error),
+    }
+}
+
+fn csv_error(error: csv::Error) -> TwilioError {
+    io_error(error.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+    use crate::serverless::environments::logs::Level;
+
+    fn log(sid: &str, message: &str) -> ServerlessLog {
+        ServerlessLog {
+            sid: sid.to_string(),
+            level: Level::Warn,
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exports_csv() {
+        let mut export = LogExport::new(Vec::new(), ExportFormat::Csv, false).unwrap();
+        export.write(&log("NO1", "Hello, \"world\"")).unwrap();
+        assert_eq!(1, export.written());
+
+        let output = String::from_utf8(export.finish().unwrap()).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(Some(CSV_COLUMNS.join(",").as_str()), lines.next());
+        assert_eq!(
+            Some("NO1,,,,,,,,WARN,\"Hello, \"\"world\"\"\",,"),
+            lines.next()
+        );
+    }
+
+    #[test]
+    fn exports_compressed_ndjson() {
+        let mut export = LogExport::new(Vec::new(), ExportFormat::Ndjson, true).unwrap();
+        export.write(&log("NO1", "first")).unwrap();
+        export.write(&log("NO2", "second")).unwrap();
+
+        let mut output = String::new();
+        GzDecoder::new(export.finish().unwrap().as_slice())
+            .read_to_string(&mut output)
+            .unwrap();
+
+        let sids: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<ServerlessLog>(line).unwrap().sid)
+            .collect();
+        assert_eq!(vec!["NO1", "NO2"], sids);
+    }
+
+    #[test]
+    fn names_export_files() {
+        let at = DateTime::parse_from_rfc3339("2024-01-01T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            "ZE1-20240101T093000Z.ndjson.gz",
+            export_file_name("ZE1", ExportFormat::Ndjson, true, at)
+        );
+        assert_eq!(
+            "ZE1-20240101T093000Z.csv",
+            export_file_name("ZE1", ExportFormat::Csv, false, at)
+        );
+    }
+}
@@ -0,0 +1,424 @@
+/*!
+
+Contains Twilio Serverless Environment Variables related functionality.
+
+Besides the individual resources, variables can be kept in step with a local
+`.env` file by diffing it against the Environment with `Variables::diff` and
+applying the result with `Variables::apply`.
+
+*/
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{Client, PageMeta, TwilioError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Represents a page of Serverless Variables from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct VariablePage {
+    variables: Vec<ServerlessVariable>,
+    meta: PageMeta,
+}
+
+/// A Serverless Environment Variable resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessVariable {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub environment_sid: String,
+    pub key: String,
+    pub value: String,
+    pub date_created: String,
+    pub date_updated: String,
+    pub url: String,
+}
+
+/// Parameters for creating a Variable.
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct CreateParams {
+    pub key: String,
+    pub value: String,
+}
+
+/// Parameters for updating a Variable.
+#[skip_serializing_none]
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct UpdateParams {
+    pub key: Option<String>,
+    pub value: Option<String>,
+}
+
+/// A change needed to bring an Environment's variables in line with a `.env` file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum VariableChange {
+    Add {
+        key: String,
+        value: String,
+    },
+    Update {
+        sid: String,
+        key: String,
+        value: String,
+    },
+    Remove {
+        sid: String,
+        key: String,
+    },
+}
+
+impl fmt::Display for VariableChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VariableChange::Add { key, .. } => write!(f, "+ {}", key),
+            VariableChange::Update { key, .. } => write!(f, "~ {}", key),
+            VariableChange::Remove { key, .. } => write!(f, "- {}", key),
+        }
+    }
+}
+
+pub struct Variables<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub environment_sid: &'b str,
+}
+
+impl<'a, 'b> Variables<'a, 'b> {
+    /// [Creates a Variable](https://www.twilio.com/docs/serverless/api/resource/variable#create-a-variable-resource)
+    ///
+    /// Creates a Variable in the Environment provided to `environment()` with the provided parameters.
+    pub async fn create(&self, params: CreateParams) -> Result<ServerlessVariable, TwilioError> {
+        self.client
+            .send_request::<ServerlessVariable, CreateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Variables",
+                    self.service_sid, self.environment_sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Variables](https://www.twilio.com/docs/serverless/api/resource/variable#read-multiple-variable-resources)
+    ///
+    /// Lists Variables of the Environment provided to `environment()` under the Serverless Service
+    /// provided to the `service()`.
+    ///
+    /// Variables will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ServerlessVariable>, TwilioError> {
+        let mut variables_page = self
+            .client
+            .send_request::<VariablePage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Variables?PageSize=50",
+                    self.service_sid, self.environment_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ServerlessVariable> = variables_page.variables;
+
+        while (variables_page.meta.next_page_url).is_some() {
+            variables_page = self
+                .client
+                .send_request::<VariablePage, ()>(
+                    Method::GET,
+                    &variables_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut variables_page.variables);
+        }
+
+        Ok(results)
+    }
+
+    /// Compares the Environment's Variables against the `desired` key/value pairs (such as
+    /// those read with `parse_env_file`) and returns the changes needed to match them.
+    ///
+    /// Variables missing from `desired` are only removed if `remove_missing` is set.
+    pub async fn diff(
+        &self,
+        desired: &BTreeMap<String, String>,
+        remove_missing: bool,
+    ) -> Result<Vec<VariableChange>, TwilioError> {
+        let existing = self.list().await?;
+        Ok(diff_variables(&existing, desired, remove_missing))
+    }
+
+    /// Applies the changes in order. Stops at the first change that fails, leaving any
+    /// earlier changes applied.
+    ///
+    /// Functions only see the new values once the Environment is next deployed.
+    pub async fn apply(&self, changes: &[VariableChange]) -> Result<(), TwilioError> {
+        for change in changes {
+            match change {
+                VariableChange::Add { key, value } => {
+                    self.create(CreateParams {
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+                    .await?;
+                }
+                VariableChange::Update { sid, value, .. } => {
+                    self.variable(sid)
+                        .update(UpdateParams {
+                            key: None,
+                            value: Some(value.clone()),
+                        })
+                        .await?;
+                }
+                VariableChange::Remove { sid, .. } => self.variable(sid).delete().await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn variable<'c>(&'c self, sid: &'c str) -> Variable<'a, 'c> {
+        Variable {
+            client: self.client,
+            service_sid: self.service_sid,
+            environment_sid: self.environment_sid,
+            sid,
+        }
+    }
+}
+
+pub struct Variable<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub environment_sid: &'b str,
+    /// SID of the Variable resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Variable<'a, 'b> {
+    /// [Gets a Variable](https://www.twilio.com/docs/serverless/api/resource/variable#fetch-a-variable-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and the Environment provided to
+    /// the `environment()` argument and fetches the Variable provided to the `variable()` argument.
+    pub async fn get(&self) -> Result<ServerlessVariable, TwilioError> {
+        self.client
+            .send_request::<ServerlessVariable, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Variables/{}",
+                    self.service_sid, self.environment_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Updates a Variable](https://www.twilio.com/docs/serverless/api/resource/variable#update-a-variable-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and the Environment provided to
+    /// the `environment()` argument and updates the Variable provided to the `variable()` argument.
+    pub async fn update(&self, params: UpdateParams) -> Result<ServerlessVariable, TwilioError> {
+        self.client
+            .send_request::<ServerlessVariable, UpdateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Variables/{}",
+                    self.service_sid, self.environment_sid, self.sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Deletes a Variable](https://www.twilio.com/docs/serverless/api/resource/variable#delete-a-variable-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and the Environment provided to
+    /// the `environment()` argument and deletes the Variable provided to the `variable()` argument.
+    pub async fn delete(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Environments/{}/Variables/{}",
+                    self.service_sid, self.environment_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+}
+
+/// Parses the contents of a `.env` file into key/value pairs.
+///
+/// Blank lines and lines starting with `#` are ignored, an `export ` prefix is allowed
+/// and values wrapped in matching single or double quotes have them removed.
+pub fn parse_env_file(contents: &str) -> Result<BTreeMap<String, String>, TwilioError> {
+    let mut variables = BTreeMap::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+        else {
+            return Err(TwilioError {
+                kind: crate::ErrorKind::ValidationError(format!(
+                    "Line {} of the .env file is not in the form KEY=VALUE",
+                    number + 1
+                )),
+            });
+        };
+
+        let value = value.trim();
+        let value = [('"', '"'), ('\'', '\'')]
+            .iter()
+            .find_map(|(open, close)| {
+                value
+                    .strip_prefix(*open)
+                    .and_then(|value| value.strip_suffix(*close))
+            })
+            .unwrap_or(value);
+
+        variables.insert(key.trim().to_string(), value.to_string());
+    }
+
+    Ok(variables)
+}
+
+// Works out the changes to turn the existing variables into the desired ones.
+fn diff_variables(
+    existing: &[ServerlessVariable],
+    desired: &BTreeMap<String, String>,
+    remove_missing: bool,
+) -> Vec<VariableChange> {
+    let mut changes = Vec::new();
+
+    for (key, value) in desired {
+        match existing.iter().find(|variable| &variable.key == key) {
+            Some(variable) if &variable.value == value => {}
+            Some(variable) => changes.push(VariableChange::Update {
+                sid: variable.sid.clone(),
+                key: key.clone(),
+                value: value.clone(),
+            }),
+            None => changes.push(VariableChange::Add {
+                key: key.clone(),
+                value: value.clone(),
+            }),
+        }
+    }
+
+    if remove_missing {
+        changes.extend(
+            existing
+                .iter()
+                .filter(|variable| !desired.contains_key(&variable.key))
+                .map(|variable| VariableChange::Remove {
+                    sid: variable.sid.clone(),
+                    key: variable.key.clone(),
+                }),
+        );
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(sid: &str, key: &str, value: &str) -> ServerlessVariable {
+        ServerlessVariable {
+            sid: sid.to_string(),
+            account_sid: String::from("ACxx"),
+            service_sid: String::from("ZSxx"),
+            environment_sid: String::from("ZExx"),
+            key: key.to_string(),
+            value: value.to_string(),
+            date_created: String::new(),
+            date_updated: String::new(),
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_env_file() {
+        let variables = parse_env_file(
+            "# Comment\n\nAPI_URL=https://example.com?a=b\nexport NAME=\"Jane Doe\"\nEMPTY=\nQUOTED='x'\n",
+        )
+        .unwrap();
+
+        assert_eq!("https://example.com?a=b", variables["API_URL"]);
+        assert_eq!("Jane Doe", variables["NAME"]);
+        assert_eq!("", variables["EMPTY"]);
+        assert_eq!("x", variables["QUOTED"]);
+        assert!(parse_env_file("NOT_A_PAIR").is_err());
+    }
+
+    #[test]
+    fn rejects_empty_keys() {
+        for contents in ["A=1\n=value", "A=1\n =value", "A=1\nexport =value"] {
+            match parse_env_file(contents).err().map(|error| error.kind) {
+                Some(crate::ErrorKind::ValidationError(message)) => assert_eq!(
+                    "Line 2 of the .env file is not in the form KEY=VALUE",
+                    message
+                ),
+                _ => panic!("Expected {:?} to be rejected", contents),
+            }
+        }
+    }
+
+    #[test]
+    fn diffs_variables() {
+        let existing = vec![
+            variable("ZV1", "SAME", "1"),
+            variable("ZV2", "CHANGED", "old"),
+            variable("ZV3", "MISSING", "x"),
+        ];
+        let desired = parse_env_file("SAME=1\nCHANGED=new\nADDED=yes").unwrap();
+
+        let changes = diff_variables(&existing, &desired, false);
+        assert_eq!(
+            vec![
+                VariableChange::Add {
+                    key: String::from("ADDED"),
+                    value: String::from("yes"),
+                },
+                VariableChange::Update {
+                    sid: String::from("ZV2"),
+                    key: String::from("CHANGED"),
+                    value: String::from("new"),
+                },
+            ],
+            changes
+        );
+
+        let changes = diff_variables(&existing, &desired, true);
+        assert_eq!(
+            Some(&VariableChange::Remove {
+                sid: String::from("ZV3"),
+                key: String::from("MISSING"),
+            }),
+            changes.last()
+        );
+    }
+}
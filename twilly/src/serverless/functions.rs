@@ -0,0 +1,231 @@
+/*!
+
+Contains Twilio Serverless Function related functionality.
+
+A Function is only a named container; its code lives in Function Versions which are
+uploaded through `versions()` and later included in a Build.
+
+*/
+
+pub mod versions;
+
+use crate::{Client, ErrorKind, PageMeta, TwilioError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use versions::{Version, Versions};
+
+/// Represents a page of Serverless Functions from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct FunctionPage {
+    functions: Vec<ServerlessFunction>,
+    meta: PageMeta,
+}
+
+/// A Serverless Function resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerlessFunction {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub friendly_name: String,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+    pub url: String,
+    pub links: Links,
+}
+
+/// Resources _linked_ to a Function.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Links {
+    pub function_versions: String,
+}
+
+/// Parameters for creating or updating a Function.
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct CreateOrUpdateParams {
+    pub friendly_name: String,
+}
+
+pub struct Functions<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+}
+
+impl<'a, 'b> Functions<'a, 'b> {
+    /// [Creates a Function](https://www.twilio.com/docs/serverless/api/resource/function#create-a-function-resource)
+    ///
+    /// Creates a Function in the Serverless Service provided to the `service()` argument.
+    pub async fn create(
+        &self,
+        params: CreateOrUpdateParams,
+    ) -> Result<ServerlessFunction, TwilioError> {
+        self.client
+            .send_request::<ServerlessFunction, CreateOrUpdateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions",
+                    self.service_sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Lists Functions](https://www.twilio.com/docs/serverless/api/resource/function#read-multiple-function-resources)
+    ///
+    /// Lists Functions of the Serverless Service provided to the `service()` argument.
+    ///
+    /// Functions will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<ServerlessFunction>, TwilioError> {
+        let mut functions_page = self
+            .client
+            .send_request::<FunctionPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions?PageSize=50",
+                    self.service_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ServerlessFunction> = functions_page.functions;
+
+        while (functions_page.meta.next_page_url).is_some() {
+            functions_page = self
+                .client
+                .send_request::<FunctionPage, ()>(
+                    Method::GET,
+                    &functions_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut functions_page.functions);
+        }
+
+        Ok(results)
+    }
+
+    /// Looks up the SIDs of the Functions with the given friendly names, in the same order.
+    ///
+    /// Returns a validation error naming any Functions that don't exist.
+    pub async fn sids_by_name(&self, names: &[String]) -> Result<Vec<String>, TwilioError> {
+        let functions = self.list().await?;
+        let mut sids = Vec::new();
+        let mut missing = Vec::new();
+
+        for name in names {
+            match functions
+                .iter()
+                .find(|function| &function.friendly_name == name)
+            {
+                Some(function) => sids.push(function.sid.clone()),
+                None => missing.push(name.as_str()),
+            }
+        }
+
+        match missing.is_empty() {
+            true => Ok(sids),
+            false => Err(TwilioError {
+                kind: ErrorKind::ValidationError(format!(
+                    "No Function named {}",
+                    missing.join(", ")
+                )),
+            }),
+        }
+    }
+}
+
+pub struct Function<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    /// SID of the Function resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Function<'a, 'b> {
+    /// [Gets a Function](https://www.twilio.com/docs/serverless/api/resource/function#fetch-a-function-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and fetches the Function
+    /// provided to the `function()` argument.
+    pub async fn get(&self) -> Result<ServerlessFunction, TwilioError> {
+        self.client
+            .send_request::<ServerlessFunction, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Updates a Function](https://www.twilio.com/docs/serverless/api/resource/function#update-a-function-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and updates the Function
+    /// provided to the `function()` argument.
+    pub async fn update(
+        &self,
+        params: CreateOrUpdateParams,
+    ) -> Result<ServerlessFunction, TwilioError> {
+        self.client
+            .send_request::<ServerlessFunction, CreateOrUpdateParams>(
+                Method::POST,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}",
+                    self.service_sid, self.sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// [Deletes a Function](https://www.twilio.com/docs/serverless/api/resource/function#delete-a-function-resource)
+    ///
+    /// Targets the Serverless Service provided to the `service()` argument and deletes the Function
+    /// provided to the `function()` argument. Functions that are part of an active Build cannot
+    /// be deleted.
+    pub async fn delete(&self) -> Result<(), TwilioError> {
+        self.client
+            .send_request_and_ignore_response::<()>(
+                Method::DELETE,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}",
+                    self.service_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// Actions relating to a known Function Version.
+    ///
+    /// Takes in the SID of the Function Version to perform actions against.
+    pub fn version(&'a self, sid: &'b str) -> Version<'a, 'b> {
+        Version {
+            client: self.client,
+            service_sid: self.service_sid,
+            function_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Function Version actions.
+    pub fn versions(&'a self) -> Versions<'a, 'b> {
+        Versions {
+            client: self.client,
+            service_sid: self.service_sid,
+            function_sid: self.sid,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+/*!
+
+Contains Twilio Serverless Function Version related functionality.
+
+Function Versions are immutable uploads of a Function's code. They are created through
+the separate `serverless-upload.twilio.com` domain as a multipart upload, while reading
+them back goes through the regular Serverless API.
+
+*/
+
+use crate::{
+    serverless::{upload::version_form, Visibility},
+    Client, PageMeta, TwilioError,
+};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Represents a page of Function Versions from the Twilio API.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+pub struct FunctionVersionPage {
+    function_versions: Vec<FunctionVersion>,
+    meta: PageMeta,
+}
+
+/// A Serverless Function Version resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionVersion {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub function_sid: String,
+    /// URL path the Function is served from once deployed, e.g. `/hello`.
+    pub path: String,
+    pub visibility: Visibility,
+    pub date_created: String,
+    pub url: String,
+    pub links: Links,
+}
+
+/// Resources _linked_ to a Function Version.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Links {
+    pub function_version_content: String,
+}
+
+/// The code of a Function Version.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionVersionContent {
+    pub sid: String,
+    pub account_sid: String,
+    pub service_sid: String,
+    pub function_sid: String,
+    pub content: String,
+    pub url: String,
+}
+
+/// Parameters for uploading a Function Version.
+#[derive(Clone, Debug)]
+pub struct CreateParams {
+    /// URL path the Function is served from, must begin with `/`.
+    pub path: String,
+    pub visibility: Visibility,
+    /// The Function's code.
+    pub content: Vec<u8>,
+    /// MIME type of the content. Usually `application/javascript`.
+    pub content_type: String,
+}
+
+pub struct Versions<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub function_sid: &'b str,
+}
+
+impl<'a, 'b> Versions<'a, 'b> {
+    /// [Creates a Function Version](https://www.twilio.com/docs/serverless/api/resource/function-version#create-a-function-version-resource)
+    ///
+    /// Uploads the provided content as a new Version of the Function provided to the `function()`
+    /// argument.
+    pub async fn create(&self, params: CreateParams) -> Result<FunctionVersion, TwilioError> {
+        let form = version_form(
+            params.path,
+            params.visibility,
+            params.content,
+            &params.content_type,
+        )?;
+
+        self.client
+            .send_multipart_request::<FunctionVersion>(
+                &format!(
+                    "https://serverless-upload.twilio.com/v1/Services/{}/Functions/{}/Versions",
+                    self.service_sid, self.function_sid
+                ),
+                form,
+            )
+            .await
+    }
+
+    /// [Lists Function Versions](https://www.twilio.com/docs/serverless/api/resource/function-version#read-multiple-function-version-resources)
+    ///
+    /// Lists Versions of the Function provided to the `function()` argument, newest first.
+    ///
+    /// Versions will be _eagerly_ paged until all retrieved.
+    pub async fn list(&self) -> Result<Vec<FunctionVersion>, TwilioError> {
+        let mut versions_page = self
+            .client
+            .send_request::<FunctionVersionPage, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}/Versions?PageSize=50",
+                    self.service_sid, self.function_sid
+                ),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<FunctionVersion> = versions_page.function_versions;
+
+        while (versions_page.meta.next_page_url).is_some() {
+            versions_page = self
+                .client
+                .send_request::<FunctionVersionPage, ()>(
+                    Method::GET,
+                    &versions_page.meta.next_page_url.unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+
+            results.append(&mut versions_page.function_versions);
+        }
+
+        Ok(results)
+    }
+}
+
+pub struct Version<'a, 'b> {
+    pub client: &'a Client,
+    pub service_sid: &'b str,
+    pub function_sid: &'b str,
+    /// SID of the Function Version resource.
+    pub sid: &'b str,
+}
+
+impl<'a, 'b> Version<'a, 'b> {
+    /// [Gets a Function Version](https://www.twilio.com/docs/serverless/api/resource/function-version#fetch-a-function-version-resource)
+    ///
+    /// Targets the Function provided to the `function()` argument and fetches the Version
+    /// provided to the `version()` argument.
+    pub async fn get(&self) -> Result<FunctionVersion, TwilioError> {
+        self.client
+            .send_request::<FunctionVersion, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}/Versions/{}",
+                    self.service_sid, self.function_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// [Gets Function Version content](https://www.twilio.com/docs/serverless/api/resource/function-version/function-version-content)
+    ///
+    /// Fetches the code uploaded for the Version provided to the `version()` argument.
+    pub async fn content(&self) -> Result<FunctionVersionContent, TwilioError> {
+        self.client
+            .send_request::<FunctionVersionContent, ()>(
+                Method::GET,
+                &format!(
+                    "https://serverless.twilio.com/v1/Services/{}/Functions/{}/Versions/{}/Content",
+                    self.service_sid, self.function_sid, self.sid
+                ),
+                None,
+                None,
+            )
+            .await
+    }
+}
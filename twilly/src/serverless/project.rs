@@ -0,0 +1,685 @@
+/*!
+
+Contains deployment of a local Serverless project, replacing the Node based Serverless
+Toolkit.
+
+A project follows the Toolkit's layout:
+
+- `functions/` holds the Functions. `hello.js` is served at `/hello`.
+- `assets/` holds the Assets. `audio/hold.mp3` is served at `/audio/hold.mp3`.
+- `package.json` lists the npm `dependencies` installed into the Build.
+- `.env` holds the Environment Variables. `ACCOUNT_SID` and `AUTH_TOKEN` are left out, as
+  they are local credentials and Functions already receive them through `context`.
+
+As with the Toolkit, a file is public unless its name is marked `.protected` or
+`.private`, e.g. `admin.private.js` is a private Function served at `/admin`.
+
+*/
+
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Client, ErrorKind, TwilioError};
+
+use super::{
+    assets,
+    builds::{self, BuildStatus, Dependency, ServerlessBuild},
+    environments::{self, deployments, variables::parse_env_file, ServerlessEnvironment},
+    functions,
+    services::{self, ServerlessService, Service},
+    upload::{read_directory, Manifest, ManifestEntry},
+    Visibility,
+};
+
+/// Variables in `.env` holding the local credentials, which are never deployed.
+const CREDENTIAL_VARIABLES: [&str; 2] = ["ACCOUNT_SID", "AUTH_TOKEN"];
+
+/// A local Serverless project read from disk with `Project::load`.
+#[derive(Clone, Debug)]
+pub struct Project {
+    /// The `name` from `package.json`, if present.
+    pub name: Option<String>,
+    pub functions: Vec<ProjectFile>,
+    pub assets: Vec<ProjectFile>,
+    pub dependencies: Vec<Dependency>,
+    /// Node.js runtime taken from `engines.node` in `package.json`, e.g. `node18`.
+    pub runtime: Option<String>,
+    /// Variables from `.env`, without `ACCOUNT_SID` and `AUTH_TOKEN`.
+    pub variables: BTreeMap<String, String>,
+}
+
+/// A Function or Asset file within a project.
+#[derive(Clone, Debug)]
+pub struct ProjectFile {
+    /// Location of the file within the project, e.g. `/functions/admin.private.js`.
+    pub source: String,
+    /// URL path the file is served from, e.g. `/admin`.
+    pub path: String,
+    pub visibility: Visibility,
+    pub content: Vec<u8>,
+    pub content_type: String,
+    pub hash: String,
+}
+
+/// Records what was uploaded by a previous deploy so unchanged files are skipped.
+///
+/// Both manifests are keyed by the file's `source`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    /// The Service the files were uploaded to. The manifest is ignored for any other Service.
+    pub service_sid: String,
+    pub functions: Manifest,
+    pub assets: Manifest,
+}
+
+/// Where and how to deploy a project.
+#[derive(Clone, Debug)]
+pub struct DeployOptions {
+    /// Unique name of the Service, created if it doesn't exist.
+    pub service_name: String,
+    /// Unique name of the Environment, created if it doesn't exist.
+    pub environment_name: String,
+    /// Domain suffix given to the Environment if it's created, e.g. `dev` for
+    /// `service-1234-dev.twil.io`. Left to Twilio when `None`.
+    pub domain_suffix: Option<String>,
+    /// Manifest returned by the previous deploy of the project.
+    pub previous: ProjectManifest,
+    /// How long to wait for the Build to complete.
+    pub build_timeout: Duration,
+}
+
+/// Progress reported while deploying.
+#[derive(Clone, Debug)]
+pub enum DeployEvent {
+    ServiceReady {
+        sid: String,
+        created: bool,
+    },
+    EnvironmentReady {
+        sid: String,
+        created: bool,
+    },
+    Uploaded {
+        source: String,
+        version_sid: String,
+    },
+    Unchanged {
+        source: String,
+    },
+    Building {
+        status: BuildStatus,
+        elapsed: Duration,
+    },
+    VariablesUpdated {
+        changes: usize,
+    },
+    Deployed {
+        deployment_sid: String,
+    },
+}
+
+/// The result of a successful deploy.
+#[derive(Debug)]
+pub struct DeployOutcome {
+    pub service: ServerlessService,
+    pub environment: ServerlessEnvironment,
+    pub build: ServerlessBuild,
+    pub deployment: deployments::ServerlessDeployment,
+    /// Manifest to pass as `previous` on the next deploy.
+    pub manifest: ProjectManifest,
+    /// URL of every deployed Function and Asset.
+    pub urls: Vec<String>,
+}
+
+impl Project {
+    /// Reads the project in `directory`. Missing `functions/`, `assets/`, `package.json` or
+    /// `.env` are treated as empty.
+    pub fn load(directory: &Path) -> Result<Project, TwilioError> {
+        let functions_directory = directory.join("functions");
+        let functions = match functions_directory.is_dir() {
+            true => read_directory(&functions_directory)?
+                .into_iter()
+                .filter(|file| file.path.ends_with(".js"))
+                .map(|file| {
+                    let (path, visibility) = split_visibility(
+                        file.path.strip_suffix(".js").unwrap_or(&file.path),
+                        Visibility::Public,
+                    );
+                    ProjectFile {
+                        source: format!("/functions{}", file.path),
+                        path,
+                        visibility,
+                        content: file.content,
+                        content_type: String::from("application/javascript"),
+                        hash: file.hash,
+                    }
+                })
+                .collect(),
+            false => Vec::new(),
+        };
+
+        let assets_directory = directory.join("assets");
+        let assets = match assets_directory.is_dir() {
+            true => read_directory(&assets_directory)?
+                .into_iter()
+                .map(|file| {
+                    let (path, visibility) = split_visibility(&file.path, Visibility::Public);
+                    ProjectFile {
+                        source: format!("/assets{}", file.path),
+                        path,
+                        visibility,
+                        content: file.content,
+                        content_type: file.content_type,
+                        hash: file.hash,
+                    }
+                })
+                .collect(),
+            false => Vec::new(),
+        };
+
+        let package = match read_optional(&directory.join("package.json"))? {
+            Some(contents) => {
+                serde_json::from_str::<Value>(&contents).map_err(|error| TwilioError {
+                    kind: ErrorKind::ValidationError(format!("Invalid package.json: {}", error)),
+                })?
+            }
+            None => Value::Null,
+        };
+
+        let mut variables = match read_optional(&directory.join(".env"))? {
+            Some(contents) => parse_env_file(&contents)?,
+            None => BTreeMap::new(),
+        };
+        variables.retain(|key, _| !CREDENTIAL_VARIABLES.contains(&key.as_str()));
+
+        Ok(Project {
+            name: package["name"].as_str().map(String::from),
+            functions,
+            assets,
+            dependencies: package_dependencies(&package),
+            runtime: package_runtime(&package),
+            variables,
+        })
+    }
+}
+
+/// Deploys the project, creating the Service and Environment if needed, uploading new or
+/// changed files, building, setting the Environment Variables and finally deploying.
+///
+/// Variables are set before the Deployment is created so they take effect with it. Existing
+/// variables not in the project's `.env` are left alone.
+pub(crate) async fn deploy<F>(
+    client: &Client,
+    project: &Project,
+    options: DeployOptions,
+    mut on_event: F,
+) -> Result<DeployOutcome, TwilioError>
+where
+    F: FnMut(DeployEvent),
+{
+    let services = client.serverless();
+    let existing_service = services
+        .services()
+        .list()
+        .await?
+        .into_iter()
+        .find(|service| service.unique_name == options.service_name);
+    let created = existing_service.is_none();
+    let service_resource = match existing_service {
+        Some(service) => service,
+        None => {
+            services
+                .services()
+                .create(services::CreateOrUpdateParams {
+                    unique_name: options.service_name.clone(),
+                    friendly_name: options.service_name.clone(),
+                    include_credentials: Some(true),
+                    ui_editable: None,
+                })
+                .await?
+        }
+    };
+    on_event(DeployEvent::ServiceReady {
+        sid: service_resource.sid.clone(),
+        created,
+    });
+
+    let service = services.service(&service_resource.sid);
+    let existing_environment = service
+        .environments()
+        .list()
+        .await?
+        .into_iter()
+        .find(|environment| environment.unique_name == options.environment_name);
+    let created = existing_environment.is_none();
+    let environment_resource = match existing_environment {
+        Some(environment) => environment,
+        None => {
+            service
+                .environments()
+                .create::<()>(environments::CreateParams {
+                    unique_name: options.environment_name.clone(),
+                    domain_suffix: options.domain_suffix.clone(),
+                })
+                .await?
+        }
+    };
+    on_event(DeployEvent::EnvironmentReady {
+        sid: environment_resource.sid.clone(),
+        created,
+    });
+
+    let previous = match options.previous.service_sid == service_resource.sid {
+        true => options.previous,
+        false => ProjectManifest::default(),
+    };
+    let manifest = ProjectManifest {
+        service_sid: service_resource.sid.clone(),
+        functions: upload_functions(
+            &service,
+            &project.functions,
+            &previous.functions,
+            &mut on_event,
+        )
+        .await?,
+        assets: upload_assets(&service, &project.assets, &previous.assets, &mut on_event).await?,
+    };
+
+    let build = service
+        .builds()
+        .create(builds::CreateParams {
+            function_versions: version_sids(&manifest.functions),
+            asset_versions: version_sids(&manifest.assets),
+            dependencies: project.dependencies.clone(),
+            runtime: project.runtime.clone(),
+        })
+        .await?;
+    let build = service
+        .build(&build.sid)
+        .wait(
+            Duration::from_secs(2),
+            options.build_timeout,
+            |status, elapsed| on_event(DeployEvent::Building { status, elapsed }),
+        )
+        .await?;
+    if build.status == BuildStatus::Failed {
+        return Err(TwilioError {
+            kind: ErrorKind::BuildError(format!("{}, nothing was deployed", build.sid)),
+        });
+    }
+
+    let environment = service.environment(&environment_resource.sid);
+    if !project.variables.is_empty() {
+        let changes = environment
+            .variables()
+            .diff(&project.variables, false)
+            .await?;
+        environment.variables().apply(&changes).await?;
+        on_event(DeployEvent::VariablesUpdated {
+            changes: changes.len(),
+        });
+    }
+
+    let deployment = environment
+        .deployments()
+        .create(deployments::CreateParams {
+            build_sid: Some(build.sid.clone()),
+        })
+        .await?;
+    on_event(DeployEvent::Deployed {
+        deployment_sid: deployment.sid.clone(),
+    });
+
+    let urls = project
+        .functions
+        .iter()
+        .chain(project.assets.iter())
+        .filter(|file| file.visibility != Visibility::Private)
+        .map(|file| format!("https://{}{}", environment_resource.domain_name, file.path))
+        .collect();
+
+    Ok(DeployOutcome {
+        service: service_resource,
+        environment: environment_resource,
+        build,
+        deployment,
+        manifest,
+        urls,
+    })
+}
+
+// Uploads Functions whose content changed since `previous`, creating the Function first
+// where needed. Returns the manifest of every Function in the project.
+async fn upload_functions<F>(
+    service: &Service<'_, '_>,
+    files: &[ProjectFile],
+    previous: &Manifest,
+    on_event: &mut F,
+) -> Result<Manifest, TwilioError>
+where
+    F: FnMut(DeployEvent),
+{
+    let existing = service.functions().list().await?;
+    let mut manifest = Manifest::new();
+
+    for file in files {
+        let existing_sids: Vec<&str> = existing
+            .iter()
+            .map(|function| function.sid.as_str())
+            .collect();
+        if let Some(entry) = unchanged(previous, file, &existing_sids) {
+            manifest.insert(file.source.clone(), entry.clone());
+            on_event(DeployEvent::Unchanged {
+                source: file.source.clone(),
+            });
+            continue;
+        }
+
+        let function_sid = match existing
+            .iter()
+            .find(|function| function.friendly_name == file.path)
+        {
+            Some(function) => function.sid.clone(),
+            None => {
+                service
+                    .functions()
+                    .create(functions::CreateOrUpdateParams {
+                        friendly_name: file.path.clone(),
+                    })
+                    .await?
+                    .sid
+            }
+        };
+
+        let version = service
+            .function(&function_sid)
+            .versions()
+            .create(functions::versions::CreateParams {
+                path: file.path.clone(),
+                visibility: file.visibility,
+                content: file.content.clone(),
+                content_type: file.content_type.clone(),
+            })
+            .await?;
+
+        on_event(DeployEvent::Uploaded {
+            source: file.source.clone(),
+            version_sid: version.sid.clone(),
+        });
+        manifest.insert(
+            file.source.clone(),
+            ManifestEntry {
+                hash: file.hash.clone(),
+                resource_sid: function_sid,
+                version_sid: version.sid,
+            },
+        );
+    }
+
+    Ok(manifest)
+}
+
+// The Asset equivalent of `upload_functions`.
+async fn upload_assets<F>(
+    service: &Service<'_, '_>,
+    files: &[ProjectFile],
+    previous: &Manifest,
+    on_event: &mut F,
+) -> Result<Manifest, TwilioError>
+where
+    F: FnMut(DeployEvent),
+{
+    let existing = service.assets().list().await?;
+    let mut manifest = Manifest::new();
+
+    for file in files {
+        let existing_sids: Vec<&str> = existing.iter().map(|asset| asset.sid.as_str()).collect();
+        if let Some(entry) = unchanged(previous, file, &existing_sids) {
+            manifest.insert(file.source.clone(), entry.clone());
+            on_event(DeployEvent::Unchanged {
+                source: file.source.clone(),
+            });
+            continue;
+        }
+
+        let asset_sid = match existing
+            .iter()
+            .find(|asset| asset.friendly_name == file.path)
+        {
+            Some(asset) => asset.sid.clone(),
+            None => {
+                service
+                    .assets()
+                    .create(assets::CreateOrUpdateParams {
+                        friendly_name: file.path.clone(),
+                    })
+                    .await?
+                    .sid
+            }
+        };
+
+        let version = service
+            .asset(&asset_sid)
+            .versions()
+            .create(assets::versions::CreateParams {
+                path: file.path.clone(),
+                visibility: file.visibility,
+                content: file.content.clone(),
+                content_type: file.content_type.clone(),
+            })
+            .await?;
+
+        on_event(DeployEvent::Uploaded {
+            source: file.source.clone(),
+            version_sid: version.sid.clone(),
+        });
+        manifest.insert(
+            file.source.clone(),
+            ManifestEntry {
+                hash: file.hash.clone(),
+                resource_sid: asset_sid,
+                version_sid: version.sid,
+            },
+        );
+    }
+
+    Ok(manifest)
+}
+
+// The previous upload of the file, if its content is the same and the resource still exists.
+fn unchanged<'m>(
+    previous: &'m Manifest,
+    file: &ProjectFile,
+    existing_sids: &[&str],
+) -> Option<&'m ManifestEntry> {
+    previous.get(&file.source).filter(|entry| {
+        entry.hash == file.hash && existing_sids.contains(&entry.resource_sid.as_str())
+    })
+}
+
+fn version_sids(manifest: &Manifest) -> Vec<String> {
+    manifest
+        .values()
+        .map(|entry| entry.version_sid.clone())
+        .collect()
+}
+
+// Removes a `.protected` or `.private` marker from the file name, returning the
+// path without it and the visibility it marks. The marker may be any dot separated
+// segment after the first, e.g. `foo.bar.private.js`.
+fn split_visibility(path: &str, default: Visibility) -> (String, Visibility) {
+    let (directory, name) = path.rsplit_once('/').unwrap_or(("", path));
+    let mut segments: Vec<&str> = name.split('.').collect();
+
+    for (marker, visibility) in [
+        ("protected", Visibility::Protected),
+        ("private", Visibility::Private),
+    ] {
+        // The first segment is the start of the file name so is never a marker.
+        if let Some(position) = segments
+            .iter()
+            .skip(1)
+            .position(|segment| *segment == marker)
+        {
+            segments.remove(position + 1);
+            return (format!("{}/{}", directory, segments.join(".")), visibility);
+        }
+    }
+
+    (path.to_string(), default)
+}
+
+fn package_dependencies(package: &Value) -> Vec<Dependency> {
+    package["dependencies"]
+        .as_object()
+        .map(|dependencies| {
+            dependencies
+                .iter()
+                .filter_map(|(name, version)| {
+                    Some(Dependency {
+                        name: name.clone(),
+                        version: version.as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Picks the Twilio runtime from an `engines.node` range such as `18` or `>=18.0.0`.
+fn package_runtime(package: &Value) -> Option<String> {
+    let engine = package["engines"]["node"].as_str()?;
+    let major: String = engine
+        .chars()
+        .skip_while(|character| !character.is_ascii_digit())
+        .take_while(|character| character.is_ascii_digit())
+        .collect();
+
+    match major.is_empty() {
+        true => None,
+        false => Some(format!("node{}", major)),
+    }
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, TwilioError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(TwilioError {
+            kind: ErrorKind::IoError(error),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_visibility_markers() {
+        assert_eq!(
+            (String::from("/hello"), Visibility::Public),
+            split_visibility("/hello", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/admin"), Visibility::Private),
+            split_visibility("/admin.private", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/audio/hold.mp3"), Visibility::Protected),
+            split_visibility("/audio/hold.protected.mp3", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/privateer.txt"), Visibility::Public),
+            split_visibility("/privateer.txt", Visibility::Public)
+        );
+    }
+
+    #[test]
+    fn splits_visibility_markers_from_multi_dot_names() {
+        assert_eq!(
+            (String::from("/lib/foo.bar.js"), Visibility::Private),
+            split_visibility("/lib/foo.bar.private.js", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/foo.bar.baz"), Visibility::Protected),
+            split_visibility("/foo.bar.baz.protected", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/notes"), Visibility::Protected),
+            split_visibility("/notes.protected", Visibility::Public)
+        );
+        assert_eq!(
+            (String::from("/private.js"), Visibility::Public),
+            split_visibility("/private.js", Visibility::Public)
+        );
+    }
+
+    #[test]
+    fn loads_project() {
+        let directory = std::env::temp_dir().join(format!("twilly-project-{}", std::process::id()));
+        fs::create_dir_all(directory.join("functions")).unwrap();
+        fs::create_dir_all(directory.join("assets")).unwrap();
+        fs::write(
+            directory.join("functions").join("hello.js"),
+            "exports.handler = 1",
+        )
+        .unwrap();
+        fs::write(directory.join("functions").join("admin.private.js"), "").unwrap();
+        fs::write(directory.join("functions").join("notes.md"), "").unwrap();
+        fs::write(directory.join("assets").join("index.html"), "<p>Hi</p>").unwrap();
+        fs::write(
+            directory.join("package.json"),
+            r#"{ "name": "demo", "dependencies": { "axios": "^1.6.0" }, "engines": { "node": ">=18" } }"#,
+        )
+        .unwrap();
+        fs::write(directory.join(".env"), "GREETING=hi").unwrap();
+
+        let project = Project::load(&directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(Some(String::from("demo")), project.name);
+        let functions: Vec<(&str, &str, Visibility)> = project
+            .functions
+            .iter()
+            .map(|file| (file.source.as_str(), file.path.as_str(), file.visibility))
+            .collect();
+        assert_eq!(
+            vec![
+                ("/functions/admin.private.js", "/admin", Visibility::Private),
+                ("/functions/hello.js", "/hello", Visibility::Public),
+            ],
+            functions
+        );
+        assert_eq!("/index.html", project.assets[0].path);
+        assert_eq!(
+            vec![Dependency {
+                name: String::from("axios"),
+                version: String::from("^1.6.0"),
+            }],
+            project.dependencies
+        );
+        assert_eq!(Some(String::from("node18")), project.runtime);
+        assert_eq!("hi", project.variables["GREETING"]);
+    }
+
+    #[test]
+    fn leaves_credentials_out_of_variables() {
+        let directory =
+            std::env::temp_dir().join(format!("twilly-project-env-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join(".env"),
+            "ACCOUNT_SID=AC11111111111111111111111111111111\nAUTH_TOKEN=secret\nGREETING=hi",
+        )
+        .unwrap();
+
+        let project = Project::load(&directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let keys: Vec<&String> = project.variables.keys().collect();
+        assert_eq!(vec!["GREETING"], keys);
+    }
+}
@@ -9,7 +9,12 @@ use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use super::environments::{Environment, Environments};
+use super::{
+    assets::{Asset, Assets},
+    builds::{Build, BuildDiff, Builds},
+    environments::{Environment, Environments},
+    functions::{Function, Functions},
+};
 
 /// Represents a page of Services from the Twilio API.
 #[allow(dead_code)]
@@ -173,7 +178,7 @@ impl<'a, 'b> Service<'a, 'b> {
     /// Actions relating to a known Service Environment.
     ///
     /// Takes in the SID of the Environment to perform actions against.
-    pub fn environment(&'a self, sid: &'b str) -> Environment {
+    pub fn environment(&'a self, sid: &'b str) -> Environment<'a, 'b> {
         Environment {
             client: self.client,
             service_sid: self.sid,
@@ -182,10 +187,78 @@ impl<'a, 'b> Service<'a, 'b> {
     }
 
     /// General Service Environment actions.
-    pub fn environments(&'a self) -> Environments {
+    pub fn environments(&'a self) -> Environments<'a, 'b> {
         Environments {
             client: self.client,
             service_sid: self.sid,
         }
     }
+
+    /// Actions relating to a known Service Function.
+    ///
+    /// Takes in the SID of the Function to perform actions against.
+    pub fn function(&'a self, sid: &'b str) -> Function<'a, 'b> {
+        Function {
+            client: self.client,
+            service_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Service Function actions.
+    pub fn functions(&'a self) -> Functions<'a, 'b> {
+        Functions {
+            client: self.client,
+            service_sid: self.sid,
+        }
+    }
+
+    /// Actions relating to a known Service Asset.
+    ///
+    /// Takes in the SID of the Asset to perform actions against.
+    pub fn asset(&'a self, sid: &'b str) -> Asset<'a, 'b> {
+        Asset {
+            client: self.client,
+            service_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Service Asset actions.
+    pub fn assets(&'a self) -> Assets<'a, 'b> {
+        Assets {
+            client: self.client,
+            service_sid: self.sid,
+        }
+    }
+
+    /// Fetches two Builds of the Service and compares their Function & Asset Versions.
+    pub async fn diff_builds(
+        &self,
+        from_build_sid: &str,
+        to_build_sid: &str,
+    ) -> Result<BuildDiff, TwilioError> {
+        let from = self.build(from_build_sid).get().await?;
+        let to = self.build(to_build_sid).get().await?;
+        Ok(BuildDiff::between(&from, &to))
+    }
+
+    /// Actions relating to a known Service Build.
+    ///
+    /// Takes in the SID of the Build to perform actions against.
+    pub fn build(&'a self, sid: &'b str) -> Build<'a, 'b> {
+        Build {
+            client: self.client,
+            service_sid: self.sid,
+            sid,
+        }
+    }
+
+    /// General Service Build actions.
+    pub fn builds(&'a self) -> Builds<'a, 'b> {
+        Builds {
+            client: self.client,
+            service_sid: self.sid,
+        }
+    }
 }
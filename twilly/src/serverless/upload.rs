@@ -0,0 +1,191 @@
+/*!
+
+Contains helpers shared by Function and Asset Version uploads.
+
+Twilio has no way of reading back an Asset's content, so to avoid uploading files that
+have not changed a `Manifest` of content hashes from the previous upload is kept by the
+caller and handed back in on the next one.
+
+*/
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{ErrorKind, TwilioError};
+
+use super::Visibility;
+
+/// What was uploaded for each file, keyed by the file's path.
+pub type Manifest = BTreeMap<String, ManifestEntry>;
+
+/// A previously uploaded file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// SHA-256 of the file content, hex encoded.
+    pub hash: String,
+    /// SID of the Function or Asset the file was uploaded to.
+    pub resource_sid: String,
+    /// SID of the Version created by the upload.
+    pub version_sid: String,
+}
+
+/// A file read from a local directory ready to be uploaded.
+#[derive(Clone, Debug)]
+pub struct LocalFile {
+    /// URL path the file will be served from, e.g. `/audio/hold.mp3`.
+    pub path: String,
+    pub content: Vec<u8>,
+    pub content_type: String,
+    pub hash: String,
+}
+
+/// Builds the multipart form for a Function or Asset Version upload.
+pub(crate) fn version_form(
+    path: String,
+    visibility: Visibility,
+    content: Vec<u8>,
+    content_type: &str,
+) -> Result<Form, TwilioError> {
+    validate_path(&path)?;
+
+    let content = Part::bytes(content)
+        .file_name(file_name(&path))
+        .mime_str(content_type)
+        .map_err(|_| TwilioError {
+            kind: ErrorKind::ValidationError(format!("Invalid content type: {}", content_type)),
+        })?;
+
+    Ok(Form::new()
+        .text("Path", path)
+        .text("Visibility", visibility.to_string())
+        .part("Content", content))
+}
+
+/// Reads every file below `directory`, skipping hidden files and directories.
+///
+/// Paths are relative to `directory` and always use `/` as the separator.
+pub fn read_directory(directory: &Path) -> Result<Vec<LocalFile>, TwilioError> {
+    let mut files = Vec::new();
+    let mut pending: Vec<PathBuf> = vec![directory.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current).map_err(|error| TwilioError {
+            kind: ErrorKind::IoError(error),
+        })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|error| TwilioError {
+                kind: ErrorKind::IoError(error),
+            })?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                pending.push(entry_path);
+                continue;
+            }
+
+            let content = fs::read(&entry_path).map_err(|error| TwilioError {
+                kind: ErrorKind::IoError(error),
+            })?;
+            let relative = entry_path
+                .strip_prefix(directory)
+                .expect("Entry should be within the directory being read");
+
+            files.push(LocalFile {
+                path: format!(
+                    "/{}",
+                    relative
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                ),
+                content_type: mime_guess::from_path(&entry_path)
+                    .first_or_octet_stream()
+                    .to_string(),
+                hash: content_hash(&content),
+                content,
+            });
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// SHA-256 of the content, hex encoded.
+pub fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Checks a Version path is one Twilio will accept.
+pub(crate) fn validate_path(path: &str) -> Result<(), TwilioError> {
+    let error = if !path.starts_with('/') {
+        "Path must begin with '/'"
+    } else if path.len() > 255 {
+        "Path must be 255 characters or fewer"
+    } else {
+        return Ok(());
+    };
+
+    Err(TwilioError {
+        kind: ErrorKind::ValidationError(String::from(error)),
+    })
+}
+
+/// The file name sent alongside uploaded content, taken from the last segment of the path.
+fn file_name(path: &str) -> String {
+    match path.rsplit('/').next() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => String::from("index"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_path() {
+        assert!(validate_path("/hello").is_ok());
+        assert!(validate_path("hello").is_err());
+        assert!(validate_path(&format!("/{}", "a".repeat(255))).is_err());
+    }
+
+    #[test]
+    fn file_name_from_path() {
+        assert_eq!("hello", file_name("/hello"));
+        assert_eq!("world.js", file_name("/nested/world.js"));
+        assert_eq!("index", file_name("/"));
+    }
+
+    #[test]
+    fn reads_directory() {
+        let directory = std::env::temp_dir().join(format!("twilly-upload-{}", std::process::id()));
+        fs::create_dir_all(directory.join("audio")).unwrap();
+        fs::create_dir_all(directory.join(".cache")).unwrap();
+        fs::write(directory.join("index.html"), "<p>Hi</p>").unwrap();
+        fs::write(directory.join("audio").join("hold.mp3"), [0u8, 1, 2]).unwrap();
+        fs::write(directory.join(".cache").join("ignored"), "x").unwrap();
+        fs::write(directory.join(".twilly-assets.json"), "{}").unwrap();
+
+        let files = read_directory(&directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(vec!["/audio/hold.mp3", "/index.html"], paths);
+        assert_eq!("audio/mpeg", files[0].content_type);
+        assert_eq!("text/html", files[1].content_type);
+        assert_eq!(content_hash(b"<p>Hi</p>"), files[1].hash);
+    }
+}
@@ -3,12 +3,17 @@
 Contains Twilio Sync related functionality.
 
 */
+pub mod copy;
 pub mod documents;
+pub mod expiry;
 pub mod listitems;
 pub mod lists;
 pub mod mapitems;
 pub mod maps;
 pub mod services;
+pub mod snapshot;
+pub mod subscription;
+pub mod transfer;
 
 use crate::Client;
 
@@ -24,7 +29,7 @@ impl<'a> Sync<'a> {
     /// Functions relating to a known Sync Service.
     ///
     /// Takes in the SID of the Sync Service to perform actions against.
-    pub fn service<'b: 'a>(&'a self, sid: &'b str) -> Service {
+    pub fn service<'b: 'a>(&'a self, sid: &'b str) -> Service<'a, 'b> {
         Service {
             client: self.client,
             sid,
@@ -32,7 +37,7 @@ impl<'a> Sync<'a> {
     }
 
     /// General Sync Service functions.
-    pub fn services(&'a self) -> Services {
+    pub fn services(&'a self) -> Services<'a> {
         Services {
             client: self.client,
         }
@@ -0,0 +1,47 @@
+/*!
+
+Contains functionality for copying Sync Documents, Lists and Maps into another
+Sync Service. The target Service may belong to a different account by obtaining
+it from a separate `Client`.
+
+*/
+
+use super::{snapshot::RestoreParams, transfer::ConflictPolicy};
+
+/// Parameters for copying a Document, List or Map into another Sync Service.
+#[derive(Clone, Debug)]
+pub struct CopyParams {
+    /// Unique name of the copy. Defaults to the unique name of the original.
+    pub unique_name: Option<String>,
+    /// Only plan the copy, nothing is written to the target Service.
+    pub dry_run: bool,
+    /// What to do if the unique name already exists in the target Service.
+    /// Overwriting a List or Map overwrites its matching items and adds any
+    /// missing ones.
+    pub conflict_policy: ConflictPolicy,
+    /// Maximum number of item requests in flight at once.
+    pub concurrency: usize,
+}
+
+impl Default for CopyParams {
+    fn default() -> Self {
+        let restore_params = RestoreParams::default();
+        CopyParams {
+            unique_name: None,
+            dry_run: restore_params.dry_run,
+            conflict_policy: restore_params.conflict_policy,
+            concurrency: restore_params.concurrency,
+        }
+    }
+}
+
+impl CopyParams {
+    // The equivalent parameters for restoring the copied object.
+    pub(crate) fn restore_params(&self) -> RestoreParams {
+        RestoreParams {
+            dry_run: self.dry_run,
+            conflict_policy: self.conflict_policy,
+            concurrency: self.concurrency,
+        }
+    }
+}
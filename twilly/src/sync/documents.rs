@@ -4,11 +4,24 @@ Contains Twilio Sync Document related functionality.
 
 */
 
+use std::time::Duration;
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
 use reqwest::{header::HeaderMap, Method};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use serde_with::skip_serializing_none;
+use serde_with::{serde_as, skip_serializing_none, DurationSeconds};
+
+use super::{
+    copy::CopyParams,
+    expiry::{validate_ttl, TtlParams},
+    services::Service,
+    snapshot::{self, DocumentRecord, RestoreReport, SyncObjects},
+    subscription::{
+        self, EventStream, ObjectType, SubscribeParams, Transport, WebSocketTransport, TWILSOCK_URL,
+    },
+};
 
 /// Represents a page of Sync Documents from the Twilio API.
 #[allow(dead_code)]
@@ -29,7 +42,7 @@ pub struct SyncDocument {
     pub data: Value,
     pub date_created: String,
     pub date_updated: String,
-    pub date_expires: Option<String>,
+    pub date_expires: Option<DateTime<Utc>>,
     /// Identity of the creator. Uses the identity of the
     /// respective client or defaults to `system` if created via REST.
     pub created_by: String,
@@ -50,20 +63,22 @@ where
 {
     pub unique_name: Option<String>,
     pub data: &'a T,
-    /// How long the Document should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
+    /// How long the Document should exist before deletion.
+    pub ttl: Option<Duration>,
 }
 
 /// Parameters for creating a Sync Document with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 struct CreateParamsWithJson {
     unique_name: Option<String>,
     data: String,
-    /// How long the Document should exist before deletion (in seconds).
-    ttl: Option<u16>,
+    /// How long the Document should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
 }
 
 /// Parameters for updating a Sync Document
@@ -74,12 +89,13 @@ where
     pub if_match: Option<String>,
     /// Any value that can be represented as JSON
     pub data: &'a T,
-    /// How long the Document should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
+    /// How long the Document should exist before deletion.
+    pub ttl: Option<Duration>,
 }
 
 /// Parameters for creating a Sync Document with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
@@ -88,8 +104,9 @@ struct UpdateParamsWithJson {
     if_match: Option<String>,
     /// Any value that can be represented as JSON
     data: String,
-    /// How long the Document should exist before deletion (in seconds).
-    ttl: Option<u16>,
+    /// How long the Document should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
 }
 
 pub struct Documents<'a, 'b> {
@@ -105,6 +122,8 @@ impl<'a, 'b> Documents<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+
         let params = CreateParamsWithJson {
             unique_name: params.unique_name,
             data: serde_json::to_string(params.data)
@@ -198,6 +217,8 @@ impl<'a, 'b> Document<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+
         // Create a new struct with the provided data parameter converted to a
         // JSON string as required by Twilio.
         let params = UpdateParamsWithJson {
@@ -226,6 +247,74 @@ impl<'a, 'b> Document<'a, 'b> {
             .await
     }
 
+    /// Sets only the TTL of the Document, leaving its data untouched.
+    pub async fn update_ttl(&self, ttl: Duration) -> Result<SyncDocument, TwilioError> {
+        validate_ttl(Some(ttl))?;
+
+        self.client
+            .send_request::<SyncDocument, TtlParams>(
+                Method::POST,
+                &format!(
+                    "https://sync.twilio.com/v1/Services/{}/Documents/{}",
+                    self.service_sid, self.sid
+                ),
+                Some(&TtlParams { ttl }),
+                None,
+            )
+            .await
+    }
+
+    /// Subscribes to changes to the Document provided to the `document()` argument.
+    ///
+    /// A Sync access token is minted from the API Key in `params` and a WebSocket opened
+    /// to Twilio. The returned stream yields each change as it happens.
+    pub async fn subscribe(&self, params: SubscribeParams) -> Result<EventStream, TwilioError> {
+        let transport = WebSocketTransport::connect(TWILSOCK_URL).await?;
+        self.subscribe_with(params, transport).await
+    }
+
+    /// Subscribes to changes to the Document provided to the `document()` argument over the
+    /// provided transport rather than a WebSocket to Twilio.
+    pub async fn subscribe_with<T>(
+        &self,
+        params: SubscribeParams,
+        transport: T,
+    ) -> Result<EventStream, TwilioError>
+    where
+        T: Transport + 'static,
+    {
+        // Fetch the Document so unique names are resolved to SIDs for the subscription.
+        let document = self.get().await?;
+        let token =
+            subscription::sync_token(self.client.account_sid(), &document.service_sid, &params)?;
+
+        subscription::open(transport, &token, ObjectType::Document, &document.sid).await
+    }
+
+    /// Copies the Document provided to the `document()` argument into the `target`
+    /// Sync Service, which may belong to another account.
+    ///
+    /// The copy keeps the data and remaining TTL and uses the `unique_name` of the
+    /// params if provided. If a Document with that unique name already exists in the
+    /// target it is handled according to the `conflict_policy`.
+    pub async fn copy_to(
+        &self,
+        target: &Service<'_, '_>,
+        params: CopyParams,
+    ) -> Result<RestoreReport, TwilioError> {
+        let mut record = DocumentRecord::from(&self.get().await?);
+        if let Some(unique_name) = params.unique_name.clone() {
+            record.unique_name = unique_name;
+        }
+
+        let objects = SyncObjects {
+            documents: vec![record],
+            ..Default::default()
+        };
+
+        snapshot::restore(target, &objects, params.restore_params()).await
+    }
+
     /// [Deletes a Sync Service](https://www.twilio.com/docs/sync/api/service#delete-a-service-resourcee)
     ///
     /// Targets the Sync Service provided to the `service()` argument and deletes the Document
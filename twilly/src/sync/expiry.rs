@@ -0,0 +1,312 @@
+/*!
+
+Contains Sync TTL helpers and expiry reporting.
+
+TTLs are given as a `Duration` and sent to Twilio in whole seconds. Documents,
+Map Items and List Items that are due to expire within a window can be found
+with `Service::expiring` and have their TTL extended in bulk with
+`Service::extend_ttl`.
+
+*/
+
+use std::{fmt, time::Duration};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use serde_with::{serde_as, DurationSeconds};
+
+use crate::TwilioError;
+
+use super::{listitems::ListParams as ListItemParams, mapitems::ListParams, services::Service};
+
+/// The longest TTL Twilio accepts for Sync resources (one year).
+pub const MAX_TTL: Duration = Duration::from_secs(31_536_000);
+
+/// A Document, Map Item or List Item that is due to expire.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Expiring {
+    #[serde(flatten)]
+    pub resource: ExpiringResource,
+    pub date_expires: DateTime<Utc>,
+}
+
+/// Identifies a resource with a TTL.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExpiringResource {
+    Document {
+        sid: String,
+        unique_name: String,
+    },
+    MapItem {
+        map_sid: String,
+        map_name: String,
+        key: String,
+    },
+    ListItem {
+        list_sid: String,
+        list_name: String,
+        index: u32,
+    },
+}
+
+impl fmt::Display for ExpiringResource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpiringResource::Document { unique_name, .. } => {
+                write!(f, "Document '{}'", unique_name)
+            }
+            ExpiringResource::MapItem { map_name, key, .. } => {
+                write!(f, "Map Item '{}' in '{}'", key, map_name)
+            }
+            ExpiringResource::ListItem {
+                list_name, index, ..
+            } => write!(f, "List Item {} in '{}'", index, list_name),
+        }
+    }
+}
+
+/// A resource whose TTL could not be extended.
+#[derive(Debug)]
+pub struct ExtendFailure {
+    pub resource: ExpiringResource,
+    pub error: TwilioError,
+}
+
+/// Summary of a bulk TTL extension.
+#[derive(Debug, Default)]
+pub struct ExtendReport {
+    pub extended: usize,
+    pub failed: Vec<ExtendFailure>,
+}
+
+/// Parameters for updating only the TTL of a resource.
+#[serde_as]
+#[derive(Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub(crate) struct TtlParams {
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub ttl: Duration,
+}
+
+// Validates that a TTL is a whole number of seconds no longer than `MAX_TTL`.
+pub(crate) fn validate_ttl(ttl: Option<Duration>) -> Result<(), TwilioError> {
+    match ttl {
+        Some(ttl) if ttl > MAX_TTL => Err(TwilioError {
+            kind: crate::ErrorKind::ValidationError(format!(
+                "TTL of {} seconds exceeds the maximum of {} seconds",
+                ttl.as_secs(),
+                MAX_TTL.as_secs()
+            )),
+        }),
+        Some(ttl) if ttl.subsec_nanos() != 0 => Err(TwilioError {
+            kind: crate::ErrorKind::ValidationError(String::from(
+                "TTL must be a whole number of seconds",
+            )),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Keeps the resources expiring before the cutoff, soonest first.
+fn expiring_before(mut expiring: Vec<Expiring>, cutoff: DateTime<Utc>) -> Vec<Expiring> {
+    expiring.retain(|expiring| expiring.date_expires <= cutoff);
+    expiring.sort_by_key(|expiring| expiring.date_expires);
+    expiring
+}
+
+// The end of a window starting now. Windows reaching past the latest representable time
+// are unbounded rather than overflowing.
+fn cutoff_after(now: DateTime<Utc>, within: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(within)
+        .ok()
+        .and_then(|within| now.checked_add_signed(within))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+// Finds every Document, Map Item and List Item in the Service expiring within the window.
+pub(crate) async fn find_expiring(
+    service: &Service<'_, '_>,
+    within: Duration,
+) -> Result<Vec<Expiring>, TwilioError> {
+    let cutoff = cutoff_after(Utc::now(), within);
+
+    let mut expiring: Vec<Expiring> = service
+        .documents()
+        .list()
+        .await?
+        .into_iter()
+        .filter_map(|document| {
+            Some(Expiring {
+                date_expires: document.date_expires?,
+                resource: ExpiringResource::Document {
+                    sid: document.sid,
+                    unique_name: document.unique_name,
+                },
+            })
+        })
+        .collect();
+
+    for map in service.maps().list().await? {
+        let map_items = service
+            .map(&map.sid)
+            .mapitems()
+            .list(ListParams {
+                order: None,
+                from: None,
+                bounds: None,
+            })
+            .await?;
+
+        expiring.extend(map_items.into_iter().filter_map(|map_item| {
+            Some(Expiring {
+                date_expires: map_item.date_expires?,
+                resource: ExpiringResource::MapItem {
+                    map_sid: map.sid.clone(),
+                    map_name: map.unique_name.clone(),
+                    key: map_item.key,
+                },
+            })
+        }));
+    }
+
+    for list in service.lists().list().await? {
+        let list_items = service
+            .list(&list.sid)
+            .listitems()
+            .list(ListItemParams {
+                order: None,
+                from: None,
+                bounds: None,
+            })
+            .await?;
+
+        expiring.extend(list_items.into_iter().filter_map(|list_item| {
+            Some(Expiring {
+                date_expires: list_item.date_expires?,
+                resource: ExpiringResource::ListItem {
+                    list_sid: list.sid.clone(),
+                    list_name: list.unique_name.clone(),
+                    index: list_item.index,
+                },
+            })
+        }));
+    }
+
+    Ok(expiring_before(expiring, cutoff))
+}
+
+// Sets the TTL of every resource to `ttl`, running up to `concurrency` updates at once.
+pub(crate) async fn extend(
+    service: &Service<'_, '_>,
+    resources: &[ExpiringResource],
+    ttl: Duration,
+    concurrency: usize,
+) -> Result<ExtendReport, TwilioError> {
+    validate_ttl(Some(ttl))?;
+
+    let mut updates = stream::iter(resources.iter().map(|resource| async move {
+        let outcome = match resource {
+            ExpiringResource::Document { sid, .. } => {
+                service.document(sid).update_ttl(ttl).await.map(|_| ())
+            }
+            ExpiringResource::MapItem { map_sid, key, .. } => service
+                .map(map_sid)
+                .mapitem(key)
+                .update_ttl(ttl)
+                .await
+                .map(|_| ()),
+            ExpiringResource::ListItem {
+                list_sid, index, ..
+            } => service
+                .list(list_sid)
+                .listitem(index)
+                .update_ttl(ttl)
+                .await
+                .map(|_| ()),
+        };
+        (resource, outcome)
+    }))
+    .buffer_unordered(concurrency.max(1));
+
+    let mut report = ExtendReport::default();
+    while let Some((resource, outcome)) = updates.next().await {
+        match outcome {
+            Ok(()) => report.extended += 1,
+            Err(error) => report.failed.push(ExtendFailure {
+                resource: resource.clone(),
+                error,
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(unique_name: &str, date_expires: &str) -> Expiring {
+        Expiring {
+            resource: ExpiringResource::Document {
+                sid: String::from("ETxx"),
+                unique_name: unique_name.to_string(),
+            },
+            date_expires: date_expires.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn validate_ttl_limits() {
+        assert!(validate_ttl(None).is_ok());
+        assert!(validate_ttl(Some(MAX_TTL)).is_ok());
+        assert!(validate_ttl(Some(MAX_TTL + Duration::from_secs(1))).is_err());
+        assert!(validate_ttl(Some(Duration::from_millis(1500))).is_err());
+    }
+
+    #[test]
+    fn cutoff_after_saturates() {
+        let now: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            "2024-01-02T00:00:00Z".parse::<DateTime<Utc>>().unwrap(),
+            cutoff_after(now, Duration::from_secs(86400))
+        );
+        assert_eq!(DateTime::<Utc>::MAX_UTC, cutoff_after(now, Duration::MAX));
+        assert_eq!(
+            DateTime::<Utc>::MAX_UTC,
+            cutoff_after(now, Duration::from_secs(i64::MAX as u64 / 1000))
+        );
+    }
+
+    #[test]
+    fn expiring_before_filters_and_sorts() {
+        let expiring = expiring_before(
+            vec![
+                document("later", "2024-01-03T00:00:00Z"),
+                document("outside", "2024-02-01T00:00:00Z"),
+                document("sooner", "2024-01-02T00:00:00Z"),
+            ],
+            "2024-01-10T00:00:00Z".parse().unwrap(),
+        );
+
+        let names: Vec<String> = expiring
+            .iter()
+            .map(|expiring| expiring.resource.to_string())
+            .collect();
+        assert_eq!(vec!["Document 'sooner'", "Document 'later'"], names);
+    }
+
+    #[test]
+    fn ttl_params_serialize_as_seconds() {
+        let params = TtlParams {
+            ttl: Duration::from_secs(3600),
+        };
+        assert_eq!(
+            serde_json::json!({ "Ttl": 3600 }),
+            serde_json::to_value(params).unwrap()
+        );
+    }
+}
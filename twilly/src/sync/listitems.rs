@@ -4,11 +4,27 @@ Contains Twilio Sync List Item related functionality.
 
 */
 
+use std::{
+    collections::HashSet,
+    ops::{Bound, RangeBounds},
+    time::Duration,
+};
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
 use reqwest::{header::HeaderMap, Method};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use serde_with::skip_serializing_none;
+use serde_with::{serde_as, skip_serializing_none, DurationSeconds};
+
+use super::{
+    expiry::{validate_ttl, TtlParams},
+    transfer::{
+        conflict_error, record_ttl, ConflictPolicy, ImportOutcome, ImportParams, ImportProgress,
+        ImportReport, ListItemRecord,
+    },
+};
 
 /// Represents a page of Sync List Items from the Twilio API.
 #[allow(dead_code)]
@@ -29,7 +45,7 @@ pub struct SyncListItem {
     pub data: Value,
     pub date_created: String,
     pub date_updated: String,
-    pub date_expires: Option<String>,
+    pub date_expires: Option<DateTime<Utc>>,
     /// Identity of the creator. Uses the identity of the
     /// respective client or defaults to `system` if created via REST.
     pub created_by: String,
@@ -42,23 +58,26 @@ where
     T: ?Sized + Serialize,
 {
     pub data: &'a T,
-    /// How long the List Item should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
-    /// How long the *parent* List resource should exist before deletion (in seconds).
-    pub collection_ttl: Option<u16>,
+    /// How long the List Item should exist before deletion.
+    pub ttl: Option<Duration>,
+    /// How long the *parent* List resource should exist before deletion.
+    pub collection_ttl: Option<Duration>,
 }
 
 /// Parameters for creating a Sync List with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 struct CreateParamsWithJson {
     data: String,
-    /// How long the List Item should exist before deletion (in seconds).
-    ttl: Option<u16>,
-    /// How long the *parent* List resource should exist before deletion (in seconds).
-    collection_ttl: Option<u16>,
+    /// How long the List Item should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
+    /// How long the *parent* List resource should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    collection_ttl: Option<Duration>,
 }
 
 #[derive(Serialize)]
@@ -93,15 +112,16 @@ where
 {
     pub if_match: Option<String>,
     pub data: &'a T,
-    /// How long the List Item should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
-    /// How long the *parent* List resource should exist before deletion (in seconds). Can only be used
+    /// How long the List Item should exist before deletion.
+    pub ttl: Option<Duration>,
+    /// How long the *parent* List resource should exist before deletion. Can only be used
     /// if the `data` or `ttl` is updated in the same request.
-    pub collection_ttl: Option<u16>,
+    pub collection_ttl: Option<Duration>,
 }
 
 /// Parameters for creating a Sync List with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
@@ -109,11 +129,13 @@ struct UpdateParamsWithJson {
     #[serde(rename(serialize = "If-Match"))]
     if_match: Option<String>,
     data: String,
-    /// How long the List Item should exist before deletion (in seconds).
-    ttl: Option<u16>,
-    /// How long the *parent* List resource should exist before deletion (in seconds). Can only be used
+    /// How long the List Item should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
+    /// How long the *parent* List resource should exist before deletion. Can only be used
     /// if the `data` or `ttl` is updated in the same request.
-    collection_ttl: Option<u16>,
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    collection_ttl: Option<Duration>,
 }
 
 pub struct ListItems<'a, 'b> {
@@ -130,6 +152,9 @@ impl<'a, 'b> ListItems<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+        validate_ttl(params.collection_ttl)?;
+
         // Create a new struct with the provided data parameter converted to a
         // JSON string as required by Twilio.
         let params = CreateParamsWithJson {
@@ -192,6 +217,230 @@ impl<'a, 'b> ListItems<'a, 'b> {
 
         Ok(results)
     }
+
+    /// Lists the List Items with an index inside of `indexes`, e.g. `10..20` or `5..`.
+    ///
+    /// Items are read in ascending order starting from the start of the range, and
+    /// paging stops as soon as the end of the range is passed rather than loading the
+    /// whole List.
+    pub async fn list_range<R>(&self, indexes: R) -> Result<Vec<SyncListItem>, TwilioError>
+    where
+        R: RangeBounds<u32>,
+    {
+        let mut list_items_page = self
+            .client
+            .send_request::<ListItemPage, ListParams>(
+                Method::GET,
+                &format!(
+                    "https://sync.twilio.com/v1/Services/{}/Lists/{}/Items?PageSize=50",
+                    self.service_sid, self.list_sid
+                ),
+                Some(&range_params(indexes.start_bound())),
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<SyncListItem> = Vec::new();
+
+        loop {
+            for list_item in list_items_page.items {
+                if !before_end(list_item.index, indexes.end_bound()) {
+                    return Ok(results);
+                }
+                results.push(list_item);
+            }
+
+            match list_items_page.meta.next_page_url {
+                Some(next_page_url) => {
+                    list_items_page = self
+                        .client
+                        .send_request::<ListItemPage, ()>(Method::GET, &next_page_url, None, None)
+                        .await?;
+                }
+                None => return Ok(results),
+            }
+        }
+    }
+
+    /// Lists List Items and keeps those whose data can be read as `T` and matches the
+    /// `predicate`. Items with data of a different shape are left out.
+    ///
+    /// Filtering happens client-side so every item described by `params` is fetched.
+    pub async fn filter<T, F>(
+        &self,
+        params: ListParams,
+        predicate: F,
+    ) -> Result<Vec<SyncListItem>, TwilioError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> bool,
+    {
+        let mut list_items = self.list(params).await?;
+        list_items.retain(|list_item| {
+            T::deserialize(&list_item.data)
+                .map(|data| predicate(&data))
+                .unwrap_or(false)
+        });
+
+        Ok(list_items)
+    }
+
+    /// Exports every List Item in the targeted Service and List, ordered by index, as
+    /// records that can be written to a file with `transfer::write_records` and later
+    /// imported.
+    pub async fn export(&self) -> Result<Vec<ListItemRecord>, TwilioError> {
+        let mut list_items = self
+            .list(ListParams {
+                order: Some(Order::Asc),
+                from: None,
+                bounds: None,
+            })
+            .await?;
+        list_items.sort_by_key(|list_item| list_item.index);
+
+        Ok(list_items.iter().map(ListItemRecord::from).collect())
+    }
+
+    /// Imports records into the targeted Service and List.
+    ///
+    /// Records whose index already exists in the List are handled according to the
+    /// `conflict_policy`, with up to `concurrency` requests in flight at once. With
+    /// `ConflictPolicy::Fail` the import is rejected before any item is written.
+    ///
+    /// All other records are appended one at a time in index order so the List keeps
+    /// the order of the file. Twilio assigns indexes to appended items so these may
+    /// differ from the indexes in the records.
+    ///
+    /// `on_progress` is called after each record is processed. Failures of individual
+    /// records do not stop the import and are collected in the returned report.
+    pub async fn import<F>(
+        &self,
+        mut records: Vec<ListItemRecord>,
+        params: ImportParams,
+        mut on_progress: F,
+    ) -> Result<ImportReport, TwilioError>
+    where
+        F: FnMut(ImportProgress),
+    {
+        let existing_indexes: HashSet<u32> = self
+            .list(ListParams {
+                order: None,
+                from: None,
+                bounds: None,
+            })
+            .await?
+            .into_iter()
+            .map(|list_item| list_item.index)
+            .collect();
+
+        records.sort_by_key(|record| record.index);
+        let (conflicting, appended): (Vec<ListItemRecord>, Vec<ListItemRecord>) = records
+            .into_iter()
+            .partition(|record| existing_indexes.contains(&record.index));
+
+        if params.conflict_policy == ConflictPolicy::Fail && !conflicting.is_empty() {
+            let conflicting_indexes: Vec<String> = conflicting
+                .iter()
+                .map(|record| record.index.to_string())
+                .collect();
+
+            return Err(conflict_error(&conflicting_indexes));
+        }
+
+        let total = conflicting.len() + appended.len();
+        let mut report = ImportReport::default();
+        let mut processed = 0;
+
+        let mut outcomes = stream::iter(conflicting.into_iter().map(|record| async move {
+            let index = record.index;
+            let outcome = self
+                .import_conflicting_record(record, params.conflict_policy)
+                .await;
+            (index, outcome)
+        }))
+        .buffer_unordered(params.concurrency.max(1));
+
+        while let Some((index, outcome)) = outcomes.next().await {
+            report.record(index.to_string(), outcome);
+            processed += 1;
+            on_progress(ImportProgress { processed, total });
+        }
+
+        for record in appended {
+            let index = record.index;
+            let outcome = match record_ttl(record.ttl) {
+                Ok(ttl) => self
+                    .create(CreateParams {
+                        data: &record.data,
+                        ttl,
+                        collection_ttl: None,
+                    })
+                    .await
+                    .map(|_| ImportOutcome::Created),
+                Err(error) => Err(error),
+            };
+
+            report.record(index.to_string(), outcome);
+            processed += 1;
+            on_progress(ImportProgress { processed, total });
+        }
+
+        Ok(report)
+    }
+
+    // Applies the conflict policy to a record whose index already exists in the List.
+    async fn import_conflicting_record(
+        &self,
+        record: ListItemRecord,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<ImportOutcome, TwilioError> {
+        match conflict_policy {
+            ConflictPolicy::Skip => Ok(ImportOutcome::Skipped),
+            ConflictPolicy::Overwrite => {
+                ListItem {
+                    client: self.client,
+                    service_sid: self.service_sid,
+                    list_sid: self.list_sid,
+                    index: &record.index,
+                }
+                .update(UpdateParams {
+                    if_match: None,
+                    data: &record.data,
+                    ttl: record_ttl(record.ttl)?,
+                    collection_ttl: None,
+                })
+                .await?;
+
+                Ok(ImportOutcome::Updated)
+            }
+            ConflictPolicy::Fail => Err(conflict_error(&[record.index.to_string()])),
+        }
+    }
+}
+
+// Builds the parameters to read List Items in ascending order from the start of
+// a range.
+fn range_params(start: Bound<&u32>) -> ListParams {
+    let (from, bounds) = match start {
+        Bound::Included(index) => (Some(index.to_string()), Some(Bounds::Inclusive)),
+        Bound::Excluded(index) => (Some(index.to_string()), Some(Bounds::Exclusive)),
+        Bound::Unbounded => (None, None),
+    };
+
+    ListParams {
+        order: Some(Order::Asc),
+        from,
+        bounds,
+    }
+}
+
+// Whether an index has not yet passed the end of a range.
+fn before_end(index: u32, end: Bound<&u32>) -> bool {
+    match end {
+        Bound::Included(end) => index <= *end,
+        Bound::Excluded(end) => index < *end,
+        Bound::Unbounded => true,
+    }
 }
 
 pub struct ListItem<'a, 'b> {
@@ -229,6 +478,9 @@ impl<'a, 'b> ListItem<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+        validate_ttl(params.collection_ttl)?;
+
         // Create a new struct with the provided data parameter converted to a
         // JSON string as required by Twilio.
         let params = UpdateParamsWithJson {
@@ -257,6 +509,23 @@ impl<'a, 'b> ListItem<'a, 'b> {
             .await
     }
 
+    /// Sets only the TTL of the List Item, leaving its data untouched.
+    pub async fn update_ttl(&self, ttl: Duration) -> Result<SyncListItem, TwilioError> {
+        validate_ttl(Some(ttl))?;
+
+        self.client
+            .send_request::<SyncListItem, TtlParams>(
+                Method::POST,
+                &format!(
+                    "https://sync.twilio.com/v1/Services/{}/Lists/{}/Items/{}",
+                    self.service_sid, self.list_sid, self.index
+                ),
+                Some(&TtlParams { ttl }),
+                None,
+            )
+            .await
+    }
+
     /// [Deletes a Sync List Item](https://www.twilio.com/docs/sync/api/listitem-resource#delete-a-listitem-resource)
     ///
     /// Targets the Sync Service provided to the `service()` argument, the List provided to the `list()`
@@ -275,3 +544,29 @@ impl<'a, 'b> ListItem<'a, 'b> {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_params_from_start_bound() {
+        let params = range_params((5..10).start_bound());
+        assert_eq!(Some(String::from("5")), params.from);
+        assert!(matches!(params.bounds, Some(Bounds::Inclusive)));
+
+        let params = range_params((Bound::Excluded(5), Bound::Unbounded).start_bound());
+        assert!(matches!(params.bounds, Some(Bounds::Exclusive)));
+
+        let params = range_params((..10).start_bound());
+        assert_eq!(None, params.from);
+    }
+
+    #[test]
+    fn before_end_respects_end_bound() {
+        assert!(before_end(9, (0..10).end_bound()));
+        assert!(!before_end(10, (0..10).end_bound()));
+        assert!(before_end(10, (0..=10).end_bound()));
+        assert!(before_end(u32::MAX, (0..).end_bound()));
+    }
+}
@@ -4,12 +4,22 @@ Contains Twilio Sync List related functionality.
 
 */
 
+use std::time::Duration;
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{serde_as, skip_serializing_none, DurationSeconds};
 
-use super::listitems::{ListItem, ListItems};
+use super::{
+    copy::CopyParams,
+    expiry::validate_ttl,
+    listitems::{ListItem, ListItems},
+    services::Service,
+    snapshot::{self, ListRecord, RestoreReport, SyncObjects},
+    transfer::remaining_ttl,
+};
 
 /// Represents a page of Sync Lists from the Twilio API.
 #[allow(dead_code)]
@@ -29,7 +39,7 @@ pub struct SyncList {
     pub url: String,
     pub date_created: String,
     pub date_updated: String,
-    pub date_expires: Option<String>,
+    pub date_expires: Option<DateTime<Utc>>,
     /// Identity of the creator. Uses the identity of the
     /// respective client or defaults to `system` if created via REST.
     pub created_by: String,
@@ -45,20 +55,26 @@ pub struct Links {
 }
 
 /// Parameters for creating a Sync List
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 pub struct CreateParams {
     pub unique_name: Option<String>,
-    pub ttl: Option<bool>,
+    /// How long the List should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub ttl: Option<Duration>,
 }
 
 /// Parameters for updating a Sync List
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 pub struct UpdateParams {
-    pub ttl: Option<bool>,
+    /// How long the List should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub ttl: Option<Duration>,
 }
 
 pub struct Lists<'a, 'b> {
@@ -71,6 +87,8 @@ impl<'a, 'b> Lists<'a, 'b> {
     ///
     /// Creates a Sync List resource with the provided parameters.
     pub async fn create(&self, params: CreateParams) -> Result<SyncList, TwilioError> {
+        validate_ttl(params.ttl)?;
+
         self.client
             .send_request::<SyncList, CreateParams>(
                 Method::POST,
@@ -154,6 +172,8 @@ impl<'a, 'b> List<'a, 'b> {
     /// Targets the Sync Service provided to the `service()` argument  and updates the List
     /// provided to the `list()` argument.
     pub async fn update(&self, params: UpdateParams) -> Result<SyncList, TwilioError> {
+        validate_ttl(params.ttl)?;
+
         self.client
             .send_request::<SyncList, UpdateParams>(
                 Method::POST,
@@ -167,6 +187,32 @@ impl<'a, 'b> List<'a, 'b> {
             .await
     }
 
+    /// Copies the List provided to the `list()` argument, including its items, into the
+    /// `target` Sync Service, which may belong to another account.
+    ///
+    /// The copy keeps the remaining TTLs and uses the `unique_name` of the params if
+    /// provided. If a List with that unique name already exists in the target it is
+    /// handled according to the `conflict_policy`.
+    pub async fn copy_to(
+        &self,
+        target: &Service<'_, '_>,
+        params: CopyParams,
+    ) -> Result<RestoreReport, TwilioError> {
+        let sync_list = self.get().await?;
+        let record = ListRecord {
+            unique_name: params.unique_name.clone().unwrap_or(sync_list.unique_name),
+            ttl: remaining_ttl(sync_list.date_expires),
+            items: self.listitems().export().await?,
+        };
+
+        let objects = SyncObjects {
+            lists: vec![record],
+            ..Default::default()
+        };
+
+        snapshot::restore(target, &objects, params.restore_params()).await
+    }
+
     /// [Deletes a Sync List](https://www.twilio.com/docs/sync/api/list-resource#delete-a-list-resource)
     ///
     /// Targets the Sync Service provided to the `service()` argument and deletes the List
@@ -190,7 +236,7 @@ impl<'a, 'b> List<'a, 'b> {
     /// Functions relating to a known Sync List Item.
     ///
     /// Takes in the key of the Sync List Item to perform actions against.
-    pub fn listitem(&'a self, index: &'b u32) -> ListItem {
+    pub fn listitem(&'a self, index: &'b u32) -> ListItem<'a, 'b> {
         ListItem {
             client: self.client,
             service_sid: self.service_sid,
@@ -200,7 +246,7 @@ impl<'a, 'b> List<'a, 'b> {
     }
 
     /// General Sync Map Item functions.
-    pub fn listitems(&'a self) -> ListItems {
+    pub fn listitems(&'a self) -> ListItems<'a, 'b> {
         ListItems {
             client: self.client,
             service_sid: self.service_sid,
@@ -4,11 +4,23 @@ Contains Twilio Sync Map Item related functionality.
 
 */
 
+use std::{collections::HashSet, time::Duration};
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
 use reqwest::{header::HeaderMap, Method};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use serde_with::skip_serializing_none;
+use serde_with::{serde_as, skip_serializing_none, DurationSeconds};
+
+use super::{
+    expiry::{validate_ttl, TtlParams},
+    transfer::{
+        conflict_error, record_ttl, ConflictPolicy, ImportOutcome, ImportParams, ImportProgress,
+        ImportReport, MapItemRecord,
+    },
+};
 
 /// Represents a page of Sync Map Items from the Twilio API.
 #[allow(dead_code)]
@@ -29,7 +41,7 @@ pub struct SyncMapItem {
     pub data: Value,
     pub date_created: String,
     pub date_updated: String,
-    pub date_expires: Option<String>,
+    pub date_expires: Option<DateTime<Utc>>,
     /// Identity of the creator. Uses the identity of the
     /// respective client or defaults to `system` if created via REST.
     pub created_by: String,
@@ -46,14 +58,15 @@ where
     pub key: String,
     /// Any value that can be represented as JSON
     pub data: &'a T,
-    /// How long the Map Item should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
-    /// How long the *parent* Map resource should exist before deletion (in seconds).
-    pub collection_ttl: Option<u16>,
+    /// How long the Map Item should exist before deletion.
+    pub ttl: Option<Duration>,
+    /// How long the *parent* Map resource should exist before deletion.
+    pub collection_ttl: Option<Duration>,
 }
 
 /// Parameters for creating a Sync Map Item with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
@@ -61,10 +74,12 @@ struct CreateParamsWithJson {
     key: String,
     /// JSON string of data
     data: String,
-    /// How long the Map Item should exist before deletion (in seconds).
-    ttl: Option<u16>,
-    /// How long the *parent* Map resource should exist before deletion (in seconds).
-    collection_ttl: Option<u16>,
+    /// How long the Map Item should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
+    /// How long the *parent* Map resource should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    collection_ttl: Option<Duration>,
 }
 
 #[derive(Serialize)]
@@ -100,15 +115,16 @@ where
     pub if_match: Option<String>,
     /// Any value that can be represented as JSON
     pub data: &'a T,
-    /// How long the Map Item should exist before deletion (in seconds).
-    pub ttl: Option<u16>,
-    /// How long the *parent* Map resource should exist before deletion (in seconds). Can only be used
+    /// How long the Map Item should exist before deletion.
+    pub ttl: Option<Duration>,
+    /// How long the *parent* Map resource should exist before deletion. Can only be used
     /// if the `data` or `ttl` is updated in the same request.
-    pub collection_ttl: Option<u16>,
+    pub collection_ttl: Option<Duration>,
 }
 
 /// Parameters for updating a Sync Map Item with
 /// data converted to a JSON string
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
@@ -117,11 +133,13 @@ struct UpdateParamsWithJson {
     if_match: Option<String>,
     /// Any value that can be represented as JSON
     data: String,
-    /// How long the Map Item should exist before deletion (in seconds).
-    ttl: Option<u16>,
-    /// How long the *parent* Map resource should exist before deletion (in seconds). Can only be used
+    /// How long the Map Item should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    ttl: Option<Duration>,
+    /// How long the *parent* Map resource should exist before deletion. Can only be used
     /// if the `data` or `ttl` is updated in the same request.
-    collection_ttl: Option<u16>,
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    collection_ttl: Option<Duration>,
 }
 
 pub struct MapItems<'a, 'b> {
@@ -138,6 +156,9 @@ impl<'a, 'b> MapItems<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+        validate_ttl(params.collection_ttl)?;
+
         // Create a new struct with the provided data parameter converted to a
         // JSON string as required by Twilio.
         let params = CreateParamsWithJson {
@@ -201,6 +222,199 @@ impl<'a, 'b> MapItems<'a, 'b> {
 
         Ok(results)
     }
+
+    /// Lists the Map Items whose key starts with `prefix`.
+    ///
+    /// Keys are read in ascending order starting from `prefix`, and paging stops as
+    /// soon as a key outside of the prefix is reached rather than loading the whole Map.
+    /// An empty prefix lists every Map Item.
+    pub async fn list_with_prefix(&self, prefix: &str) -> Result<Vec<SyncMapItem>, TwilioError> {
+        let params = ListParams {
+            order: Some(Order::Asc),
+            from: (!prefix.is_empty()).then(|| prefix.to_string()),
+            bounds: Some(Bounds::Inclusive),
+        };
+
+        let mut map_items_page = self
+            .client
+            .send_request::<MapItemPage, ListParams>(
+                Method::GET,
+                &format!(
+                    "https://sync.twilio.com/v1/Services/{}/Maps/{}/Items?PageSize=50",
+                    self.service_sid, self.map_sid
+                ),
+                Some(&params),
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<SyncMapItem> = Vec::new();
+
+        loop {
+            for map_item in map_items_page.items {
+                if !map_item.key.starts_with(prefix) {
+                    return Ok(results);
+                }
+                results.push(map_item);
+            }
+
+            match map_items_page.meta.next_page_url {
+                Some(next_page_url) => {
+                    map_items_page = self
+                        .client
+                        .send_request::<MapItemPage, ()>(Method::GET, &next_page_url, None, None)
+                        .await?;
+                }
+                None => return Ok(results),
+            }
+        }
+    }
+
+    /// Lists Map Items and keeps those whose data can be read as `T` and matches the
+    /// `predicate`. Items with data of a different shape are left out.
+    ///
+    /// Filtering happens client-side so every item described by `params` is fetched.
+    pub async fn filter<T, F>(
+        &self,
+        params: ListParams,
+        predicate: F,
+    ) -> Result<Vec<SyncMapItem>, TwilioError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> bool,
+    {
+        let mut map_items = self.list(params).await?;
+        map_items.retain(|map_item| {
+            T::deserialize(&map_item.data)
+                .map(|data| predicate(&data))
+                .unwrap_or(false)
+        });
+
+        Ok(map_items)
+    }
+
+    /// Exports every Map Item in the targeted Service and Map as records that can
+    /// be written to a file with `transfer::write_records` and later imported.
+    pub async fn export(&self) -> Result<Vec<MapItemRecord>, TwilioError> {
+        let map_items = self
+            .list(ListParams {
+                order: None,
+                from: None,
+                bounds: None,
+            })
+            .await?;
+
+        Ok(map_items.iter().map(MapItemRecord::from).collect())
+    }
+
+    /// Imports records into the targeted Service and Map.
+    ///
+    /// Records whose key already exists in the Map are handled according to the
+    /// `conflict_policy`. With `ConflictPolicy::Fail` the import is rejected before any
+    /// item is written. At most `concurrency` requests are in flight at once and
+    /// `on_progress` is called after each record is processed.
+    ///
+    /// Failures of individual records do not stop the import and are collected in
+    /// the returned report.
+    pub async fn import<F>(
+        &self,
+        records: Vec<MapItemRecord>,
+        params: ImportParams,
+        mut on_progress: F,
+    ) -> Result<ImportReport, TwilioError>
+    where
+        F: FnMut(ImportProgress),
+    {
+        let existing_keys: HashSet<String> = self
+            .list(ListParams {
+                order: None,
+                from: None,
+                bounds: None,
+            })
+            .await?
+            .into_iter()
+            .map(|map_item| map_item.key)
+            .collect();
+
+        if params.conflict_policy == ConflictPolicy::Fail {
+            let conflicting_keys: Vec<String> = records
+                .iter()
+                .filter(|record| existing_keys.contains(&record.key))
+                .map(|record| record.key.clone())
+                .collect();
+
+            if !conflicting_keys.is_empty() {
+                return Err(conflict_error(&conflicting_keys));
+            }
+        }
+
+        let total = records.len();
+        let mut report = ImportReport::default();
+        let mut outcomes = stream::iter(records.into_iter().map(|record| {
+            let exists = existing_keys.contains(&record.key);
+            async move {
+                let key = record.key.clone();
+                let outcome = self
+                    .import_record(record, exists, params.conflict_policy)
+                    .await;
+                (key, outcome)
+            }
+        }))
+        .buffer_unordered(params.concurrency.max(1));
+
+        let mut processed = 0;
+        while let Some((key, outcome)) = outcomes.next().await {
+            report.record(key, outcome);
+            processed += 1;
+            on_progress(ImportProgress { processed, total });
+        }
+
+        Ok(report)
+    }
+
+    // Creates or updates a single Map Item from a record.
+    async fn import_record(
+        &self,
+        record: MapItemRecord,
+        exists: bool,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<ImportOutcome, TwilioError> {
+        let ttl = record_ttl(record.ttl)?;
+
+        if !exists {
+            self.create(CreateParams {
+                key: record.key,
+                data: &record.data,
+                ttl,
+                collection_ttl: None,
+            })
+            .await?;
+
+            return Ok(ImportOutcome::Created);
+        }
+
+        match conflict_policy {
+            ConflictPolicy::Skip => Ok(ImportOutcome::Skipped),
+            ConflictPolicy::Overwrite => {
+                MapItem {
+                    client: self.client,
+                    service_sid: self.service_sid,
+                    map_sid: self.map_sid,
+                    key: &record.key,
+                }
+                .update(UpdateParams {
+                    if_match: None,
+                    data: &record.data,
+                    ttl,
+                    collection_ttl: None,
+                })
+                .await?;
+
+                Ok(ImportOutcome::Updated)
+            }
+            ConflictPolicy::Fail => Err(conflict_error(&[record.key])),
+        }
+    }
 }
 
 pub struct MapItem<'a, 'b> {
@@ -238,6 +452,9 @@ impl<'a, 'b> MapItem<'a, 'b> {
     where
         T: ?Sized + Serialize,
     {
+        validate_ttl(params.ttl)?;
+        validate_ttl(params.collection_ttl)?;
+
         // Create a new struct with the provided data parameter converted to a
         // JSON string as required by Twilio.
         let params = UpdateParamsWithJson {
@@ -267,6 +484,23 @@ impl<'a, 'b> MapItem<'a, 'b> {
             .await
     }
 
+    /// Sets only the TTL of the Map Item, leaving its data untouched.
+    pub async fn update_ttl(&self, ttl: Duration) -> Result<SyncMapItem, TwilioError> {
+        validate_ttl(Some(ttl))?;
+
+        self.client
+            .send_request::<SyncMapItem, TtlParams>(
+                Method::POST,
+                &format!(
+                    "https://sync.twilio.com/v1/Services/{}/Maps/{}/Items/{}",
+                    self.service_sid, self.map_sid, self.key
+                ),
+                Some(&TtlParams { ttl }),
+                None,
+            )
+            .await
+    }
+
     /// [Deletes a Sync Map Item](https://www.twilio.com/docs/sync/api/map-item-resource#delete-a-mapitem-resource)
     ///
     /// Targets the Sync Service provided to the `service()` argument, the Map provided to the `map()`
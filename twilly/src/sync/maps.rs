@@ -4,12 +4,25 @@ Contains Twilio Sync Map related functionality.
 
 */
 
+use std::time::Duration;
+
 use crate::{Client, PageMeta, TwilioError};
+use chrono::{DateTime, Utc};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{serde_as, skip_serializing_none, DurationSeconds};
 
-use super::mapitems::{MapItem, MapItems};
+use super::{
+    copy::CopyParams,
+    expiry::validate_ttl,
+    mapitems::{MapItem, MapItems},
+    services::Service,
+    snapshot::{self, MapRecord, RestoreReport, SyncObjects},
+    subscription::{
+        self, EventStream, ObjectType, SubscribeParams, Transport, WebSocketTransport, TWILSOCK_URL,
+    },
+    transfer::remaining_ttl,
+};
 
 /// Represents a page of Sync Maps from the Twilio API.
 #[allow(dead_code)]
@@ -29,7 +42,7 @@ pub struct SyncMap {
     pub url: String,
     pub date_created: String,
     pub date_updated: String,
-    pub date_expires: Option<String>,
+    pub date_expires: Option<DateTime<Utc>>,
     /// Identity of the creator. Uses the identity of the
     /// respective client or defaults to `system` if created via REST.
     pub created_by: String,
@@ -45,20 +58,26 @@ pub struct Links {
 }
 
 /// Parameters for creating a Sync Map
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 pub struct CreateParams {
     pub unique_name: Option<String>,
-    pub ttl: Option<bool>,
+    /// How long the Map should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub ttl: Option<Duration>,
 }
 
 /// Parameters for updating a Sync Map
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 pub struct UpdateParams {
-    pub ttl: Option<bool>,
+    /// How long the Map should exist before deletion.
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub ttl: Option<Duration>,
 }
 
 pub struct Maps<'a, 'b> {
@@ -71,6 +90,8 @@ impl<'a, 'b> Maps<'a, 'b> {
     ///
     /// Creates a Sync Map resource with the provided parameters.
     pub async fn create(&self, params: CreateParams) -> Result<SyncMap, TwilioError> {
+        validate_ttl(params.ttl)?;
+
         self.client
             .send_request::<SyncMap, CreateParams>(
                 Method::POST,
@@ -154,6 +175,8 @@ impl<'a, 'b> Map<'a, 'b> {
     /// Targets the Sync Service provided to the `service()` argument  and updates the Map
     /// provided to the `map()` argument.
     pub async fn update(&self, params: UpdateParams) -> Result<SyncMap, TwilioError> {
+        validate_ttl(params.ttl)?;
+
         self.client
             .send_request::<SyncMap, UpdateParams>(
                 Method::POST,
@@ -167,6 +190,58 @@ impl<'a, 'b> Map<'a, 'b> {
             .await
     }
 
+    /// Subscribes to changes to the Map provided to the `map()` argument.
+    ///
+    /// A Sync access token is minted from the API Key in `params` and a WebSocket opened
+    /// to Twilio. The returned stream yields each change as it happens.
+    pub async fn subscribe(&self, params: SubscribeParams) -> Result<EventStream, TwilioError> {
+        let transport = WebSocketTransport::connect(TWILSOCK_URL).await?;
+        self.subscribe_with(params, transport).await
+    }
+
+    /// Subscribes to changes to the Map provided to the `map()` argument over the
+    /// provided transport rather than a WebSocket to Twilio.
+    pub async fn subscribe_with<T>(
+        &self,
+        params: SubscribeParams,
+        transport: T,
+    ) -> Result<EventStream, TwilioError>
+    where
+        T: Transport + 'static,
+    {
+        // Fetch the Map so unique names are resolved to SIDs for the subscription.
+        let map = self.get().await?;
+        let token = subscription::sync_token(self.client.account_sid(), &map.service_sid, &params)?;
+
+        subscription::open(transport, &token, ObjectType::Map, &map.sid).await
+    }
+
+    /// Copies the Map provided to the `map()` argument, including its items, into the
+    /// `target` Sync Service, which may belong to another account.
+    ///
+    /// The copy keeps the remaining TTLs and uses the `unique_name` of the params if
+    /// provided. If a Map with that unique name already exists in the target it is
+    /// handled according to the `conflict_policy`.
+    pub async fn copy_to(
+        &self,
+        target: &Service<'_, '_>,
+        params: CopyParams,
+    ) -> Result<RestoreReport, TwilioError> {
+        let sync_map = self.get().await?;
+        let record = MapRecord {
+            unique_name: params.unique_name.clone().unwrap_or(sync_map.unique_name),
+            ttl: remaining_ttl(sync_map.date_expires),
+            items: self.mapitems().export().await?,
+        };
+
+        let objects = SyncObjects {
+            maps: vec![record],
+            ..Default::default()
+        };
+
+        snapshot::restore(target, &objects, params.restore_params()).await
+    }
+
     /// [Deletes a Sync Map](https://www.twilio.com/docs/sync/api/map-resource#delete-a-sync-map-resource)
     ///
     /// Targets the Sync Service provided to the `service()` argument and deletes the Map
@@ -190,7 +265,7 @@ impl<'a, 'b> Map<'a, 'b> {
     /// Functions relating to a known Sync Map Item.
     ///
     /// Takes in the key of the Sync Map Item to perform actions against.
-    pub fn mapitem(&'a self, key: &'b str) -> MapItem {
+    pub fn mapitem(&'a self, key: &'b str) -> MapItem<'a, 'b> {
         MapItem {
             client: self.client,
             service_sid: self.service_sid,
@@ -200,7 +275,7 @@ impl<'a, 'b> Map<'a, 'b> {
     }
 
     /// General Sync Map Item functions.
-    pub fn mapitems(&'a self) -> MapItems {
+    pub fn mapitems(&'a self) -> MapItems<'a, 'b> {
         MapItems {
             client: self.client,
             service_sid: self.service_sid,
@@ -4,6 +4,8 @@ Contains Twilio Sync Service related functionality.
 
 */
 
+use std::time::Duration;
+
 use crate::{Client, PageMeta, TwilioError};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
@@ -11,8 +13,10 @@ use serde_with::skip_serializing_none;
 
 use super::{
     documents::{Document, Documents},
+    expiry::{self, Expiring, ExpiringResource, ExtendReport},
     lists::{List, Lists},
     maps::{Map, Maps},
+    snapshot::{self, RestoreParams, RestoreReport, Snapshot},
 };
 
 /// Represents a page of Sync Services from the Twilio API.
@@ -191,10 +195,54 @@ impl<'a, 'b> Service<'a, 'b> {
             .await
     }
 
+    /// Finds every Document, Map Item and List Item in the Sync Service provided to the
+    /// `Service()` argument that expires within the window, soonest first.
+    ///
+    /// All Maps and Lists are read in full so this can be slow on large Services.
+    pub async fn expiring(&self, within: Duration) -> Result<Vec<Expiring>, TwilioError> {
+        expiry::find_expiring(self, within).await
+    }
+
+    /// Sets the TTL of each resource to `ttl`, running up to `concurrency` updates at once.
+    /// Resources that fail to update are listed in the report rather than stopping the others.
+    pub async fn extend_ttl(
+        &self,
+        resources: &[ExpiringResource],
+        ttl: Duration,
+        concurrency: usize,
+    ) -> Result<ExtendReport, TwilioError> {
+        expiry::extend(self, resources, ttl, concurrency).await
+    }
+
+    /// Takes a snapshot of the Sync Service provided to the `Service()` argument.
+    ///
+    /// The snapshot holds the Service configuration and every Document, List and Map
+    /// with their items and remaining TTLs. Write it to a file with `Snapshot::write`.
+    pub async fn snapshot(&self) -> Result<Snapshot, TwilioError> {
+        snapshot::capture(self).await
+    }
+
+    /// Restores a snapshot into the Sync Service provided to the `Service()` argument.
+    ///
+    /// Documents, Lists and Maps are matched to existing ones by unique name and
+    /// handled according to the `conflict_policy`. With `ConflictPolicy::Fail` nothing is
+    /// written if any already exist. The Service configuration is not changed, see
+    /// `Snapshot::service_params` to create a Service matching the snapshot.
+    ///
+    /// When `dry_run` is set only the plan of changes is returned. Once writing starts, an
+    /// object or item that fails is recorded in the report and the rest are still restored.
+    pub async fn restore(
+        &self,
+        snapshot: &Snapshot,
+        params: RestoreParams,
+    ) -> Result<RestoreReport, TwilioError> {
+        snapshot::restore(self, &snapshot.objects, params).await
+    }
+
     /// Functions relating to a known Sync Document.
     ///
     /// Takes in the SID of the Sync Document to perform actions against.
-    pub fn document(&'a self, sid: &'b str) -> Document {
+    pub fn document(&'a self, sid: &'b str) -> Document<'a, 'b> {
         Document {
             client: self.client,
             service_sid: self.sid,
@@ -203,7 +251,7 @@ impl<'a, 'b> Service<'a, 'b> {
     }
 
     /// General Sync Document functions.
-    pub fn documents(&'a self) -> Documents {
+    pub fn documents(&'a self) -> Documents<'a, 'b> {
         Documents {
             client: self.client,
             service_sid: self.sid,
@@ -213,7 +261,7 @@ impl<'a, 'b> Service<'a, 'b> {
     /// Functions relating to a known Sync Map.
     ///
     /// Takes in the SID of the Sync Map to perform actions against.
-    pub fn map(&'a self, sid: &'b str) -> Map {
+    pub fn map(&'a self, sid: &'b str) -> Map<'a, 'b> {
         Map {
             client: self.client,
             service_sid: self.sid,
@@ -222,7 +270,7 @@ impl<'a, 'b> Service<'a, 'b> {
     }
 
     /// General Sync Map functions.
-    pub fn maps(&'a self) -> Maps {
+    pub fn maps(&'a self) -> Maps<'a, 'b> {
         Maps {
             client: self.client,
             service_sid: self.sid,
@@ -230,7 +278,7 @@ impl<'a, 'b> Service<'a, 'b> {
     }
 
     /// General Sync List functions.
-    pub fn lists(&'a self) -> Lists {
+    pub fn lists(&'a self) -> Lists<'a, 'b> {
         Lists {
             client: self.client,
             service_sid: self.sid,
@@ -240,7 +288,7 @@ impl<'a, 'b> Service<'a, 'b> {
     /// Functions relating to a known Sync List.
     ///
     /// Takes in the SID of the Sync List to perform actions against.
-    pub fn list(&'a self, sid: &'b str) -> List {
+    pub fn list(&'a self, sid: &'b str) -> List<'a, 'b> {
         List {
             client: self.client,
             service_sid: self.sid,
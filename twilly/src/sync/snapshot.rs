@@ -0,0 +1,665 @@
+/*!
+
+Contains Sync Service snapshot (backup) and restore functionality.
+
+A snapshot holds the configuration of a Sync Service along with every Document,
+List and Map in it, including items and remaining TTLs. Snapshots are written
+to a single JSON file and can be restored into the same Service or a different
+one, on any account.
+
+*/
+
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Read, Write},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::Display;
+
+use crate::TwilioError;
+
+use super::{
+    documents::{
+        CreateParams as CreateDocumentParams, SyncDocument, UpdateParams as UpdateDocumentParams,
+    },
+    lists::{CreateParams as CreateListParams, UpdateParams as UpdateListParams},
+    maps::{CreateParams as CreateMapParams, UpdateParams as UpdateMapParams},
+    services::{CreateOrUpdateParams, Service, SyncService},
+    transfer::{
+        conflict_error, record_ttl, remaining_ttl, ConflictPolicy, ImportOutcome, ImportParams,
+        ImportReport, ListItemRecord, MapItemRecord,
+    },
+};
+
+/// The snapshot format version written by this crate.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A point in time copy of a Sync Service and its contents.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Snapshot {
+    pub version: u32,
+    /// When the snapshot was taken (RFC 3339).
+    pub date_created: String,
+    /// SID of the Sync Service the snapshot was taken from.
+    pub service_sid: String,
+    pub service: ServiceConfig,
+    #[serde(flatten)]
+    pub objects: SyncObjects,
+}
+
+/// Documents, Lists and Maps (with their items) that can be restored into a
+/// Sync Service.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncObjects {
+    pub documents: Vec<DocumentRecord>,
+    pub lists: Vec<ListRecord>,
+    pub maps: Vec<MapRecord>,
+}
+
+/// The configurable properties of a Sync Service. See `SyncService` for details.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServiceConfig {
+    pub unique_name: Option<String>,
+    pub friendly_name: Option<String>,
+    pub webhook_url: Option<String>,
+    pub webhooks_from_rest_enabled: bool,
+    pub acl_enabled: bool,
+    pub reachability_debouncing_enabled: bool,
+    pub reachability_debouncing_window: u16,
+}
+
+impl From<&SyncService> for ServiceConfig {
+    fn from(service: &SyncService) -> Self {
+        ServiceConfig {
+            unique_name: service.unique_name.clone(),
+            friendly_name: service.friendly_name.clone(),
+            webhook_url: service.webhook_url.clone(),
+            webhooks_from_rest_enabled: service.webhooks_from_rest_enabled,
+            acl_enabled: service.acl_enabled,
+            reachability_debouncing_enabled: service.reachability_debouncing_enabled,
+            reachability_debouncing_window: service.reachability_debouncing_window,
+        }
+    }
+}
+
+/// A Sync Document held in a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DocumentRecord {
+    pub unique_name: String,
+    pub data: Value,
+    /// Seconds remaining before the Document expires at the time of the snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl From<&SyncDocument> for DocumentRecord {
+    fn from(document: &SyncDocument) -> Self {
+        DocumentRecord {
+            unique_name: document.unique_name.clone(),
+            data: document.data.clone(),
+            ttl: remaining_ttl(document.date_expires),
+        }
+    }
+}
+
+/// A Sync List and its items held in a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ListRecord {
+    pub unique_name: String,
+    /// Seconds remaining before the List expires at the time of the snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    pub items: Vec<ListItemRecord>,
+}
+
+/// A Sync Map and its items held in a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MapRecord {
+    pub unique_name: String,
+    /// Seconds remaining before the Map expires at the time of the snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    pub items: Vec<MapItemRecord>,
+}
+
+/// Parameters for restoring a snapshot.
+#[derive(Clone, Copy, Debug)]
+pub struct RestoreParams {
+    /// Only plan the restore, nothing is written to the Service.
+    pub dry_run: bool,
+    /// What to do with Documents, Lists and Maps whose unique name already exists in
+    /// the Service. Overwriting a List or Map overwrites its matching items and adds
+    /// any missing ones.
+    pub conflict_policy: ConflictPolicy,
+    /// Maximum number of item requests in flight at once.
+    pub concurrency: usize,
+}
+
+impl Default for RestoreParams {
+    fn default() -> Self {
+        let import_params = ImportParams::default();
+        RestoreParams {
+            dry_run: false,
+            conflict_policy: import_params.conflict_policy,
+            concurrency: import_params.concurrency,
+        }
+    }
+}
+
+/// The kinds of Sync object held in a snapshot.
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum ObjectKind {
+    Document,
+    List,
+    Map,
+}
+
+/// What a restore does with a Sync object from the snapshot.
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum RestoreStep {
+    Create,
+    Overwrite,
+    Skip,
+}
+
+/// A planned change to a single Sync object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestoreAction {
+    pub kind: ObjectKind,
+    pub unique_name: String,
+    pub step: RestoreStep,
+    /// Number of items in the List or Map. Always `0` for Documents.
+    pub items: usize,
+}
+
+impl fmt::Display for RestoreAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ObjectKind::Document => {
+                write!(f, "{} {} '{}'", self.step, self.kind, self.unique_name)
+            }
+            _ => write!(
+                f,
+                "{} {} '{}' ({} items)",
+                self.step, self.kind, self.unique_name, self.items
+            ),
+        }
+    }
+}
+
+/// The outcome of a restore.
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// Every planned change, in the order they are applied.
+    pub plan: Vec<RestoreAction>,
+    /// Tally of Documents, Lists and Maps. Empty for a dry run.
+    pub objects: ImportReport,
+    /// Tally of List and Map items. Empty for a dry run. A List or Map whose items couldn't
+    /// be imported at all is recorded as a single failure, and the restore carries on.
+    pub items: ImportReport,
+}
+
+// SIDs of the Sync objects already in a Service, keyed by unique name.
+#[derive(Default)]
+struct ExistingObjects {
+    documents: HashMap<String, String>,
+    lists: HashMap<String, String>,
+    maps: HashMap<String, String>,
+}
+
+impl Snapshot {
+    /// Writes the snapshot as JSON.
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(writer);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Reads a snapshot from JSON. Snapshots written by a newer version of the
+    /// crate are rejected.
+    pub fn read<R: Read>(reader: R) -> io::Result<Snapshot> {
+        let snapshot: Snapshot = serde_json::from_reader(reader)?;
+
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Snapshot version {} is newer than the supported version {}",
+                    snapshot.version, SNAPSHOT_VERSION
+                ),
+            ));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Parameters for creating a new Sync Service with the configuration held in
+    /// the snapshot.
+    pub fn service_params(&self) -> CreateOrUpdateParams {
+        CreateOrUpdateParams {
+            friendly_name: self
+                .service
+                .friendly_name
+                .clone()
+                .or_else(|| self.service.unique_name.clone()),
+            webhook_url: self.service.webhook_url.clone(),
+            reachability_webhooks_enabled: None,
+            acl_enabled: Some(self.service.acl_enabled),
+            reachability_debouncing_enabled: Some(self.service.reachability_debouncing_enabled),
+            reachability_debouncing_window: Some(self.service.reachability_debouncing_window),
+            webhooks_from_rest_enabled: Some(self.service.webhooks_from_rest_enabled),
+        }
+    }
+
+    /// The changes a restore would make to a Service that does not yet contain
+    /// any Documents, Lists or Maps.
+    pub fn plan_for_new_service(&self) -> Vec<RestoreAction> {
+        self.objects
+            .plan(&ExistingObjects::default(), ConflictPolicy::Fail)
+    }
+}
+
+impl SyncObjects {
+    // Decides what to do with each object given those already in the target Service.
+    fn plan(
+        &self,
+        existing: &ExistingObjects,
+        conflict_policy: ConflictPolicy,
+    ) -> Vec<RestoreAction> {
+        let step = |exists: bool| match (exists, conflict_policy) {
+            (false, _) => RestoreStep::Create,
+            (true, ConflictPolicy::Skip) => RestoreStep::Skip,
+            (true, _) => RestoreStep::Overwrite,
+        };
+
+        let documents = self.documents.iter().map(|document| RestoreAction {
+            kind: ObjectKind::Document,
+            unique_name: document.unique_name.clone(),
+            step: step(existing.documents.contains_key(&document.unique_name)),
+            items: 0,
+        });
+        let lists = self.lists.iter().map(|list| RestoreAction {
+            kind: ObjectKind::List,
+            unique_name: list.unique_name.clone(),
+            step: step(existing.lists.contains_key(&list.unique_name)),
+            items: list.items.len(),
+        });
+        let maps = self.maps.iter().map(|map| RestoreAction {
+            kind: ObjectKind::Map,
+            unique_name: map.unique_name.clone(),
+            step: step(existing.maps.contains_key(&map.unique_name)),
+            items: map.items.len(),
+        });
+
+        documents.chain(lists).chain(maps).collect()
+    }
+}
+
+// Takes a snapshot of the Service.
+pub(crate) async fn capture(service: &Service<'_, '_>) -> Result<Snapshot, TwilioError> {
+    let sync_service = service.get().await?;
+
+    let documents = service
+        .documents()
+        .list()
+        .await?
+        .iter()
+        .map(DocumentRecord::from)
+        .collect();
+
+    let mut lists = Vec::new();
+    for list in service.lists().list().await? {
+        let items = service.list(&list.sid).listitems().export().await?;
+        lists.push(ListRecord {
+            ttl: remaining_ttl(list.date_expires),
+            unique_name: list.unique_name,
+            items,
+        });
+    }
+
+    let mut maps = Vec::new();
+    for map in service.maps().list().await? {
+        let items = service.map(&map.sid).mapitems().export().await?;
+        maps.push(MapRecord {
+            ttl: remaining_ttl(map.date_expires),
+            unique_name: map.unique_name,
+            items,
+        });
+    }
+
+    Ok(Snapshot {
+        version: SNAPSHOT_VERSION,
+        date_created: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        service_sid: sync_service.sid.clone(),
+        service: ServiceConfig::from(&sync_service),
+        objects: SyncObjects {
+            documents,
+            lists,
+            maps,
+        },
+    })
+}
+
+// Restores the objects into the Service.
+pub(crate) async fn restore(
+    service: &Service<'_, '_>,
+    objects: &SyncObjects,
+    params: RestoreParams,
+) -> Result<RestoreReport, TwilioError> {
+    let existing = ExistingObjects {
+        documents: service
+            .documents()
+            .list()
+            .await?
+            .into_iter()
+            .map(|document| (document.unique_name, document.sid))
+            .collect(),
+        lists: service
+            .lists()
+            .list()
+            .await?
+            .into_iter()
+            .map(|list| (list.unique_name, list.sid))
+            .collect(),
+        maps: service
+            .maps()
+            .list()
+            .await?
+            .into_iter()
+            .map(|map| (map.unique_name, map.sid))
+            .collect(),
+    };
+
+    let plan = objects.plan(&existing, params.conflict_policy);
+
+    if params.conflict_policy == ConflictPolicy::Fail {
+        let conflicting_names: Vec<String> = plan
+            .iter()
+            .filter(|action| action.step != RestoreStep::Create)
+            .map(|action| format!("{} '{}'", action.kind, action.unique_name))
+            .collect();
+
+        if !conflicting_names.is_empty() {
+            return Err(conflict_error(&conflicting_names));
+        }
+    }
+
+    let mut report = RestoreReport {
+        plan,
+        ..Default::default()
+    };
+
+    if params.dry_run {
+        return Ok(report);
+    }
+
+    let item_params = ImportParams {
+        concurrency: params.concurrency,
+        conflict_policy: ConflictPolicy::Overwrite,
+    };
+
+    for (action, document) in report.plan.iter().zip(&objects.documents) {
+        let outcome = restore_document(service, document, action.step, &existing).await;
+        report
+            .objects
+            .record(format!("Document '{}'", document.unique_name), outcome);
+    }
+
+    let list_actions = report.plan.iter().skip(objects.documents.len());
+    for (action, list) in list_actions.zip(&objects.lists) {
+        let ttl = match record_ttl(list.ttl) {
+            Ok(ttl) => ttl,
+            Err(error) => {
+                report
+                    .objects
+                    .record(format!("List '{}'", list.unique_name), Err(error));
+                continue;
+            }
+        };
+
+        let list_sid = match action.step {
+            RestoreStep::Skip => {
+                report.objects.record(
+                    format!("List '{}'", list.unique_name),
+                    Ok(ImportOutcome::Skipped),
+                );
+                continue;
+            }
+            RestoreStep::Create => service
+                .lists()
+                .create(CreateListParams {
+                    unique_name: Some(list.unique_name.clone()),
+                    ttl,
+                })
+                .await
+                .map(|created| (created.sid, ImportOutcome::Created)),
+            RestoreStep::Overwrite => {
+                let list_sid = existing.lists[&list.unique_name].clone();
+                match ttl {
+                    Some(ttl) => service
+                        .list(&list_sid)
+                        .update(UpdateListParams { ttl: Some(ttl) })
+                        .await
+                        .map(|_| (list_sid, ImportOutcome::Updated)),
+                    None => Ok((list_sid, ImportOutcome::Updated)),
+                }
+            }
+        };
+
+        match list_sid {
+            Ok((list_sid, outcome)) => {
+                report
+                    .objects
+                    .record(format!("List '{}'", list.unique_name), Ok(outcome));
+                match service
+                    .list(&list_sid)
+                    .listitems()
+                    .import(list.items.clone(), item_params, |_| {})
+                    .await
+                {
+                    Ok(items) => report.items.merge(items),
+                    Err(error) => report
+                        .items
+                        .record(format!("List '{}' items", list.unique_name), Err(error)),
+                }
+            }
+            Err(error) => report
+                .objects
+                .record(format!("List '{}'", list.unique_name), Err(error)),
+        }
+    }
+
+    let map_actions = report
+        .plan
+        .iter()
+        .skip(objects.documents.len() + objects.lists.len());
+    for (action, map) in map_actions.zip(&objects.maps) {
+        let ttl = match record_ttl(map.ttl) {
+            Ok(ttl) => ttl,
+            Err(error) => {
+                report
+                    .objects
+                    .record(format!("Map '{}'", map.unique_name), Err(error));
+                continue;
+            }
+        };
+
+        let map_sid = match action.step {
+            RestoreStep::Skip => {
+                report.objects.record(
+                    format!("Map '{}'", map.unique_name),
+                    Ok(ImportOutcome::Skipped),
+                );
+                continue;
+            }
+            RestoreStep::Create => service
+                .maps()
+                .create(CreateMapParams {
+                    unique_name: Some(map.unique_name.clone()),
+                    ttl,
+                })
+                .await
+                .map(|created| (created.sid, ImportOutcome::Created)),
+            RestoreStep::Overwrite => {
+                let map_sid = existing.maps[&map.unique_name].clone();
+                match ttl {
+                    Some(ttl) => service
+                        .map(&map_sid)
+                        .update(UpdateMapParams { ttl: Some(ttl) })
+                        .await
+                        .map(|_| (map_sid, ImportOutcome::Updated)),
+                    None => Ok((map_sid, ImportOutcome::Updated)),
+                }
+            }
+        };
+
+        match map_sid {
+            Ok((map_sid, outcome)) => {
+                report
+                    .objects
+                    .record(format!("Map '{}'", map.unique_name), Ok(outcome));
+                match service
+                    .map(&map_sid)
+                    .mapitems()
+                    .import(map.items.clone(), item_params, |_| {})
+                    .await
+                {
+                    Ok(items) => report.items.merge(items),
+                    Err(error) => report
+                        .items
+                        .record(format!("Map '{}' items", map.unique_name), Err(error)),
+                }
+            }
+            Err(error) => report
+                .objects
+                .record(format!("Map '{}'", map.unique_name), Err(error)),
+        }
+    }
+
+    Ok(report)
+}
+
+// Creates or overwrites a single Document according to its planned step.
+async fn restore_document(
+    service: &Service<'_, '_>,
+    document: &DocumentRecord,
+    step: RestoreStep,
+    existing: &ExistingObjects,
+) -> Result<ImportOutcome, TwilioError> {
+    match step {
+        RestoreStep::Skip => Ok(ImportOutcome::Skipped),
+        RestoreStep::Create => {
+            service
+                .documents()
+                .create(CreateDocumentParams {
+                    unique_name: Some(document.unique_name.clone()),
+                    data: &document.data,
+                    ttl: record_ttl(document.ttl)?,
+                })
+                .await?;
+
+            Ok(ImportOutcome::Created)
+        }
+        RestoreStep::Overwrite => {
+            service
+                .document(&existing.documents[&document.unique_name])
+                .update(UpdateDocumentParams {
+                    if_match: None,
+                    data: &document.data,
+                    ttl: record_ttl(document.ttl)?,
+                })
+                .await?;
+
+            Ok(ImportOutcome::Updated)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            date_created: String::from("2024-01-01T00:00:00Z"),
+            service_sid: String::from("IS11111111111111111111111111111111"),
+            service: ServiceConfig {
+                unique_name: None,
+                friendly_name: Some(String::from("Staging")),
+                webhook_url: None,
+                webhooks_from_rest_enabled: false,
+                acl_enabled: true,
+                reachability_debouncing_enabled: false,
+                reachability_debouncing_window: 5000,
+            },
+            objects: SyncObjects {
+                documents: vec![DocumentRecord {
+                    unique_name: String::from("settings"),
+                    data: serde_json::json!({ "theme": "dark" }),
+                    ttl: None,
+                }],
+                lists: vec![ListRecord {
+                    unique_name: String::from("queue"),
+                    ttl: Some(60),
+                    items: vec![ListItemRecord {
+                        index: 0,
+                        data: serde_json::json!("first"),
+                        ttl: None,
+                    }],
+                }],
+                maps: vec![MapRecord {
+                    unique_name: String::from("users"),
+                    ttl: None,
+                    items: Vec::new(),
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn plan_for_new_service_creates_everything() {
+        let plan = snapshot().plan_for_new_service();
+
+        assert_eq!(3, plan.len());
+        assert!(plan.iter().all(|action| action.step == RestoreStep::Create));
+        assert_eq!("Create List 'queue' (1 items)", plan[1].to_string());
+    }
+
+    #[test]
+    fn plan_applies_conflict_policy_to_existing_objects() {
+        let existing = ExistingObjects {
+            maps: HashMap::from([(
+                String::from("users"),
+                String::from("MP11111111111111111111111111111111"),
+            )]),
+            ..Default::default()
+        };
+
+        let skipped = snapshot().objects.plan(&existing, ConflictPolicy::Skip);
+        assert_eq!(RestoreStep::Create, skipped[0].step);
+        assert_eq!(RestoreStep::Skip, skipped[2].step);
+
+        let overwritten = snapshot()
+            .objects
+            .plan(&existing, ConflictPolicy::Overwrite);
+        assert_eq!(RestoreStep::Overwrite, overwritten[2].step);
+    }
+
+    #[test]
+    fn read_rejects_newer_versions() {
+        let mut newer = snapshot();
+        newer.version = SNAPSHOT_VERSION + 1;
+        let mut buffer = Vec::new();
+        newer.write(&mut buffer).unwrap();
+
+        assert!(Snapshot::read(buffer.as_slice()).is_err());
+
+        let mut buffer = Vec::new();
+        snapshot().write(&mut buffer).unwrap();
+        assert_eq!(snapshot(), Snapshot::read(buffer.as_slice()).unwrap());
+    }
+}
@@ -0,0 +1,532 @@
+/*!
+
+Contains real-time subscriptions to Sync Document and Map changes.
+
+Subscriptions speak the Twilio Sync client protocol (Twilsock) over a
+[`Transport`], which is a WebSocket to Twilio by default. Any other transport,
+such as an in-process or local stand-in server, can be provided instead.
+
+A Sync Access Token (see `twilly::jwt`) is minted for the subscription from an
+API Key, as the account Auth Token cannot be used to sign one.
+
+*/
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, stream::BoxStream, SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::{
+    jwt::{AccessToken, SyncGrant},
+    ErrorKind, TwilioError,
+};
+
+/// The Twilsock endpoint used by `subscribe()`.
+pub const TWILSOCK_URL: &str = "wss://tsock.us1.twilio.com/v3/wsconnect";
+
+/// Parameters for subscribing to a Sync Document or Map.
+#[derive(Clone, Debug)]
+pub struct SubscribeParams {
+    /// SID of the API Key used to sign the access token, begins with SK...
+    pub api_key_sid: String,
+    pub api_key_secret: String,
+    /// Identity the subscription connects as.
+    pub identity: String,
+    /// How long the minted access token is valid for. The subscription ends
+    /// when it expires.
+    pub token_ttl: Duration,
+}
+
+/// A change to a subscribed Document or Map.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncEvent {
+    DocumentUpdated {
+        revision: String,
+        data: Value,
+    },
+    DocumentRemoved,
+    MapItemAdded {
+        key: String,
+        revision: String,
+        data: Value,
+    },
+    MapItemUpdated {
+        key: String,
+        revision: String,
+        data: Value,
+    },
+    MapItemRemoved {
+        key: String,
+    },
+    MapRemoved,
+}
+
+/// A stream of events from a subscription. The stream ends when the connection
+/// is closed.
+pub type EventStream = BoxStream<'static, Result<SyncEvent, TwilioError>>;
+
+/// Carries Twilsock frames to and from Twilio. Implement this to run
+/// subscriptions over something other than the default WebSocket.
+pub trait Transport: Send {
+    /// Sends a single text frame.
+    fn send(&mut self, frame: String) -> BoxFuture<'_, Result<(), TwilioError>>;
+
+    /// Receives the next text frame, or `None` once the connection is closed.
+    fn receive(&mut self) -> BoxFuture<'_, Result<Option<String>, TwilioError>>;
+}
+
+/// A WebSocket `Transport`.
+pub struct WebSocketTransport {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WebSocketTransport {
+    /// Opens a WebSocket to `url`, e.g. `TWILSOCK_URL`.
+    pub async fn connect(url: &str) -> Result<WebSocketTransport, TwilioError> {
+        let (socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(connection_error)?;
+
+        Ok(WebSocketTransport { socket })
+    }
+}
+
+impl Transport for WebSocketTransport {
+    fn send(&mut self, frame: String) -> BoxFuture<'_, Result<(), TwilioError>> {
+        Box::pin(async move {
+            self.socket
+                .send(Message::Text(frame))
+                .await
+                .map_err(connection_error)
+        })
+    }
+
+    fn receive(&mut self) -> BoxFuture<'_, Result<Option<String>, TwilioError>> {
+        Box::pin(async move {
+            while let Some(message) = self.socket.next().await {
+                match message.map_err(connection_error)? {
+                    Message::Text(frame) => return Ok(Some(frame)),
+                    Message::Binary(frame) => {
+                        return Ok(Some(String::from_utf8_lossy(&frame).into_owned()))
+                    }
+                    Message::Close(_) => return Ok(None),
+                    _ => continue,
+                }
+            }
+
+            Ok(None)
+        })
+    }
+}
+
+/// The kind of Sync object being subscribed to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ObjectType {
+    Document,
+    Map,
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjectType::Document => write!(f, "document"),
+            ObjectType::Map => write!(f, "map"),
+        }
+    }
+}
+
+// Mints a Sync access token for the Service, signed with the API Key secret.
+pub(crate) fn sync_token(
+    account_sid: &str,
+    service_sid: &str,
+    params: &SubscribeParams,
+) -> Result<String, TwilioError> {
+    let mut token = AccessToken::new(
+        account_sid,
+        &params.api_key_sid,
+        &params.api_key_secret,
+        &params.identity,
+    );
+    token.ttl = params.token_ttl;
+    token.grants.sync = Some(SyncGrant {
+        service_sid: service_sid.to_string(),
+        endpoint_id: None,
+    });
+
+    token.to_jwt()
+}
+
+// Initialises a Twilsock session over the transport and subscribes to the
+// object, returning the stream of its events.
+pub(crate) async fn open<T>(
+    mut transport: T,
+    token: &str,
+    object_type: ObjectType,
+    object_sid: &str,
+) -> Result<EventStream, TwilioError>
+where
+    T: Transport + 'static,
+{
+    let init_id = message_id();
+    transport
+        .send(encode_frame(
+            &json!({
+                "method": "init",
+                "id": init_id,
+                "token": token,
+                "continuation_token": null,
+                "capabilities": ["client_update", "offline_storage"],
+                "payload_size": 0,
+            }),
+            "",
+        ))
+        .await?;
+    await_reply(&mut transport, &init_id).await?;
+
+    let payload = json!({
+        "event_protocol_version": 3,
+        "action": "establish",
+        "requests": [{ "object_sid": object_sid, "object_type": object_type.to_string() }],
+    })
+    .to_string();
+    let subscribe_id = message_id();
+    transport
+        .send(encode_frame(
+            &json!({
+                "method": "message",
+                "id": subscribe_id,
+                "http_request": {
+                    "host": "cds.twilio.com",
+                    "path": "/v4/Subscriptions",
+                    "method": "POST",
+                    "params": {},
+                    "headers": { "Content-Type": "application/json" },
+                },
+                "payload_type": "application/json",
+                "payload_size": payload.len(),
+            }),
+            &payload,
+        ))
+        .await?;
+    await_reply(&mut transport, &subscribe_id).await?;
+
+    let object_sid = object_sid.to_string();
+    let events = futures::stream::unfold(Some(transport), move |transport| {
+        let object_sid = object_sid.clone();
+        async move {
+            let mut transport = transport?;
+            match next_event(&mut transport, object_type, &object_sid).await {
+                Ok(Some(event)) => Some((Ok(event), Some(transport))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        }
+    });
+
+    Ok(events.boxed())
+}
+
+// Reads frames until an event for the object arrives, answering pings and
+// acknowledging notifications along the way.
+async fn next_event<T: Transport>(
+    transport: &mut T,
+    object_type: ObjectType,
+    object_sid: &str,
+) -> Result<Option<SyncEvent>, TwilioError> {
+    loop {
+        let Some(frame) = transport.receive().await? else {
+            return Ok(None);
+        };
+        let (header, payload) = decode_frame(&frame)?;
+
+        match header["method"].as_str() {
+            Some("ping") => {
+                transport.send(reply_frame(&header)).await?;
+            }
+            Some("notification") => {
+                transport.send(reply_frame(&header)).await?;
+                if let Some(event) = parse_event(payload, object_type, object_sid)? {
+                    return Ok(Some(event));
+                }
+            }
+            Some("close") => return Ok(None),
+            _ => continue,
+        }
+    }
+}
+
+// Waits for the reply to a request, failing if it was not successful.
+async fn await_reply<T: Transport>(transport: &mut T, id: &str) -> Result<(), TwilioError> {
+    loop {
+        let frame = transport.receive().await?.ok_or_else(|| {
+            connection_error("Connection closed before Twilio replied to the request")
+        })?;
+        let (header, _) = decode_frame(&frame)?;
+
+        if header["method"] != "reply" || header["id"] != id {
+            continue;
+        }
+
+        let code = header["status"]["code"].as_u64().unwrap_or_default();
+        let http_code = header["http_response"]["status"]["code"]
+            .as_u64()
+            .unwrap_or(code);
+        if (200..300).contains(&code) && (200..300).contains(&http_code) {
+            return Ok(());
+        }
+
+        return Err(connection_error(format!(
+            "Twilio rejected the request ({}): {}",
+            http_code, header["status"]["description"]
+        )));
+    }
+}
+
+// Builds the event for a notification about the subscribed object. Notifications
+// for other objects and unrecognised event types are ignored.
+fn parse_event(
+    payload: &str,
+    object_type: ObjectType,
+    object_sid: &str,
+) -> Result<Option<SyncEvent>, TwilioError> {
+    let notification: Value = serde_json::from_str(payload).map_err(connection_error)?;
+    let event = &notification["event"];
+    let text = |field: &str| event[field].as_str().unwrap_or_default().to_string();
+
+    let event = match (object_type, notification["event_type"].as_str()) {
+        (ObjectType::Document, Some(event_type)) if text("document_sid") == object_sid => {
+            match event_type {
+                "document_updated" => SyncEvent::DocumentUpdated {
+                    revision: text("document_revision"),
+                    data: event["document_data"].clone(),
+                },
+                "document_removed" => SyncEvent::DocumentRemoved,
+                _ => return Ok(None),
+            }
+        }
+        (ObjectType::Map, Some(event_type)) if text("map_sid") == object_sid => match event_type {
+            "map_item_added" => SyncEvent::MapItemAdded {
+                key: text("item_key"),
+                revision: text("item_revision"),
+                data: event["item_data"].clone(),
+            },
+            "map_item_updated" => SyncEvent::MapItemUpdated {
+                key: text("item_key"),
+                revision: text("item_revision"),
+                data: event["item_data"].clone(),
+            },
+            "map_item_removed" => SyncEvent::MapItemRemoved {
+                key: text("item_key"),
+            },
+            "map_removed" => SyncEvent::MapRemoved,
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(event))
+}
+
+// Encodes a Twilsock frame: a preamble holding the header length, the JSON
+// header and the payload.
+fn encode_frame(header: &Value, payload: &str) -> String {
+    let header = header.to_string();
+    format!(
+        "TWILSOCK V3.0 {}\r\n{}\r\n{}",
+        header.len(),
+        header,
+        payload
+    )
+}
+
+// Splits a Twilsock frame into its header and payload.
+fn decode_frame(frame: &str) -> Result<(Value, &str), TwilioError> {
+    let invalid = || connection_error("Received an invalid Twilsock frame");
+
+    let (preamble, rest) = frame.split_once("\r\n").ok_or_else(invalid)?;
+    let header_size = preamble
+        .strip_prefix("TWILSOCK V3.0 ")
+        .and_then(|size| size.parse::<usize>().ok())
+        .ok_or_else(invalid)?;
+    let header = rest.get(..header_size).ok_or_else(invalid)?;
+    let payload = rest
+        .get(header_size..)
+        .and_then(|payload| payload.strip_prefix("\r\n"))
+        .unwrap_or_default();
+
+    Ok((
+        serde_json::from_str(header).map_err(|_| invalid())?,
+        payload,
+    ))
+}
+
+// Builds a successful reply to a frame sent by Twilio.
+fn reply_frame(header: &Value) -> String {
+    encode_frame(
+        &json!({
+            "method": "reply",
+            "id": header["id"],
+            "status": { "code": 200, "status": "OK" },
+            "payload_size": 0,
+        }),
+        "",
+    )
+}
+
+// Generates a unique id for an outgoing message.
+fn message_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    format!(
+        "TM{:016x}{:016x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn connection_error(error: impl fmt::Display) -> TwilioError {
+    TwilioError {
+        kind: ErrorKind::ConnectionError(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn frame_round_trip() {
+        let frame = encode_frame(&json!({ "method": "ping", "id": "TM1" }), "{\"a\":1}");
+        let (header, payload) = decode_frame(&frame).unwrap();
+
+        assert_eq!("ping", header["method"]);
+        assert_eq!("{\"a\":1}", payload);
+        assert!(decode_frame("not a frame").is_err());
+    }
+
+    #[test]
+    fn sync_token_is_signed_jwt() {
+        let token = sync_token(
+            "ACxx",
+            "ISxx",
+            &SubscribeParams {
+                api_key_sid: String::from("SKxx"),
+                api_key_secret: String::from("secret"),
+                identity: String::from("dashboard"),
+                token_ttl: Duration::from_secs(3600),
+            },
+        )
+        .unwrap();
+
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(3, parts.len());
+
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        assert_eq!("SKxx", claims["iss"]);
+        assert_eq!("ACxx", claims["sub"]);
+        assert_eq!("ISxx", claims["grants"]["data_sync"]["service_sid"]);
+    }
+
+    #[test]
+    fn sync_token_is_minted_for_subaccount() {
+        let parent = crate::Client::new(&crate::TwilioConfig::build(
+            String::from("AC11111111111111111111111111111111"),
+            String::from("11111111111111111111111111111111"),
+        ));
+        let subaccount = parent.for_subaccount("AC22222222222222222222222222222222");
+        let token = sync_token(
+            subaccount.account_sid(),
+            "ISxx",
+            &SubscribeParams {
+                api_key_sid: String::from("SKxx"),
+                api_key_secret: String::from("secret"),
+                identity: String::from("dashboard"),
+                token_ttl: Duration::from_secs(3600),
+            },
+        )
+        .unwrap();
+
+        let claims: Value = serde_json::from_slice(
+            &URL_SAFE_NO_PAD
+                .decode(token.split('.').nth(1).unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!("AC22222222222222222222222222222222", claims["sub"]);
+    }
+
+    // Stands in for Twilio: accepts the init and subscription, then sends one
+    // event for another Document followed by one for the subscribed Document.
+    async fn stand_in_server(listener: TcpListener) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        for _ in 0..2 {
+            let Some(Ok(Message::Text(frame))) = socket.next().await else {
+                panic!("Expected a request frame");
+            };
+            let (header, _) = decode_frame(&frame).unwrap();
+            socket
+                .send(Message::Text(reply_frame(&header)))
+                .await
+                .unwrap();
+        }
+
+        for document_sid in ["ETother", "ETxx"] {
+            let payload = json!({
+                "event_type": "document_updated",
+                "event": {
+                    "document_sid": document_sid,
+                    "document_revision": "1",
+                    "document_data": { "status": "open" },
+                },
+            })
+            .to_string();
+            let frame = encode_frame(
+                &json!({ "method": "notification", "id": "TMn", "payload_size": payload.len() }),
+                &payload,
+            );
+            socket.send(Message::Text(frame)).await.unwrap();
+
+            // Wait for the notification to be acknowledged.
+            let Some(Ok(Message::Text(reply))) = socket.next().await else {
+                panic!("Expected a reply frame");
+            };
+            assert_eq!("reply", decode_frame(&reply).unwrap().0["method"]);
+        }
+
+        socket.close(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn subscribes_over_websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(stand_in_server(listener));
+
+        let transport = WebSocketTransport::connect(&url).await.unwrap();
+        let mut events = open(transport, "token", ObjectType::Document, "ETxx")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            SyncEvent::DocumentUpdated {
+                revision: String::from("1"),
+                data: json!({ "status": "open" }),
+            },
+            events.next().await.unwrap().unwrap()
+        );
+        assert!(events.next().await.is_none());
+
+        server.await.unwrap();
+    }
+}
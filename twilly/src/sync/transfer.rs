@@ -0,0 +1,303 @@
+/*!
+
+Contains helpers for bulk exporting and importing Sync Map and List items.
+
+Items are exported as _records_ holding only what is needed to recreate them
+(key or index, data and remaining TTL) and can be written to or read from
+JSON or NDJSON files.
+
+*/
+
+use std::{
+    io::{self, BufRead, Write},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::TwilioError;
+
+use super::{expiry::validate_ttl, listitems::SyncListItem, mapitems::SyncMapItem};
+
+/// File formats supported for exporting and importing items.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq)]
+pub enum Format {
+    /// A single JSON array of records.
+    #[strum(to_string = "JSON")]
+    Json,
+    /// One JSON record per line.
+    #[strum(to_string = "NDJSON")]
+    Ndjson,
+}
+
+impl Format {
+    /// Determines the format from a file path's extension. Paths ending in `.ndjson`
+    /// or `.jsonl` are NDJSON, everything else is treated as JSON.
+    pub fn from_path(path: &str) -> Format {
+        let lowercase_path = path.to_lowercase();
+        if lowercase_path.ends_with(".ndjson") || lowercase_path.ends_with(".jsonl") {
+            Format::Ndjson
+        } else {
+            Format::Json
+        }
+    }
+
+    /// The file extension typically used for the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// An exported Sync Map Item.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MapItemRecord {
+    pub key: String,
+    pub data: Value,
+    /// Seconds remaining before the item expires at the time of export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl From<&SyncMapItem> for MapItemRecord {
+    fn from(item: &SyncMapItem) -> Self {
+        MapItemRecord {
+            key: item.key.clone(),
+            data: item.data.clone(),
+            ttl: remaining_ttl(item.date_expires),
+        }
+    }
+}
+
+/// An exported Sync List Item.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ListItemRecord {
+    pub index: u32,
+    pub data: Value,
+    /// Seconds remaining before the item expires at the time of export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl From<&SyncListItem> for ListItemRecord {
+    fn from(item: &SyncListItem) -> Self {
+        ListItemRecord {
+            index: item.index,
+            data: item.data.clone(),
+            ttl: remaining_ttl(item.date_expires),
+        }
+    }
+}
+
+/// What to do when an imported record already exists in the target.
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, EnumString, PartialEq)]
+pub enum ConflictPolicy {
+    /// Leave the existing item untouched.
+    #[strum(to_string = "Skip")]
+    Skip,
+    /// Replace the existing item's data and TTL.
+    #[strum(to_string = "Overwrite")]
+    Overwrite,
+    /// Abort the import before anything is written.
+    #[default]
+    #[strum(to_string = "Fail")]
+    Fail,
+}
+
+/// Parameters for importing items.
+#[derive(Clone, Copy, Debug)]
+pub struct ImportParams {
+    /// Maximum number of requests in flight at once. Values below 1 are treated as 1.
+    pub concurrency: usize,
+    pub conflict_policy: ConflictPolicy,
+}
+
+impl Default for ImportParams {
+    fn default() -> Self {
+        ImportParams {
+            concurrency: 5,
+            conflict_policy: ConflictPolicy::default(),
+        }
+    }
+}
+
+/// Progress of an import, passed to the progress callback after every record.
+#[derive(Clone, Copy, Debug)]
+pub struct ImportProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// A record that could not be imported.
+#[derive(Debug)]
+pub struct ImportFailure {
+    /// Key (or index for List Items) of the record.
+    pub key: String,
+    pub error: TwilioError,
+}
+
+/// Summary of a completed import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: Vec<ImportFailure>,
+}
+
+impl ImportReport {
+    // Tallies the outcome of importing a single record.
+    pub(crate) fn record(&mut self, key: String, outcome: Result<ImportOutcome, TwilioError>) {
+        match outcome {
+            Ok(ImportOutcome::Created) => self.created += 1,
+            Ok(ImportOutcome::Updated) => self.updated += 1,
+            Ok(ImportOutcome::Skipped) => self.skipped += 1,
+            Err(error) => self.failed.push(ImportFailure { key, error }),
+        }
+    }
+
+    // Adds the tallies of another report to this one.
+    pub(crate) fn merge(&mut self, other: ImportReport) {
+        self.created += other.created;
+        self.updated += other.updated;
+        self.skipped += other.skipped;
+        self.failed.extend(other.failed);
+    }
+}
+
+// The result of importing a single record.
+pub(crate) enum ImportOutcome {
+    Created,
+    Updated,
+    Skipped,
+}
+
+/// Writes records to `writer` in the provided format.
+pub fn write_records<W, T>(writer: W, records: &[T], format: Format) -> io::Result<()>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut writer = io::BufWriter::new(writer);
+
+    match format {
+        Format::Json => serde_json::to_writer_pretty(&mut writer, records)?,
+        Format::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+    }
+
+    writer.flush()
+}
+
+/// Reads records from `reader` in the provided format. Blank NDJSON lines are ignored.
+pub fn read_records<R, T>(reader: R, format: Format) -> io::Result<Vec<T>>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    match format {
+        Format::Json => Ok(serde_json::from_reader(reader)?),
+        Format::Ndjson => {
+            let mut records = Vec::new();
+            for line in reader.lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                records.push(serde_json::from_str(&line)?);
+            }
+
+            Ok(records)
+        }
+    }
+}
+
+// Builds the error returned when a `Fail` conflict policy finds records that
+// already exist in the target.
+pub(crate) fn conflict_error(conflicting_keys: &[String]) -> TwilioError {
+    TwilioError {
+        kind: crate::ErrorKind::ValidationError(format!(
+            "{} item(s) already exist and the conflict policy is to fail: {}",
+            conflicting_keys.len(),
+            conflicting_keys.join(", ")
+        )),
+    }
+}
+
+// Converts an item's `date_expires` into the seconds remaining from now. Items
+// that are past their expiry but not yet removed are given the minimum TTL.
+pub(crate) fn remaining_ttl(date_expires: Option<DateTime<Utc>>) -> Option<u32> {
+    let remaining = date_expires?.signed_duration_since(Utc::now());
+
+    Some(u32::try_from(remaining.num_seconds()).unwrap_or(0).max(1))
+}
+
+// Converts a record TTL into the type accepted by create and update parameters.
+pub(crate) fn record_ttl(ttl: Option<u32>) -> Result<Option<Duration>, TwilioError> {
+    let ttl = ttl.map(|ttl| Duration::from_secs(u64::from(ttl)));
+    validate_ttl(ttl)?;
+
+    Ok(ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<MapItemRecord> {
+        vec![
+            MapItemRecord {
+                key: String::from("first"),
+                data: serde_json::json!({ "name": "Alice" }),
+                ttl: None,
+            },
+            MapItemRecord {
+                key: String::from("second"),
+                data: serde_json::json!([1, 2, 3]),
+                ttl: Some(3600),
+            },
+        ]
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut buffer = Vec::new();
+        write_records(&mut buffer, &records(), Format::Json).unwrap();
+
+        let read: Vec<MapItemRecord> = read_records(buffer.as_slice(), Format::Json).unwrap();
+        assert_eq!(records(), read);
+    }
+
+    #[test]
+    fn ndjson_round_trip() {
+        let mut buffer = Vec::new();
+        write_records(&mut buffer, &records(), Format::Ndjson).unwrap();
+
+        assert_eq!(2, String::from_utf8_lossy(&buffer).lines().count());
+
+        let read: Vec<MapItemRecord> = read_records(buffer.as_slice(), Format::Ndjson).unwrap();
+        assert_eq!(records(), read);
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Format::Ndjson, Format::from_path("backup.NDJSON"));
+        assert_eq!(Format::Ndjson, Format::from_path("backup.jsonl"));
+        assert_eq!(Format::Json, Format::from_path("backup.json"));
+    }
+
+    #[test]
+    fn record_ttl_out_of_range() {
+        assert_eq!(Some(Duration::from_secs(60)), record_ttl(Some(60)).unwrap());
+        assert!(record_ttl(Some(31_536_001)).is_err());
+    }
+}
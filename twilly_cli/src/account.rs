@@ -1,3 +1,7 @@
+pub(crate) mod costs;
+mod keys;
+pub(crate) mod usage;
+
 use std::{process, str::FromStr};
 
 use inquire::{validator::Validation, Confirm, Select, Text};
@@ -9,6 +13,9 @@ use twilly_cli::{
     ActionChoice, FilterChoice,
 };
 
+/// How many subaccounts are read at once when reporting across them.
+const CONCURRENCY: usize = 5;
+
 #[derive(Debug, Clone, Display, EnumIter, EnumString)]
 pub enum Action {
     #[strum(to_string = "Get account")]
@@ -17,12 +24,32 @@ pub enum Action {
     ListAccounts,
     #[strum(to_string = "Create account")]
     CreateAccount,
+    #[strum(to_string = "Manage API keys")]
+    ManageKeys,
+    #[strum(to_string = "Usage and spend")]
+    Usage,
+    #[strum(to_string = "Cost report")]
+    CostReport,
+    #[strum(to_string = "Switch back to parent account")]
+    SwitchToParent,
     Back,
     Exit,
 }
 
-pub async fn choose_account_action(twilio: &Client) {
-    let options: Vec<Action> = Action::iter().collect();
+/// The account chosen to act on from the account menu.
+pub struct AccountSwitch {
+    pub client: Client,
+    /// Friendly name of the subaccount switched into, `None` when back on the parent.
+    pub subaccount: Option<String>,
+}
+
+/// Account actions. Returns the account to switch to if the user chose to act on a
+/// subaccount, or to return to the parent.
+pub async fn choose_account_action(twilio: &Client) -> Option<AccountSwitch> {
+    let in_subaccount = twilio.account_sid() != twilio.config.account_sid;
+    let options: Vec<Action> = Action::iter()
+        .filter(|action| in_subaccount || !matches!(action, Action::SwitchToParent))
+        .collect();
 
     loop {
         let action_selection_prompt = Select::new("Select an action:", options.clone());
@@ -98,7 +125,7 @@ pub async fn choose_account_action(twilio: &Client) {
 
                             // The action we can perform on the account we are using are limited.
                             // Remove it from the list.
-                            accounts.retain(|ac| ac.sid != twilio.config.account_sid);
+                            accounts.retain(|ac| ac.sid != twilio.account_sid());
 
                             if accounts.is_empty() {
                                 println!("No accounts found.");
@@ -154,6 +181,7 @@ pub async fn choose_account_action(twilio: &Client) {
                                     "active" => {
                                         if let Some(account_action) = get_action_choice_from_user(
                                             vec![
+                                                "Switch into this account".into(),
                                                 "Change name".into(),
                                                 "Suspend".into(),
                                                 "Close".into(),
@@ -167,6 +195,23 @@ pub async fn choose_account_action(twilio: &Client) {
                                                 ActionChoice::Exit => process::exit(0),
                                                 ActionChoice::Other(choice) => {
                                                     match choice.as_str() {
+                                                        "Switch into this account" => {
+                                                            println!(
+                                                                "Switched into {}.",
+                                                                selected_account.friendly_name
+                                                            );
+                                                            println!();
+                                                            return Some(AccountSwitch {
+                                                                client: twilio.for_subaccount(
+                                                                    &selected_account.sid,
+                                                                ),
+                                                                subaccount: Some(
+                                                                    selected_account
+                                                                        .friendly_name
+                                                                        .clone(),
+                                                                ),
+                                                            });
+                                                        }
                                                         "Change name" => {
                                                             change_account_name(
                                                                 twilio,
@@ -279,6 +324,17 @@ pub async fn choose_account_action(twilio: &Client) {
                         }
                     }
                 }
+                Action::ManageKeys => keys::choose_key_action(twilio).await,
+                Action::Usage => usage::choose_usage_action(twilio).await,
+                Action::CostReport => costs::show_cost_report(twilio).await,
+                Action::SwitchToParent => {
+                    println!("Switched back to {}.", twilio.config.account_sid);
+                    println!();
+                    return Some(AccountSwitch {
+                        client: Client::new(&twilio.config),
+                        subaccount: None,
+                    });
+                }
                 Action::Back => {
                     break;
                 }
@@ -288,6 +344,8 @@ pub async fn choose_account_action(twilio: &Client) {
             break;
         }
     }
+
+    None
 }
 
 async fn change_account_name(twilio: &Client, account_sid: &str) {
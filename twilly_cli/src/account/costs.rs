@@ -0,0 +1,198 @@
+use std::{cmp::Ordering, fs::File, io::Write};
+
+use futures::{future::join, stream, StreamExt};
+use inquire::{Confirm, Text};
+use serde::Serialize;
+use twilly::{
+    account::{
+        usage::{ListParams, Period},
+        Account, Status,
+    },
+    Client, TwilioError,
+};
+use twilly_cli::prompt_user;
+
+use super::CONCURRENCY;
+
+/// Balance and spend so far this month of the parent account or one of its subaccounts.
+#[derive(Debug, Serialize)]
+pub(crate) struct AccountCost {
+    pub sid: String,
+    pub friendly_name: String,
+    pub status: Status,
+    pub balance: Option<f64>,
+    pub currency: Option<String>,
+    pub month_to_date: Option<f64>,
+}
+
+/// Costs of the account in use and each of its subaccounts, fetched concurrently and
+/// ordered by spend this month, highest first. Closed accounts and any that can't be
+/// read are listed without figures, after the rest.
+pub(crate) async fn account_costs(twilio: &Client) -> Result<Vec<AccountCost>, TwilioError> {
+    let accounts = twilio.accounts().list(None, None).await?;
+    let mut costs: Vec<AccountCost> = stream::iter(accounts)
+        .map(|account| account_cost(twilio, account))
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+
+    sort_by_spend(&mut costs);
+    Ok(costs)
+}
+
+// Orders costs by spend, highest first, with those whose spend is unknown last. Accounts
+// are fetched in no particular order so ties are broken by name.
+fn sort_by_spend(costs: &mut [AccountCost]) {
+    costs.sort_by(|a, b| {
+        match (a.month_to_date, b.month_to_date) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(|| a.friendly_name.cmp(&b.friendly_name))
+    });
+}
+
+async fn account_cost(twilio: &Client, account: Account) -> AccountCost {
+    let mut cost = AccountCost {
+        sid: account.sid,
+        friendly_name: account.friendly_name,
+        status: account.status,
+        balance: None,
+        currency: None,
+        month_to_date: None,
+    };
+    if cost.status == Status::Closed {
+        return cost;
+    }
+
+    let client = twilio.for_subaccount(&cost.sid);
+    let accounts = client.accounts();
+    let usage = accounts.usage();
+    let params = ListParams {
+        category: Some(String::from("totalprice")),
+        include_subaccounts: Some(false),
+        ..Default::default()
+    };
+    let (balance, records) = join(
+        accounts.balance(),
+        usage.records(Period::ThisMonth, &params),
+    )
+    .await;
+
+    match balance {
+        Ok(balance) => {
+            cost.balance = Some(balance.balance);
+            cost.currency = Some(balance.currency);
+        }
+        Err(error) => eprintln!("Unable to get the balance of {}: {}", cost.sid, error),
+    }
+    match records {
+        Ok(records) => cost.month_to_date = Some(records.iter().map(|record| record.price).sum()),
+        Err(error) => eprintln!("Unable to get the usage of {}: {}", cost.sid, error),
+    }
+
+    cost
+}
+
+/// Prints the cost of every account, offering to export it as CSV.
+pub async fn show_cost_report(twilio: &Client) {
+    println!("Retrieving balances and usage...");
+    let costs = match account_costs(twilio).await {
+        Ok(costs) => costs,
+        Err(error) => {
+            eprintln!("Unable to build cost report. Action aborted: {}", error);
+            println!();
+            return;
+        }
+    };
+
+    let figure = |value: Option<f64>| value.map(|value| format!("{:.2}", value));
+    for cost in &costs {
+        println!(
+            "{:<40} {:<34} {:<10} {:>12} {:<4} {:>12}",
+            cost.friendly_name,
+            cost.sid,
+            cost.status,
+            figure(cost.balance).unwrap_or_else(|| String::from("-")),
+            cost.currency.as_deref().unwrap_or_default(),
+            figure(cost.month_to_date).unwrap_or_else(|| String::from("-")),
+        );
+    }
+    println!();
+
+    let export_prompt = Confirm::new("Export to CSV?")
+        .with_placeholder("N")
+        .with_default(false);
+    if prompt_user(export_prompt) != Some(true) {
+        return;
+    }
+
+    let Some(path) =
+        prompt_user(Text::new("Enter the path to write to:").with_default("costs.csv"))
+    else {
+        return;
+    };
+    match File::create(&path)
+        .map_err(csv::Error::from)
+        .and_then(|file| write_csv(&costs, file))
+    {
+        Ok(_) => println!("Cost report written to {}.", path),
+        Err(error) => eprintln!("Unable to write CSV. Action aborted: {}", error),
+    }
+    println!();
+}
+
+fn write_csv(costs: &[AccountCost], out: impl Write) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    for cost in costs {
+        writer.serialize(cost)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(sid: &str, month_to_date: Option<f64>) -> AccountCost {
+        AccountCost {
+            sid: sid.to_string(),
+            friendly_name: sid.to_string(),
+            status: Status::Active,
+            balance: month_to_date.map(|_| 10.0),
+            currency: month_to_date.map(|_| String::from("USD")),
+            month_to_date,
+        }
+    }
+
+    #[test]
+    fn orders_by_spend_with_unknown_last() {
+        let mut costs = vec![
+            cost("AC5", None),
+            cost("AC2", Some(0.0)),
+            cost("AC3", Some(12.5)),
+            cost("AC4", Some(-1.0)),
+            cost("AC1", None),
+        ];
+
+        sort_by_spend(&mut costs);
+        let sids: Vec<&str> = costs.iter().map(|cost| cost.sid.as_str()).collect();
+        assert_eq!(vec!["AC3", "AC2", "AC4", "AC1", "AC5"], sids);
+    }
+
+    #[test]
+    fn writes_csv_with_headers() {
+        let mut out = Vec::new();
+        write_csv(&[cost("AC1", Some(1.5)), cost("AC2", None)], &mut out).unwrap();
+
+        assert_eq!(
+            "sid,friendly_name,status,balance,currency,month_to_date\n\
+             AC1,AC1,active,10.0,USD,1.5\n\
+             AC2,AC2,active,,,\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
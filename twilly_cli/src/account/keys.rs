@@ -0,0 +1,217 @@
+use std::{fs, process};
+
+use inquire::{validator::Validation, Confirm, MultiSelect, Select, Text};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{
+    account::keys::{ApiKey, NewApiKey},
+    Client,
+};
+use twilly_cli::{prompt_user, prompt_user_multi_selection, prompt_user_selection};
+
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum KeysAction {
+    #[strum(to_string = "List API keys")]
+    ListKeys,
+    #[strum(to_string = "Create API key")]
+    CreateKey,
+    #[strum(to_string = "Rename API key")]
+    RenameKey,
+    #[strum(to_string = "Revoke API keys")]
+    RevokeKeys,
+    Back,
+    Exit,
+}
+
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+enum KeyType {
+    #[strum(to_string = "Standard - access to every API except accounts and keys")]
+    Standard,
+    #[strum(to_string = "Restricted - limited by a permissions policy")]
+    Restricted,
+}
+
+pub async fn choose_key_action(twilio: &Client) {
+    let options: Vec<KeysAction> = KeysAction::iter().collect();
+
+    loop {
+        let action_selection_prompt = Select::new("Select an action:", options.clone());
+        let Some(action) = prompt_user_selection(action_selection_prompt) else {
+            break;
+        };
+        let accounts = twilio.accounts();
+
+        match action {
+            KeysAction::ListKeys => match accounts.keys().list().await {
+                Ok(keys) => {
+                    if keys.is_empty() {
+                        println!("No API keys found.");
+                    }
+                    for key in &keys {
+                        println!("{} - created {}", key, key.date_created);
+                    }
+                    println!();
+                }
+                Err(error) => {
+                    eprintln!("Unable to list API keys. Action aborted: {}", error);
+                    println!();
+                }
+            },
+            KeysAction::CreateKey => {
+                let Some(key_type) = prompt_user_selection(Select::new(
+                    "Choose a key type:",
+                    KeyType::iter().collect(),
+                )) else {
+                    continue;
+                };
+                let friendly_name_prompt =
+                    Text::new("Enter a friendly name:").with_validator(|val: &str| {
+                        match val.trim().is_empty() {
+                            true => Ok(Validation::Invalid("Enter at least one character".into())),
+                            false => Ok(Validation::Valid),
+                        }
+                    });
+                let Some(friendly_name) = prompt_user(friendly_name_prompt) else {
+                    continue;
+                };
+
+                println!("Creating API key...");
+                let key = match key_type {
+                    KeyType::Standard => accounts.keys().create(Some(&friendly_name)).await,
+                    KeyType::Restricted => {
+                        let Some(policy) = request_policy() else {
+                            continue;
+                        };
+                        accounts
+                            .keys()
+                            .create_restricted(&friendly_name, &policy)
+                            .await
+                    }
+                };
+
+                match key {
+                    Ok(key) => show_new_key(&key),
+                    Err(error) => {
+                        eprintln!("Unable to create API key. Action aborted: {}", error);
+                        println!();
+                    }
+                }
+            }
+            KeysAction::RenameKey => {
+                let Some(key) = choose_key(twilio).await else {
+                    continue;
+                };
+                let friendly_name_prompt =
+                    Text::new("Provide a name:").with_initial_value(&key.friendly_name);
+                let Some(friendly_name) = prompt_user(friendly_name_prompt) else {
+                    continue;
+                };
+
+                match accounts.key(&key.sid).update(&friendly_name).await {
+                    Ok(key) => {
+                        println!("API key renamed to {}.", key.friendly_name);
+                        println!();
+                    }
+                    Err(error) => {
+                        eprintln!("Unable to rename API key. Action aborted: {}", error);
+                        println!();
+                    }
+                }
+            }
+            KeysAction::RevokeKeys => {
+                let keys = match accounts.keys().list().await {
+                    Ok(keys) if keys.is_empty() => {
+                        println!("No API keys found.");
+                        println!();
+                        continue;
+                    }
+                    Ok(keys) => keys,
+                    Err(error) => {
+                        eprintln!("Unable to list API keys. Action aborted: {}", error);
+                        println!();
+                        continue;
+                    }
+                };
+
+                let Some(revoked) =
+                    prompt_user_multi_selection(MultiSelect::new("Choose keys to revoke:", keys))
+                else {
+                    continue;
+                };
+                if revoked.is_empty() {
+                    continue;
+                }
+
+                let confirm_message = format!(
+                    "Revoke {} keys? Anything using them will stop working immediately.",
+                    revoked.len()
+                );
+                let confirm_prompt = Confirm::new(&confirm_message)
+                    .with_placeholder("N")
+                    .with_default(false);
+                if prompt_user(confirm_prompt) != Some(true) {
+                    continue;
+                }
+
+                for key in &revoked {
+                    match accounts.key(&key.sid).delete().await {
+                        Ok(_) => println!("Revoked {}.", key),
+                        Err(error) => eprintln!("Unable to revoke {}: {}", key, error),
+                    }
+                }
+                println!();
+            }
+            KeysAction::Back => break,
+            KeysAction::Exit => process::exit(0),
+        }
+    }
+}
+
+async fn choose_key(twilio: &Client) -> Option<ApiKey> {
+    let keys = match twilio.accounts().keys().list().await {
+        Ok(keys) => keys,
+        Err(error) => {
+            eprintln!("Unable to list API keys. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+
+    if keys.is_empty() {
+        println!("No API keys found.");
+        println!();
+        return None;
+    }
+
+    prompt_user_selection(Select::new("Choose an API key:", keys))
+}
+
+/// Reads a permissions policy for a restricted key from a JSON file.
+fn request_policy() -> Option<String> {
+    let path_prompt = Text::new("Enter the path of the JSON policy file:")
+        .with_help_message(r#"e.g. {"allow": ["/twilio/messaging/messages/read"]}"#);
+    let path = prompt_user(path_prompt)?;
+
+    match fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|policy| {
+            serde_json::from_str::<serde_json::Value>(&policy)
+                .map(|_| policy)
+                .map_err(|error| error.to_string())
+        }) {
+        Ok(policy) => Some(policy),
+        Err(error) => {
+            eprintln!("Unable to read policy. Action aborted: {}", error);
+            println!();
+            None
+        }
+    }
+}
+
+/// The secret is only returned on creation, so it is shown once here.
+fn show_new_key(key: &NewApiKey) {
+    println!("API key created: {} ({})", key.friendly_name, key.sid);
+    println!("Secret: {}", key.secret);
+    println!("This is the only time the secret is shown. Store it somewhere safe now.");
+    println!();
+}
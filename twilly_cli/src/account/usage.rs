@@ -0,0 +1,319 @@
+use std::process;
+
+use futures::{stream, StreamExt, TryStreamExt};
+use inquire::{validator::Validation, Confirm, Select, Text};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{
+    account::{
+        usage::{
+            summarize_by_category, CategorySpend, CreateTriggerParams, ListParams, Period,
+            Recurring, TriggerBy, UsageRecord, UsageTrigger,
+        },
+        Status,
+    },
+    Client, TwilioError,
+};
+use twilly_cli::{get_date_from_user, prompt_user, prompt_user_selection, DateRange};
+
+use super::CONCURRENCY;
+
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum UsageAction {
+    #[strum(to_string = "Spend report")]
+    SpendReport,
+    #[strum(to_string = "List usage triggers")]
+    ListTriggers,
+    #[strum(to_string = "Create usage trigger")]
+    CreateTrigger,
+    #[strum(to_string = "Delete usage trigger")]
+    DeleteTrigger,
+    Back,
+    Exit,
+}
+
+/// Accounts a spend report covers.
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+enum ReportScope {
+    #[strum(to_string = "This account")]
+    Account,
+    #[strum(to_string = "A subaccount")]
+    Subaccount,
+    #[strum(to_string = "All subaccounts")]
+    AllSubaccounts,
+}
+
+pub async fn choose_usage_action(twilio: &Client) {
+    let options: Vec<UsageAction> = UsageAction::iter().collect();
+
+    loop {
+        let action_selection_prompt = Select::new("Select an action:", options.clone());
+        let Some(action) = prompt_user_selection(action_selection_prompt) else {
+            break;
+        };
+        let accounts = twilio.accounts();
+
+        match action {
+            UsageAction::SpendReport => {
+                if let Err(error) = spend_report(twilio).await {
+                    eprintln!("Unable to build spend report. Action aborted: {}", error);
+                    println!();
+                }
+            }
+            UsageAction::ListTriggers => match accounts.usage().triggers().await {
+                Ok(triggers) => {
+                    if triggers.is_empty() {
+                        println!("No usage triggers found.");
+                    }
+                    for trigger in &triggers {
+                        println!("{}", describe_trigger(trigger));
+                    }
+                    println!();
+                }
+                Err(error) => {
+                    eprintln!("Unable to list usage triggers. Action aborted: {}", error);
+                    println!();
+                }
+            },
+            UsageAction::CreateTrigger => {
+                let Some(params) = request_trigger_params() else {
+                    continue;
+                };
+
+                match accounts.usage().create_trigger(params).await {
+                    Ok(trigger) => {
+                        println!("Usage trigger created: {}", describe_trigger(&trigger));
+                        println!();
+                    }
+                    Err(error) => {
+                        eprintln!("Unable to create usage trigger. Action aborted: {}", error);
+                        println!();
+                    }
+                }
+            }
+            UsageAction::DeleteTrigger => {
+                let triggers = match accounts.usage().triggers().await {
+                    Ok(triggers) if triggers.is_empty() => {
+                        println!("No usage triggers found.");
+                        println!();
+                        continue;
+                    }
+                    Ok(triggers) => triggers,
+                    Err(error) => {
+                        eprintln!("Unable to list usage triggers. Action aborted: {}", error);
+                        println!();
+                        continue;
+                    }
+                };
+
+                let options: Vec<String> = triggers.iter().map(describe_trigger).collect();
+                let Some(choice) =
+                    prompt_user_selection(Select::new("Choose a usage trigger:", options.clone()))
+                else {
+                    continue;
+                };
+                let trigger = &triggers[options
+                    .iter()
+                    .position(|option| option == &choice)
+                    .expect("Chosen trigger should exist")];
+
+                let confirm_prompt = Confirm::new("Are you sure you wish to delete this trigger?")
+                    .with_placeholder("N")
+                    .with_default(false);
+                if prompt_user(confirm_prompt) == Some(true) {
+                    match accounts.usage().delete_trigger(&trigger.sid).await {
+                        Ok(_) => println!("Usage trigger deleted."),
+                        Err(error) => {
+                            eprintln!("Unable to delete usage trigger. Action aborted: {}", error)
+                        }
+                    }
+                    println!();
+                }
+            }
+            UsageAction::Back => break,
+            UsageAction::Exit => process::exit(0),
+        }
+    }
+}
+
+/// Usage Records of the account the client acts on, or of each of its open subaccounts.
+/// Records of several subaccounts are fetched concurrently.
+pub(crate) async fn usage_records(
+    twilio: &Client,
+    all_subaccounts: bool,
+    period: Period,
+    params: &ListParams,
+) -> Result<Vec<UsageRecord>, TwilioError> {
+    if !all_subaccounts {
+        return twilio.accounts().usage().records(period, params).await;
+    }
+
+    // Closed accounts can't be read so would fail the whole report.
+    let subaccounts = twilio.accounts().list(None, None).await?;
+    let clients: Vec<Client> = subaccounts
+        .iter()
+        .filter(|account| account.sid != twilio.account_sid() && account.status != Status::Closed)
+        .map(|account| twilio.for_subaccount(&account.sid))
+        .collect();
+    let records: Vec<Vec<UsageRecord>> = stream::iter(&clients)
+        .map(|client| async move { client.accounts().usage().records(period, params).await })
+        .buffer_unordered(CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    Ok(records.into_iter().flatten().collect())
+}
+
+/// Prints spend per category for the accounts and period chosen by the user.
+async fn spend_report(twilio: &Client) -> Result<(), TwilioError> {
+    let Some(scope) =
+        prompt_user_selection(Select::new("Report on:", ReportScope::iter().collect()))
+    else {
+        return Ok(());
+    };
+
+    let subaccount = match scope {
+        ReportScope::Subaccount => {
+            let mut subaccounts = twilio.accounts().list(None, None).await?;
+            subaccounts.retain(|account| account.sid != twilio.account_sid());
+            if subaccounts.is_empty() {
+                println!("No subaccounts found.");
+                println!();
+                return Ok(());
+            }
+            let Some(subaccount) =
+                prompt_user_selection(Select::new("Choose a subaccount:", subaccounts))
+            else {
+                return Ok(());
+            };
+            Some(twilio.for_subaccount(&subaccount.sid))
+        }
+        _ => None,
+    };
+
+    let Some((period, params)) = request_period() else {
+        return Ok(());
+    };
+
+    println!("Retrieving usage...");
+    let records = usage_records(
+        subaccount.as_ref().unwrap_or(twilio),
+        matches!(scope, ReportScope::AllSubaccounts),
+        period,
+        &params,
+    )
+    .await?;
+    print_spend(&summarize_by_category(&records));
+    Ok(())
+}
+
+/// Asks for the period to report on, and the dates it covers for those needing a range.
+fn request_period() -> Option<(Period, ListParams)> {
+    let period = prompt_user_selection(
+        Select::new("Choose a period:", Period::iter().collect())
+            .with_starting_cursor(Period::iter().position(|p| p == Period::default())?),
+    )?;
+
+    let mut params = ListParams {
+        include_subaccounts: Some(false),
+        ..Default::default()
+    };
+    if matches!(
+        period,
+        Period::Total | Period::Daily | Period::Monthly | Period::Yearly
+    ) {
+        let start_date = get_date_from_user("Start date:", None)?;
+        let end_date = get_date_from_user(
+            "End date:",
+            Some(DateRange {
+                minimum_date: start_date,
+                maximum_date: chrono::Local::now().date_naive(),
+            }),
+        )?;
+        params.start_date = Some(start_date);
+        params.end_date = Some(end_date);
+    }
+
+    Some((period, params))
+}
+
+fn print_spend(spend: &[CategorySpend]) {
+    if spend.is_empty() {
+        println!("No spend found.");
+        println!();
+        return;
+    }
+
+    for category in spend {
+        println!(
+            "{:<50} {:>14.2} {:<12} {:>12.4} {}",
+            category.description,
+            category.usage,
+            category.usage_unit.as_deref().unwrap_or_default(),
+            category.price,
+            category
+                .price_unit
+                .as_deref()
+                .unwrap_or_default()
+                .to_uppercase()
+        );
+    }
+    println!();
+}
+
+fn request_trigger_params() -> Option<CreateTriggerParams> {
+    let category_prompt = Text::new("Enter the usage category:")
+        .with_placeholder("totalprice")
+        .with_help_message("e.g. sms, calls or totalprice for all spend")
+        .with_validator(|val: &str| match val.trim().is_empty() {
+            true => Ok(Validation::Invalid("Enter a usage category".into())),
+            false => Ok(Validation::Valid),
+        });
+    let usage_category = prompt_user(category_prompt)?;
+
+    let trigger_by =
+        prompt_user_selection(Select::new("Trigger by:", TriggerBy::iter().collect()))?;
+    let value_prompt =
+        Text::new("Enter the threshold:").with_validator(|val: &str| match val.parse::<f64>() {
+            Ok(_) => Ok(Validation::Valid),
+            Err(_) => Ok(Validation::Invalid("Enter a number".into())),
+        });
+    let trigger_value = prompt_user(value_prompt)?;
+
+    let recurring = prompt_user_selection(Select::new(
+        "Reset the trigger:",
+        Recurring::iter().collect(),
+    ))?;
+    let callback_prompt =
+        Text::new("Enter the callback URL:").with_validator(|val: &str| {
+            match val.starts_with("https://") || val.starts_with("http://") {
+                true => Ok(Validation::Valid),
+                false => Ok(Validation::Invalid("Enter an http(s) URL".into())),
+            }
+        });
+    let callback_url = prompt_user(callback_prompt)?;
+    let friendly_name = prompt_user(Text::new("Enter a friendly name (empty for none):"))?;
+
+    Some(CreateTriggerParams {
+        callback_url,
+        trigger_value,
+        usage_category,
+        callback_method: None,
+        friendly_name: Some(friendly_name).filter(|name| !name.is_empty()),
+        recurring: Some(recurring),
+        trigger_by: Some(trigger_by),
+    })
+}
+
+fn describe_trigger(trigger: &UsageTrigger) -> String {
+    format!(
+        "{} ({}): {} {} >= {}, {} -> {}",
+        trigger.friendly_name.as_deref().unwrap_or(&trigger.sid),
+        trigger.sid,
+        trigger.usage_category,
+        trigger.trigger_by,
+        trigger.trigger_value,
+        trigger.recurring.as_deref().unwrap_or("once"),
+        trigger.callback_url
+    )
+}
@@ -0,0 +1,149 @@
+mod account;
+mod conversation;
+mod output;
+mod profile;
+mod serverless;
+mod sync;
+
+use std::{env, fmt::Display};
+
+use clap::{Parser, Subcommand};
+use strum::IntoEnumIterator;
+use twilly::{Client, ErrorKind, TwilioConfig, TwilioError};
+
+use crate::profile::Profiles;
+
+use output::print;
+pub use output::{Format, Output};
+
+/// Interact with Twilio from the terminal. Run without a command for the interactive menus.
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    /// How command results are written to stdout.
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Text)]
+    pub output: Format,
+    /// Comma separated fields to include in table and CSV output, e.g. `sid,friendly_name`.
+    /// Nested fields are joined with `.`.
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub columns: Vec<String>,
+    /// Name of the stored profile to use instead of the default.
+    #[arg(long, short, global = true)]
+    pub profile: Option<String>,
+    /// SID of a subaccount to act on using the profile's credentials.
+    #[arg(long, global = true)]
+    pub subaccount: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Accounts and subaccounts.
+    #[command(subcommand)]
+    Account(account::AccountCommand),
+    /// Conversations.
+    #[command(subcommand)]
+    Conversations(conversation::ConversationCommand),
+    /// Sync Services, Documents, Maps and Lists.
+    #[command(subcommand)]
+    Sync(sync::SyncCommand),
+    /// Serverless Services, Environments, Functions and Assets.
+    #[command(subcommand)]
+    Serverless(serverless::ServerlessCommand),
+    /// Stored credential profiles.
+    #[command(subcommand)]
+    Profiles(profile::ProfilesCommand),
+}
+
+/// Loads credentials for a scripted run without prompting. A profile passed with
+/// `--profile` is used first, then `TWILIO_ACCOUNT_SID` and `TWILIO_AUTH_TOKEN`, then the
+/// default profile.
+pub fn load_config(profile: Option<&str>) -> Result<TwilioConfig, TwilioError> {
+    let validation_error = |message: String| TwilioError {
+        kind: ErrorKind::ValidationError(message),
+    };
+
+    if let Some(name) = profile {
+        return Profiles::load().config(name).map_err(validation_error);
+    }
+
+    if let (Ok(account_sid), Ok(auth_token)) = (
+        env::var("TWILIO_ACCOUNT_SID"),
+        env::var("TWILIO_AUTH_TOKEN"),
+    ) {
+        return Ok(TwilioConfig {
+            account_sid,
+            auth_token,
+        });
+    }
+
+    let mut profiles = Profiles::load();
+    match profiles.default_profile().map(|(name, _)| name.clone()) {
+        Some(name) => profiles.config(&name).map_err(validation_error),
+        None => Err(validation_error(String::from(
+            "No credentials found. Set TWILIO_ACCOUNT_SID and TWILIO_AUTH_TOKEN or run twilly_cli without a command to add a profile",
+        ))),
+    }
+}
+
+/// Runs a single command to completion.
+pub async fn run(
+    command: Command,
+    profile: Option<&str>,
+    subaccount: Option<&str>,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    if let Command::Profiles(command) = command {
+        return profile::run(command, output).await;
+    }
+
+    let mut twilio = Client::new(&load_config(profile)?);
+    if let Some(subaccount) = subaccount {
+        twilio = twilio.for_subaccount(subaccount);
+    }
+    match command {
+        Command::Account(command) => account::run(&twilio, command, output).await,
+        Command::Conversations(command) => conversation::run(&twilio, command, output).await,
+        Command::Sync(command) => sync::run(&twilio, command, output).await,
+        Command::Serverless(command) => serverless::run(&twilio, command, output).await,
+        Command::Profiles(_) => unreachable!("Profile commands are run without a client"),
+    }
+}
+
+/// Parses an argument into one of the variants of `T`, matching the variant's display
+/// name case-insensitively. Spaces in the name may be given as `-`, e.g. `this-month`.
+fn parse_variant<T: IntoEnumIterator + Display>(value: &str) -> Result<T, String> {
+    T::iter()
+        .find(|variant| {
+            variant
+                .to_string()
+                .replace(' ', "-")
+                .eq_ignore_ascii_case(&value.replace(' ', "-"))
+        })
+        .ok_or_else(|| {
+            format!(
+                "expected one of: {}",
+                T::iter()
+                    .map(|variant| variant.to_string().replace(' ', "-").to_lowercase())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Rejects a destructive command unless `--yes` was passed.
+fn require_confirmation(confirmed: bool, action: &str) -> Result<(), TwilioError> {
+    match confirmed {
+        true => Ok(()),
+        false => Err(TwilioError {
+            kind: ErrorKind::ValidationError(format!("Pass --yes to confirm {}", action)),
+        }),
+    }
+}
+
+fn io_error(error: std::io::Error) -> TwilioError {
+    TwilioError {
+        kind: ErrorKind::IoError(error),
+    }
+}
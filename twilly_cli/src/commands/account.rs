@@ -0,0 +1,315 @@
+use std::{fs, path::PathBuf};
+
+use chrono::NaiveDate;
+use clap::Subcommand;
+use twilly::{
+    account::{
+        usage::{
+            summarize_by_category, CreateTriggerParams, ListParams, Period, Recurring, TriggerBy,
+        },
+        Status,
+    },
+    Client, TwilioError,
+};
+
+use crate::account::{costs::account_costs, usage::usage_records};
+
+use super::{io_error, parse_variant, print, require_confirmation, Output};
+
+#[derive(Subcommand)]
+pub enum AccountCommand {
+    /// Fetch an account, defaulting to the one in use.
+    Get {
+        /// Account SID, begins with AC...
+        #[arg(long)]
+        sid: Option<String>,
+    },
+    /// List the account and its subaccounts.
+    List {
+        #[arg(long)]
+        friendly_name: Option<String>,
+        /// active, suspended or closed.
+        #[arg(long, value_parser = parse_variant::<Status>)]
+        status: Option<Status>,
+        /// Leave out the account in use.
+        #[arg(long)]
+        subaccounts_only: bool,
+    },
+    /// Create a subaccount.
+    Create {
+        #[arg(long)]
+        friendly_name: Option<String>,
+    },
+    /// Change the friendly name of an account.
+    Rename { sid: String, friendly_name: String },
+    /// Suspend an active account.
+    Suspend { sid: String },
+    /// Re-activate a suspended account.
+    Activate { sid: String },
+    /// Permanently close an account. This cannot be undone.
+    Close {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// API keys of the account in use.
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    /// Usage records, spend and usage triggers of the account in use.
+    #[command(subcommand)]
+    Usage(UsageCommand),
+    /// Balance, status and spend this month of the account and each subaccount, highest
+    /// spend first.
+    Costs,
+}
+
+#[derive(Subcommand)]
+pub enum UsageCommand {
+    /// List usage records, one per category for each period.
+    Records {
+        #[command(flatten)]
+        range: UsageRange,
+        /// Only include this usage category, e.g. sms.
+        #[arg(long)]
+        category: Option<String>,
+    },
+    /// Spend per category, highest first.
+    Report {
+        #[command(flatten)]
+        range: UsageRange,
+        /// Sum the spend of every subaccount instead of the account in use.
+        #[arg(long)]
+        all_subaccounts: bool,
+    },
+    #[command(subcommand)]
+    Triggers(TriggersCommand),
+}
+
+#[derive(clap::Args)]
+pub struct UsageRange {
+    /// total, daily, monthly, yearly, today, this-month or last-month.
+    #[arg(long, value_parser = parse_variant::<Period>, default_value = "this-month")]
+    period: Period,
+    /// First day included (YYYY-MM-DD).
+    #[arg(long)]
+    start_date: Option<NaiveDate>,
+    /// Last day included (YYYY-MM-DD).
+    #[arg(long)]
+    end_date: Option<NaiveDate>,
+}
+
+#[derive(Subcommand)]
+pub enum TriggersCommand {
+    List,
+    /// Call a URL once usage of a category reaches a threshold.
+    Create {
+        /// Usage category, e.g. sms or totalprice.
+        #[arg(long)]
+        category: String,
+        #[arg(long)]
+        threshold: String,
+        /// count, usage or price.
+        #[arg(long, value_parser = parse_variant::<TriggerBy>)]
+        trigger_by: Option<TriggerBy>,
+        /// daily, monthly, yearly or never.
+        #[arg(long, value_parser = parse_variant::<Recurring>)]
+        recurring: Option<Recurring>,
+        #[arg(long)]
+        callback_url: String,
+        #[arg(long)]
+        callback_method: Option<String>,
+        #[arg(long)]
+        friendly_name: Option<String>,
+    },
+    Delete {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeysCommand {
+    List,
+    Get {
+        sid: String,
+    },
+    /// Create a key. The secret is only included in this output.
+    Create {
+        #[arg(long)]
+        friendly_name: Option<String>,
+        /// JSON permissions policy file. Creates a restricted key limited to it.
+        #[arg(long, requires = "friendly_name")]
+        policy: Option<PathBuf>,
+    },
+    Rename {
+        sid: String,
+        friendly_name: String,
+    },
+    /// Revoke a key. Requests signed with it fail immediately.
+    Delete {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+pub async fn run(
+    twilio: &Client,
+    command: AccountCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let accounts = twilio.accounts();
+
+    match command {
+        AccountCommand::Get { sid } => print(&accounts.get(sid.as_deref()).await?, output)?,
+        AccountCommand::List {
+            friendly_name,
+            status,
+            subaccounts_only,
+        } => {
+            let mut account_list = accounts
+                .list(friendly_name.as_deref(), status.as_ref())
+                .await?;
+            if subaccounts_only {
+                account_list.retain(|account| account.sid != twilio.account_sid());
+            }
+            print(&account_list, output)?;
+        }
+        AccountCommand::Create { friendly_name } => {
+            print(&accounts.create(friendly_name.as_deref()).await?, output)?
+        }
+        AccountCommand::Rename { sid, friendly_name } => print(
+            &accounts.update(&sid, Some(&friendly_name), None).await?,
+            output,
+        )?,
+        AccountCommand::Suspend { sid } => print(
+            &accounts
+                .update(&sid, None, Some(&Status::Suspended))
+                .await?,
+            output,
+        )?,
+        AccountCommand::Activate { sid } => print(
+            &accounts.update(&sid, None, Some(&Status::Active)).await?,
+            output,
+        )?,
+        AccountCommand::Close { sid, yes } => {
+            require_confirmation(yes, "closing the account")?;
+            print(
+                &accounts.update(&sid, None, Some(&Status::Closed)).await?,
+                output,
+            )?
+        }
+        AccountCommand::Keys(command) => run_keys(twilio, command, output).await?,
+        AccountCommand::Usage(command) => run_usage(twilio, command, output).await?,
+        AccountCommand::Costs => print(&account_costs(twilio).await?, output)?,
+    }
+
+    Ok(())
+}
+
+async fn run_keys(
+    twilio: &Client,
+    command: KeysCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let accounts = twilio.accounts();
+
+    match command {
+        KeysCommand::List => print(&accounts.keys().list().await?, output)?,
+        KeysCommand::Get { sid } => print(&accounts.key(&sid).get().await?, output)?,
+        KeysCommand::Create {
+            friendly_name,
+            policy,
+        } => {
+            let key = match (friendly_name, policy) {
+                (Some(friendly_name), Some(policy)) => {
+                    let policy = fs::read_to_string(policy).map_err(io_error)?;
+                    accounts
+                        .keys()
+                        .create_restricted(&friendly_name, &policy)
+                        .await?
+                }
+                (friendly_name, _) => accounts.keys().create(friendly_name.as_deref()).await?,
+            };
+            print(&key, output)?;
+        }
+        KeysCommand::Rename { sid, friendly_name } => {
+            print(&accounts.key(&sid).update(&friendly_name).await?, output)?
+        }
+        KeysCommand::Delete { sid, yes } => {
+            require_confirmation(yes, "revoking the key")?;
+            accounts.key(&sid).delete().await?;
+            eprintln!("Key revoked.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_usage(
+    twilio: &Client,
+    command: UsageCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let usage = twilio.accounts().usage();
+
+    match command {
+        UsageCommand::Records { range, category } => {
+            let params = ListParams {
+                category,
+                ..range.params()
+            };
+            print(&usage.records(range.period, &params).await?, output)?
+        }
+        UsageCommand::Report {
+            range,
+            all_subaccounts,
+        } => {
+            let records =
+                usage_records(twilio, all_subaccounts, range.period, &range.params()).await?;
+            print(&summarize_by_category(&records), output)?
+        }
+        UsageCommand::Triggers(TriggersCommand::List) => print(&usage.triggers().await?, output)?,
+        UsageCommand::Triggers(TriggersCommand::Create {
+            category,
+            threshold,
+            trigger_by,
+            recurring,
+            callback_url,
+            callback_method,
+            friendly_name,
+        }) => print(
+            &usage
+                .create_trigger(CreateTriggerParams {
+                    callback_url,
+                    trigger_value: threshold,
+                    usage_category: category,
+                    callback_method,
+                    friendly_name,
+                    recurring,
+                    trigger_by,
+                })
+                .await?,
+            output,
+        )?,
+        UsageCommand::Triggers(TriggersCommand::Delete { sid, yes }) => {
+            require_confirmation(yes, "deleting the usage trigger")?;
+            usage.delete_trigger(&sid).await?;
+            eprintln!("Usage trigger deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+impl UsageRange {
+    fn params(&self) -> ListParams {
+        ListParams {
+            start_date: self.start_date,
+            end_date: self.end_date,
+            include_subaccounts: Some(false),
+            ..Default::default()
+        }
+    }
+}
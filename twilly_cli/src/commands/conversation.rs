@@ -0,0 +1,137 @@
+use chrono::NaiveDate;
+use clap::Subcommand;
+use twilly::{
+    conversation::{Conversation, State, UpdateConversation},
+    Client, TwilioError,
+};
+
+use super::{parse_variant, print, require_confirmation, Output};
+
+#[derive(Subcommand)]
+pub enum ConversationCommand {
+    /// Fetch a Conversation by SID or unique name.
+    Get { sid: String },
+    /// List Conversations, most recently active first.
+    List {
+        /// active, inactive or closed.
+        #[arg(long, value_parser = parse_variant::<State>)]
+        state: Option<State>,
+        /// Only Conversations started on or after this date (YYYY-MM-DD).
+        #[arg(long)]
+        start_date: Option<NaiveDate>,
+        /// Only Conversations started on or before this date (YYYY-MM-DD).
+        #[arg(long)]
+        end_date: Option<NaiveDate>,
+    },
+    /// List the Conversations a participant belongs to.
+    ListByParticipant {
+        /// Identity of a Conversations SDK participant.
+        #[arg(long, required_unless_present = "address")]
+        identity: Option<String>,
+        /// Address a participant communicates on, e.g. a phone number.
+        #[arg(long)]
+        address: Option<String>,
+    },
+    /// Mark a Conversation as active.
+    Reactivate { sid: String },
+    /// Mark a Conversation as inactive.
+    Deactivate { sid: String },
+    /// Close a Conversation.
+    Close { sid: String },
+    /// Close every active Conversation.
+    CloseAll {
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Delete a Conversation.
+    Delete {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Delete every Conversation. There is no going back.
+    DeleteAll {
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+pub async fn run(
+    twilio: &Client,
+    command: ConversationCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let conversations = twilio.conversations();
+
+    match command {
+        ConversationCommand::Get { sid } => print(&conversations.get(&sid).await?, output)?,
+        ConversationCommand::List {
+            state,
+            start_date,
+            end_date,
+        } => print(
+            &conversations.list(start_date, end_date, state).await?,
+            output,
+        )?,
+        ConversationCommand::ListByParticipant { identity, address } => print(
+            &conversations
+                .participant_conversations()
+                .list(identity, address)
+                .await?,
+            output,
+        )?,
+        ConversationCommand::Reactivate { sid } => {
+            print(&set_state(twilio, &sid, State::Active).await?, output)?
+        }
+        ConversationCommand::Deactivate { sid } => {
+            print(&set_state(twilio, &sid, State::Inactive).await?, output)?
+        }
+        ConversationCommand::Close { sid } => {
+            print(&set_state(twilio, &sid, State::Closed).await?, output)?
+        }
+        ConversationCommand::CloseAll { yes } => {
+            require_confirmation(yes, "closing all Conversations")?;
+            let active = conversations.list(None, None, Some(State::Active)).await?;
+            for conversation in &active {
+                set_state(twilio, &conversation.sid, State::Closed).await?;
+                eprintln!("Closed {}", conversation.sid);
+                // Matches the interactive menu, one per second to avoid overwhelming Twilio.
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+            eprintln!("Closed {} Conversations.", active.len());
+        }
+        ConversationCommand::Delete { sid, yes } => {
+            require_confirmation(yes, "deleting the Conversation")?;
+            conversations.delete(&sid).await?;
+            eprintln!("Conversation deleted.");
+        }
+        ConversationCommand::DeleteAll { yes } => {
+            require_confirmation(yes, "deleting all Conversations")?;
+            let all = conversations.list(None, None, None).await?;
+            for conversation in &all {
+                conversations.delete(&conversation.sid).await?;
+                eprintln!("Deleted {}", conversation.sid);
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+            eprintln!("Deleted {} Conversations.", all.len());
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_state(twilio: &Client, sid: &str, state: State) -> Result<Conversation, TwilioError> {
+    twilio
+        .conversations()
+        .update(
+            sid,
+            UpdateConversation {
+                unique_name: None,
+                friendly_name: None,
+                state: Some(state),
+                attributes: None,
+                timers: None,
+            },
+        )
+        .await
+}
@@ -0,0 +1,235 @@
+use std::{
+    fmt::Debug,
+    io::{self, Write},
+};
+
+use clap::ValueEnum;
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Table};
+use serde::Serialize;
+use serde_json::Value;
+use twilly::{ErrorKind, TwilioError};
+
+use super::io_error;
+
+/// Formats results can be written in.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Human readable details, as shown in the interactive menus.
+    Text,
+    /// A table with a row per result.
+    Table,
+    /// Pretty printed JSON.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Comma separated values with a header row.
+    Csv,
+    /// YAML, as accepted by most configuration tooling.
+    Yaml,
+}
+
+/// How command results are written to stdout.
+pub struct Output {
+    pub format: Format,
+    /// Fields included in table and CSV output. Every top-level field that isn't an object
+    /// or array is included when empty. Fields not found in any result are rejected.
+    pub columns: Vec<String>,
+}
+
+impl Output {
+    pub fn is_text(&self) -> bool {
+        matches!(self.format, Format::Text)
+    }
+}
+
+/// Writes a result to stdout in the requested format. Lists are written a row per item.
+pub fn print<T: Serialize + Debug>(value: &T, output: &Output) -> Result<(), TwilioError> {
+    write(&mut io::stdout().lock(), value, output)
+}
+
+fn write<T: Serialize + Debug>(
+    out: &mut impl Write,
+    value: &T,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let rows = || match serde_json::to_value(value).expect("Result should serialize to JSON") {
+        Value::Array(rows) => rows,
+        value => vec![value],
+    };
+
+    match output.format {
+        Format::Text => writeln!(out, "{:#?}", value).map_err(io_error),
+        Format::Json => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(value).expect("Result should serialize to JSON")
+        )
+        .map_err(io_error),
+        Format::Yaml => write!(
+            out,
+            "{}",
+            serde_yaml::to_string(value).expect("Result should serialize to YAML")
+        )
+        .map_err(io_error),
+        Format::Ndjson => rows()
+            .iter()
+            .try_for_each(|row| writeln!(out, "{}", row))
+            .map_err(io_error),
+        Format::Table => {
+            let rows = rows();
+            let columns = columns(&rows, &output.columns)?;
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED).set_header(&columns);
+            for row in &rows {
+                table.add_row(columns.iter().map(|column| cell(row, column)));
+            }
+            writeln!(out, "{}", table).map_err(io_error)
+        }
+        Format::Csv => {
+            let rows = rows();
+            let columns = columns(&rows, &output.columns)?;
+            write_csv(out, &rows, &columns).map_err(|error| io_error(error.into()))
+        }
+    }
+}
+
+fn write_csv(out: &mut impl Write, rows: &[Value], columns: &[String]) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(columns)?;
+    for row in rows {
+        writer.write_record(columns.iter().map(|column| cell(row, column)))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The requested columns, or every scalar field found across the rows in the order they
+/// first appear. Requested columns must be found in at least one row.
+fn columns(rows: &[Value], requested: &[String]) -> Result<Vec<String>, TwilioError> {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        match row {
+            Value::Object(fields) => {
+                for (name, value) in fields {
+                    if !value.is_object() && !value.is_array() && !columns.contains(name) {
+                        columns.push(name.clone());
+                    }
+                }
+            }
+            _ if columns.is_empty() => columns.push(String::from("value")),
+            _ => {}
+        }
+    }
+    if requested.is_empty() {
+        return Ok(columns);
+    }
+
+    match requested
+        .iter()
+        .find(|column| !rows.is_empty() && rows.iter().all(|row| field(row, column).is_none()))
+    {
+        Some(unknown) => Err(TwilioError {
+            kind: ErrorKind::ValidationError(format!(
+                "Unknown column {}, expected one of: {}",
+                unknown,
+                columns.join(", ")
+            )),
+        }),
+        None => Ok(requested.to_vec()),
+    }
+}
+
+/// The value of a column within a row. Nested fields are reached by joining names with
+/// `.`, e.g. `links.deployments`.
+fn field<'a>(row: &'a Value, column: &str) -> Option<&'a Value> {
+    match row {
+        Value::Object(_) => column
+            .split('.')
+            .try_fold(row, |value, field| value.get(field)),
+        row if column == "value" => Some(row),
+        _ => None,
+    }
+}
+
+fn cell(row: &Value, column: &str) -> String {
+    match field(row, column) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(value: &Value, format: Format, columns: &[&str]) -> Result<String, TwilioError> {
+        let output = Output {
+            format,
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+        };
+        let mut out = Vec::new();
+        write(&mut out, value, &output)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    fn accounts() -> Value {
+        json!([
+            { "sid": "AC1", "friendly_name": "Main", "links": { "keys": "/Keys" } },
+            { "sid": "AC2", "friendly_name": null, "status": "closed" },
+        ])
+    }
+
+    #[test]
+    fn csv_has_every_scalar_column() {
+        assert_eq!(
+            "sid,friendly_name,status\nAC1,Main,\nAC2,,closed\n",
+            render(&accounts(), Format::Csv, &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn filters_columns() {
+        assert_eq!(
+            "status,links.keys\n,/Keys\nclosed,\n",
+            render(&accounts(), Format::Csv, &["status", "links.keys"]).unwrap()
+        );
+
+        let table = render(&accounts(), Format::Table, &["sid"]).unwrap();
+        assert!(table.contains("AC2"));
+        assert!(!table.contains("Main"));
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        for format in [Format::Csv, Format::Table] {
+            let error = render(&accounts(), format, &["sid", "name"]).err().unwrap();
+            match error.kind {
+                ErrorKind::ValidationError(message) => assert_eq!(
+                    "Unknown column name, expected one of: sid, friendly_name, status",
+                    message
+                ),
+                _ => panic!("Expected a validation error"),
+            }
+        }
+    }
+
+    #[test]
+    fn writes_ndjson_and_yaml() {
+        assert_eq!(
+            "{\"sid\":\"AC1\",\"friendly_name\":\"Main\",\"links\":{\"keys\":\"/Keys\"}}\n\
+             {\"sid\":\"AC2\",\"friendly_name\":null,\"status\":\"closed\"}\n",
+            render(&accounts(), Format::Ndjson, &[]).unwrap()
+        );
+        assert_eq!(
+            "sid: AC1\nfriendly_name: Main\n",
+            render(
+                &json!({ "sid": "AC1", "friendly_name": "Main" }),
+                Format::Yaml,
+                &[]
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use clap::Subcommand;
+use serde::Serialize;
+use twilly::{Client, ErrorKind, TwilioConfig, TwilioError};
+
+use super::{print, require_confirmation, Output};
+use crate::profile::{rotate_auth_token, Profile, Profiles};
+
+#[derive(Subcommand)]
+pub enum ProfilesCommand {
+    /// List stored profiles. Auth tokens are never shown.
+    List,
+    /// Check a set of credentials and store them under a name.
+    Add {
+        name: String,
+        /// Account SID, begins with AC...
+        #[arg(long)]
+        account_sid: String,
+        #[arg(long, env = "TWILIO_AUTH_TOKEN", hide_env_values = true)]
+        auth_token: String,
+    },
+    /// Rename a profile, keeping it as the default if it was.
+    Rename { name: String, new_name: String },
+    /// Remove a profile. Its auth token is deleted unless another profile uses the account.
+    Remove { name: String },
+    /// Use a profile when no `--profile` is given.
+    SetDefault { name: String },
+    /// Replace a profile's auth token by creating, checking and promoting a secondary
+    /// token. The stored profile is updated with the new token.
+    RotateAuthToken {
+        name: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// A profile as listed, without its auth token.
+#[derive(Debug, Serialize)]
+struct ProfileSummary {
+    name: String,
+    account_sid: String,
+    friendly_name: String,
+    default: bool,
+}
+
+pub async fn run(command: ProfilesCommand, output: &Output) -> Result<(), TwilioError> {
+    let mut profiles = Profiles::load();
+
+    match command {
+        ProfilesCommand::List => {
+            let default_name = profiles.default_profile().map(|(name, _)| name.clone());
+            let summaries: Vec<ProfileSummary> = profiles
+                .profiles
+                .iter()
+                .map(|(name, profile)| ProfileSummary {
+                    name: name.clone(),
+                    account_sid: profile.account_sid.clone(),
+                    friendly_name: profile.friendly_name.clone(),
+                    default: Some(name) == default_name.as_ref(),
+                })
+                .collect();
+            print(&summaries, output)?;
+        }
+        ProfilesCommand::Add {
+            name,
+            account_sid,
+            auth_token,
+        } => {
+            // Checked up front so a bad name fails before the account is looked up.
+            profiles.check_name(&name).map_err(validation_error)?;
+            if !account_sid.starts_with("AC") || account_sid.len() != 34 {
+                return Err(validation_error(String::from(
+                    "Account SID should start with AC and be 34 characters in length",
+                )));
+            }
+            if auth_token.len() != 32 {
+                return Err(validation_error(String::from(
+                    "Auth token should be 32 characters in length",
+                )));
+            }
+
+            let config = TwilioConfig {
+                account_sid,
+                auth_token,
+            };
+            let account = Client::new(&config).accounts().get(None).await?;
+            let name = profiles
+                .insert(
+                    &name,
+                    Profile::new(config.account_sid, account.friendly_name),
+                    &config.auth_token,
+                )
+                .map_err(validation_error)?;
+            profiles.save();
+            eprintln!("Profile {} added.", name);
+        }
+        ProfilesCommand::Rename { name, new_name } => {
+            profiles
+                .rename(&name, &new_name)
+                .map_err(validation_error)?;
+            profiles.save();
+            eprintln!("Profile renamed.");
+        }
+        ProfilesCommand::Remove { name } => {
+            profiles
+                .remove(&name)
+                .ok_or_else(|| validation_error(format!("No profile named {}", name)))?;
+            profiles.save();
+            eprintln!("Profile removed.");
+        }
+        ProfilesCommand::SetDefault { name } => {
+            profiles.set_default(&name).map_err(validation_error)?;
+            profiles.save();
+            eprintln!("{} is now the default profile.", name);
+        }
+        ProfilesCommand::RotateAuthToken { name, yes } => {
+            require_confirmation(yes, "rotating the auth token")?;
+            rotate_auth_token(&mut profiles, &name)
+                .await
+                .map_err(validation_error)?;
+            eprintln!("Auth token rotated and stored.");
+        }
+    }
+
+    Ok(())
+}
+
+fn validation_error(message: String) -> TwilioError {
+    TwilioError {
+        kind: ErrorKind::ValidationError(message),
+    }
+}
@@ -0,0 +1,974 @@
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use futures::StreamExt;
+use regex::Regex;
+use twilly::{
+    serverless::{
+        builds::{BuildStatus, CreateParams as CreateBuildParams},
+        environments::{
+            deployments,
+            logs::{
+                export::{export_file_name, ExportFormat, LogExport},
+                Level, LogFilter,
+            },
+            variables::{parse_env_file, CreateParams as CreateVariableParams, UpdateParams},
+        },
+        functions::{self, versions::CreateParams as CreateVersionParams},
+        project::{DeployOptions, Project, ProjectManifest},
+        services::{CreateOrUpdateParams, Service},
+        upload::Manifest,
+        Visibility,
+    },
+    Client, ErrorKind, TwilioError,
+};
+
+use super::{io_error, parse_variant, print, require_confirmation, Format, Output};
+use crate::serverless::{
+    environments::{
+        deploy::{latest_versions, parse_dependencies},
+        deployments::print_diff,
+        logs::print_log,
+    },
+    project::{print_event, MANIFEST_FILE},
+};
+
+#[derive(Subcommand)]
+pub enum ServerlessCommand {
+    /// Serverless Services.
+    #[command(subcommand)]
+    Services(ServicesCommand),
+    /// Deploy a local Serverless project, creating the Service and Environment if needed.
+    Deploy {
+        /// Project directory, laid out as for the Serverless Toolkit.
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// Service unique name. Defaults to the `name` in package.json.
+        #[arg(long)]
+        service_name: Option<String>,
+        #[arg(long, default_value = "dev")]
+        environment: String,
+        /// Domain suffix of the Environment if it's created, e.g. `dev`.
+        #[arg(long)]
+        domain_suffix: Option<String>,
+    },
+    /// Environments of a Service.
+    #[command(subcommand)]
+    Environments(EnvironmentsCommand),
+    /// Functions of a Service.
+    #[command(subcommand)]
+    Functions(FunctionsCommand),
+    /// Assets of a Service.
+    #[command(subcommand)]
+    Assets(AssetsCommand),
+    /// Logs of an Environment.
+    #[command(subcommand)]
+    Logs(LogsCommand),
+    /// Variables of an Environment.
+    #[command(subcommand)]
+    Variables(VariablesCommand),
+}
+
+#[derive(Subcommand)]
+pub enum ServicesCommand {
+    List,
+    Get {
+        sid: String,
+    },
+    Create {
+        unique_name: String,
+        /// Defaults to the unique name.
+        #[arg(long)]
+        friendly_name: Option<String>,
+        /// Make the account credentials available to Functions.
+        #[arg(long)]
+        include_credentials: bool,
+        /// Allow the Service to be edited in the Twilio Console.
+        #[arg(long)]
+        ui_editable: bool,
+    },
+    Delete {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct EnvironmentArgs {
+    #[arg(long)]
+    service: String,
+    #[arg(long)]
+    environment: String,
+}
+
+#[derive(Subcommand)]
+pub enum EnvironmentsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Get {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+    },
+    /// Deploy an existing Build, or build the newest Function and Asset Versions and deploy that.
+    Deploy {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        /// SID of a completed Build, begins with ZB...
+        #[arg(long, conflicts_with = "dependencies")]
+        build: Option<String>,
+        /// npm dependencies of a new Build, e.g. `axios@1.6.0,twilio@4.19.0`.
+        #[arg(long, default_value = "")]
+        dependencies: String,
+    },
+    /// List Deployments, newest first.
+    Deployments {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+    },
+    /// Deploy the Build running in this Environment to another.
+    Promote {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        /// SID of the Environment to promote to.
+        #[arg(long)]
+        to: String,
+    },
+    /// Redeploy an earlier Build, by default the one deployed before the current.
+    Rollback {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[arg(long)]
+        build: Option<String>,
+    },
+    Delete {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FunctionsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        function: String,
+    },
+    Create {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        friendly_name: String,
+    },
+    Versions {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        function: String,
+    },
+    /// Print the code of a Function Version.
+    Content {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        function: String,
+        #[arg(long)]
+        version: String,
+    },
+    /// Upload a local file as a new Function Version.
+    Upload {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        function: String,
+        #[arg(long)]
+        file: PathBuf,
+        /// URL path, must begin with `/`.
+        #[arg(long)]
+        path: String,
+        /// public, protected or private.
+        #[arg(long, default_value = "protected", value_parser = parse_variant::<Visibility>)]
+        visibility: Visibility,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        function: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AssetsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Versions {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        asset: String,
+    },
+    /// Upload a local directory as Assets, skipping files unchanged since the last upload.
+    Upload {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        directory: PathBuf,
+        /// public, protected or private.
+        #[arg(long, default_value = "protected", value_parser = parse_variant::<Visibility>)]
+        visibility: Visibility,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        asset: String,
+    },
+}
+
+#[derive(Args)]
+pub struct LogFilterArgs {
+    /// Friendly name of a Function to include. Can be repeated.
+    #[arg(long = "function")]
+    functions: Vec<String>,
+    /// info, warn or error. Can be repeated.
+    #[arg(long = "level", value_parser = parse_variant::<Level>)]
+    levels: Vec<Level>,
+    /// Only logs from this request, begins with RQ...
+    #[arg(long)]
+    request: Option<String>,
+    /// Only logs whose message matches this regular expression.
+    #[arg(long)]
+    message: Option<Regex>,
+}
+
+#[derive(Args)]
+pub struct DateRangeArgs {
+    /// Defaults to a day ago, e.g. 2024-01-01T00:00:00Z.
+    #[arg(long)]
+    start: Option<DateTime<Utc>>,
+    /// Defaults to now.
+    #[arg(long)]
+    end: Option<DateTime<Utc>>,
+}
+
+#[derive(Subcommand)]
+pub enum LogsCommand {
+    Get {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[arg(long)]
+        log: String,
+    },
+    List {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[command(flatten)]
+        filter: LogFilterArgs,
+        #[command(flatten)]
+        dates: DateRangeArgs,
+    },
+    /// Follow new logs until interrupted.
+    Tail {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[command(flatten)]
+        filter: LogFilterArgs,
+    },
+    /// Write logs to a file named after the Environment and the current time.
+    Export {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[command(flatten)]
+        filter: LogFilterArgs,
+        #[command(flatten)]
+        dates: DateRangeArgs,
+        /// csv or ndjson.
+        #[arg(long, default_value = "ndjson", value_parser = parse_variant::<ExportFormat>)]
+        format: ExportFormat,
+        #[arg(long)]
+        gzip: bool,
+        #[arg(long, default_value = ".")]
+        directory: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VariablesCommand {
+    List {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+    },
+    /// Add a Variable or change the value of an existing one.
+    Set {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        key: String,
+        value: String,
+    },
+    Delete {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        key: String,
+    },
+    /// Bring the Variables in line with a .env file.
+    Apply {
+        #[command(flatten)]
+        target: EnvironmentArgs,
+        #[arg(long, default_value = ".env")]
+        file: PathBuf,
+        /// Remove Variables that are not in the file.
+        #[arg(long)]
+        remove_missing: bool,
+        /// Only list the changes that would be made.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub async fn run(
+    twilio: &Client,
+    command: ServerlessCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        ServerlessCommand::Services(command) => match command {
+            ServicesCommand::List => print(&serverless.services().list().await?, output)?,
+            ServicesCommand::Get { sid } => print(&serverless.service(&sid).get().await?, output)?,
+            ServicesCommand::Create {
+                unique_name,
+                friendly_name,
+                include_credentials,
+                ui_editable,
+            } => print(
+                &serverless
+                    .services()
+                    .create(CreateOrUpdateParams {
+                        friendly_name: friendly_name.unwrap_or_else(|| unique_name.clone()),
+                        unique_name,
+                        include_credentials: Some(include_credentials),
+                        ui_editable: Some(ui_editable),
+                    })
+                    .await?,
+                output,
+            )?,
+            ServicesCommand::Delete { sid, yes } => {
+                require_confirmation(yes, "deleting the Serverless Service")?;
+                serverless.service(&sid).delete().await?;
+                eprintln!("Serverless Service deleted.");
+            }
+        },
+        ServerlessCommand::Deploy {
+            path,
+            service_name,
+            environment,
+            domain_suffix,
+        } => {
+            let project = Project::load(&path)?;
+            let service_name = service_name.or(project.name.clone()).ok_or(TwilioError {
+                kind: ErrorKind::ValidationError(
+                    "Pass --service-name as package.json has no name".into(),
+                ),
+            })?;
+
+            let manifest_path = path.join(MANIFEST_FILE);
+            let previous: ProjectManifest = fs::read_to_string(&manifest_path)
+                .ok()
+                .and_then(|contents| serde_json::from_str(&contents).ok())
+                .unwrap_or_default();
+
+            let outcome = serverless
+                .deploy(
+                    &project,
+                    DeployOptions {
+                        service_name,
+                        environment_name: environment,
+                        domain_suffix,
+                        previous,
+                        build_timeout: Duration::from_secs(300),
+                    },
+                    |event| {
+                        if output.is_text() {
+                            print_event(event)
+                        }
+                    },
+                )
+                .await?;
+
+            let manifest = serde_json::to_string_pretty(&outcome.manifest)
+                .expect("Manifest should serialize to JSON");
+            fs::write(&manifest_path, manifest).map_err(io_error)?;
+
+            match output.format {
+                Format::Text => {
+                    println!("Deployed to {}:", outcome.environment.domain_name);
+                    for url in &outcome.urls {
+                        println!("  {}", url);
+                    }
+                }
+                _ => print(&outcome.urls, output)?,
+            }
+        }
+        ServerlessCommand::Environments(command) => {
+            run_environments(twilio, command, output).await?
+        }
+        ServerlessCommand::Functions(command) => run_functions(twilio, command, output).await?,
+        ServerlessCommand::Assets(command) => run_assets(twilio, command, output).await?,
+        ServerlessCommand::Logs(command) => run_logs(twilio, command, output).await?,
+        ServerlessCommand::Variables(command) => run_variables(twilio, command, output).await?,
+    }
+
+    Ok(())
+}
+
+async fn run_environments(
+    twilio: &Client,
+    command: EnvironmentsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        EnvironmentsCommand::List { service } => print(
+            &serverless.service(&service).environments().list().await?,
+            output,
+        )?,
+        EnvironmentsCommand::Get { target } => print(
+            &serverless
+                .service(&target.service)
+                .environment(&target.environment)
+                .get()
+                .await?,
+            output,
+        )?,
+        EnvironmentsCommand::Deploy {
+            target,
+            build,
+            dependencies,
+        } => {
+            let service = serverless.service(&target.service);
+            let build_sid = match build {
+                Some(build_sid) => build_sid,
+                None => build_latest(twilio, &service, &dependencies).await?,
+            };
+
+            let deployment = service
+                .environment(&target.environment)
+                .deployments()
+                .create(deployments::CreateParams {
+                    build_sid: Some(build_sid),
+                })
+                .await?;
+            print(&deployment, output)?;
+        }
+        EnvironmentsCommand::Deployments { target } => {
+            let mut deployments = serverless
+                .service(&target.service)
+                .environment(&target.environment)
+                .deployments()
+                .list()
+                .await?;
+            deployments.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+            print(&deployments, output)?;
+        }
+        EnvironmentsCommand::Promote { target, to } => {
+            let service = serverless.service(&target.service);
+            let build_sid = service
+                .environment(&target.environment)
+                .get()
+                .await?
+                .build_sid
+                .ok_or(TwilioError {
+                    kind: ErrorKind::ValidationError(format!(
+                        "Nothing is deployed to {}",
+                        target.environment
+                    )),
+                })?;
+
+            let target_environment = service.environment(&to);
+            if let Some(current_build_sid) = target_environment.get().await?.build_sid {
+                print_build_change(&service, &current_build_sid, &build_sid, output).await?;
+            }
+            print(&target_environment.redeploy(&build_sid).await?, output)?;
+        }
+        EnvironmentsCommand::Rollback { target, build } => {
+            let service = serverless.service(&target.service);
+            let environment = service.environment(&target.environment);
+            let current_build_sid = environment.get().await?.build_sid;
+
+            let build_sid = match build {
+                Some(build_sid) => build_sid,
+                None => {
+                    let mut deployments = environment.deployments().list().await?;
+                    deployments.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+                    deployments
+                        .into_iter()
+                        .filter_map(|deployment| deployment.build_sid)
+                        .find(|build_sid| Some(build_sid) != current_build_sid.as_ref())
+                        .ok_or(TwilioError {
+                            kind: ErrorKind::ValidationError(
+                                "No previous Deployments to roll back to".into(),
+                            ),
+                        })?
+                }
+            };
+
+            if let Some(current_build_sid) = &current_build_sid {
+                print_build_change(&service, current_build_sid, &build_sid, output).await?;
+            }
+            print(&environment.redeploy(&build_sid).await?, output)?;
+        }
+        EnvironmentsCommand::Delete { target } => {
+            serverless
+                .service(&target.service)
+                .environment(&target.environment)
+                .delete()
+                .await?;
+            eprintln!("Serverless Environment deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a Build from the newest Version of every Function and Asset and waits for it
+/// to complete, returning its SID.
+async fn build_latest(
+    twilio: &Client,
+    service: &Service<'_, '_>,
+    dependencies: &str,
+) -> Result<String, TwilioError> {
+    let dependencies = parse_dependencies(dependencies)?;
+    let (function_versions, asset_versions) =
+        latest_versions(twilio, &service.get().await?).await?;
+    if function_versions.is_empty() && asset_versions.is_empty() {
+        return Err(TwilioError {
+            kind: ErrorKind::ValidationError("No Function or Asset Versions found to build".into()),
+        });
+    }
+
+    let build = service
+        .builds()
+        .create(CreateBuildParams {
+            function_versions,
+            asset_versions,
+            dependencies,
+            runtime: None,
+        })
+        .await?;
+    let build = service
+        .build(&build.sid)
+        .wait(
+            Duration::from_secs(2),
+            Duration::from_secs(300),
+            |status, elapsed| eprintln!("  {} {} ({}s)", build.sid, status, elapsed.as_secs()),
+        )
+        .await?;
+
+    match build.status {
+        BuildStatus::Completed => Ok(build.sid),
+        _ => Err(TwilioError {
+            kind: ErrorKind::BuildError(format!("Build {} failed", build.sid)),
+        }),
+    }
+}
+
+/// Prints what changes between two Builds when writing text output.
+async fn print_build_change(
+    service: &Service<'_, '_>,
+    from_build_sid: &str,
+    to_build_sid: &str,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    if output.is_text() {
+        print_diff(&service.diff_builds(from_build_sid, to_build_sid).await?);
+    }
+
+    Ok(())
+}
+
+async fn run_functions(
+    twilio: &Client,
+    command: FunctionsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        FunctionsCommand::List { service } => print(
+            &serverless.service(&service).functions().list().await?,
+            output,
+        )?,
+        FunctionsCommand::Get { service, function } => print(
+            &serverless
+                .service(&service)
+                .function(&function)
+                .get()
+                .await?,
+            output,
+        )?,
+        FunctionsCommand::Create {
+            service,
+            friendly_name,
+        } => print(
+            &serverless
+                .service(&service)
+                .functions()
+                .create(functions::CreateOrUpdateParams { friendly_name })
+                .await?,
+            output,
+        )?,
+        FunctionsCommand::Versions { service, function } => print(
+            &serverless
+                .service(&service)
+                .function(&function)
+                .versions()
+                .list()
+                .await?,
+            output,
+        )?,
+        FunctionsCommand::Content {
+            service,
+            function,
+            version,
+        } => {
+            let content = serverless
+                .service(&service)
+                .function(&function)
+                .version(&version)
+                .content()
+                .await?;
+            match output.format {
+                Format::Text => println!("{}", content.content),
+                _ => print(&content, output)?,
+            }
+        }
+        FunctionsCommand::Upload {
+            service,
+            function,
+            file,
+            path,
+            visibility,
+        } => {
+            if !path.starts_with('/') {
+                return Err(TwilioError {
+                    kind: ErrorKind::ValidationError("Path must begin with '/'".into()),
+                });
+            }
+            let content = fs::read(&file).map_err(io_error)?;
+            print(
+                &serverless
+                    .service(&service)
+                    .function(&function)
+                    .versions()
+                    .create(CreateVersionParams {
+                        path,
+                        visibility,
+                        content,
+                        content_type: String::from("application/javascript"),
+                    })
+                    .await?,
+                output,
+            )?;
+        }
+        FunctionsCommand::Delete { service, function } => {
+            serverless
+                .service(&service)
+                .function(&function)
+                .delete()
+                .await?;
+            eprintln!("Function deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_assets(
+    twilio: &Client,
+    command: AssetsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        AssetsCommand::List { service } => {
+            print(&serverless.service(&service).assets().list().await?, output)?
+        }
+        AssetsCommand::Versions { service, asset } => print(
+            &serverless
+                .service(&service)
+                .asset(&asset)
+                .versions()
+                .list()
+                .await?,
+            output,
+        )?,
+        AssetsCommand::Upload {
+            service,
+            directory,
+            visibility,
+        } => {
+            // Shares the manifest with the interactive upload so either skips unchanged files.
+            let manifest_path = directory.join(format!(".twilly-assets-{}.json", service));
+            let previous: Manifest = fs::read_to_string(&manifest_path)
+                .ok()
+                .and_then(|contents| serde_json::from_str(&contents).ok())
+                .unwrap_or_default();
+
+            // A failed upload still saves the Versions created so far so the next run skips them.
+            let (upload, error) = match serverless
+                .service(&service)
+                .assets()
+                .upload_directory(&directory, visibility, &previous)
+                .await
+            {
+                Ok(upload) => (upload, None),
+                Err(failed) => (failed.partial, Some(failed.error)),
+            };
+
+            let manifest = serde_json::to_string_pretty(&upload.manifest)
+                .expect("Manifest should serialize to JSON");
+            fs::write(&manifest_path, manifest).map_err(io_error)?;
+            if let Some(error) = error {
+                return Err(error);
+            }
+
+            eprintln!(
+                "{} uploaded, {} unchanged.",
+                upload.uploaded.len(),
+                upload.unchanged.len()
+            );
+            print(&upload.uploaded, output)?;
+        }
+        AssetsCommand::Delete { service, asset } => {
+            serverless.service(&service).asset(&asset).delete().await?;
+            eprintln!("Asset deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a `LogFilter` from the arguments, looking up Functions by friendly name.
+async fn log_filter(
+    service: &Service<'_, '_>,
+    args: LogFilterArgs,
+) -> Result<LogFilter, TwilioError> {
+    let function_sids = match args.functions.is_empty() {
+        true => Vec::new(),
+        false => service.functions().sids_by_name(&args.functions).await?,
+    };
+
+    Ok(LogFilter {
+        levels: args.levels,
+        function_sids,
+        request_sid: args.request,
+        message: args.message,
+    })
+}
+
+async fn run_logs(
+    twilio: &Client,
+    command: LogsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        LogsCommand::Get { target, log } => print(
+            &serverless
+                .service(&target.service)
+                .environment(&target.environment)
+                .log(&log)
+                .get()
+                .await?,
+            output,
+        )?,
+        LogsCommand::List {
+            target,
+            filter,
+            dates,
+        } => {
+            let service = serverless.service(&target.service);
+            let filter = log_filter(&service, filter).await?;
+            let logs = service
+                .environment(&target.environment)
+                .logs()
+                .query(&filter, dates.start, dates.end)
+                .await?;
+
+            match output.format {
+                Format::Text => logs.iter().for_each(print_log),
+                _ => print(&logs, output)?,
+            }
+        }
+        LogsCommand::Tail { target, filter } => {
+            let service = serverless.service(&target.service);
+            let filter = log_filter(&service, filter).await?;
+            let environment = service.environment(&target.environment);
+            let logs = environment.logs();
+            let mut tail = logs.tail(filter, Duration::from_secs(2));
+
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => break,
+                    log = tail.next() => match log {
+                        // Formats other than text are written as NDJSON so each log can be piped as it arrives.
+                        Some(Ok(log)) => match output.format {
+                            Format::Text => print_log(&log),
+                            _ => println!(
+                                "{}",
+                                serde_json::to_string(&log).expect("Log should serialize to JSON")
+                            ),
+                        },
+                        Some(Err(error)) => eprintln!("Unable to fetch logs, retrying: {}", error),
+                        None => break,
+                    },
+                }
+            }
+        }
+        LogsCommand::Export {
+            target,
+            filter,
+            dates,
+            format,
+            gzip,
+            directory,
+        } => {
+            let service = serverless.service(&target.service);
+            let filter = log_filter(&service, filter).await?;
+            let path = directory.join(export_file_name(
+                &target.environment,
+                format,
+                gzip,
+                Utc::now(),
+            ));
+
+            let file = File::create(&path).map_err(io_error)?;
+            let mut export = LogExport::new(BufWriter::new(file), format, gzip)?;
+            let environment = service.environment(&target.environment);
+            let logs = environment.logs();
+            let mut stream = logs.stream(filter, dates.start, dates.end);
+            while let Some(log) = stream.next().await {
+                export.write(&log?)?;
+            }
+
+            let written = export.written();
+            export.finish()?;
+            eprintln!("Wrote {} logs to {}", written, path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_variables(
+    twilio: &Client,
+    command: VariablesCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let serverless = twilio.serverless();
+
+    match command {
+        VariablesCommand::List { target } => print(
+            &serverless
+                .service(&target.service)
+                .environment(&target.environment)
+                .variables()
+                .list()
+                .await?,
+            output,
+        )?,
+        VariablesCommand::Set { target, key, value } => {
+            let service = serverless.service(&target.service);
+            let environment = service.environment(&target.environment);
+            let existing = environment
+                .variables()
+                .list()
+                .await?
+                .into_iter()
+                .find(|variable| variable.key == key);
+
+            let variable = match existing {
+                Some(variable) => {
+                    environment
+                        .variable(&variable.sid)
+                        .update(UpdateParams {
+                            key: None,
+                            value: Some(value),
+                        })
+                        .await?
+                }
+                None => {
+                    environment
+                        .variables()
+                        .create(CreateVariableParams { key, value })
+                        .await?
+                }
+            };
+            print(&variable, output)?;
+        }
+        VariablesCommand::Delete { target, key } => {
+            let service = serverless.service(&target.service);
+            let environment = service.environment(&target.environment);
+            let variable = environment
+                .variables()
+                .list()
+                .await?
+                .into_iter()
+                .find(|variable| variable.key == key)
+                .ok_or(TwilioError {
+                    kind: ErrorKind::ValidationError(format!("No Variable named {}", key)),
+                })?;
+
+            environment.variable(&variable.sid).delete().await?;
+            eprintln!("Variable deleted.");
+        }
+        VariablesCommand::Apply {
+            target,
+            file,
+            remove_missing,
+            dry_run,
+        } => {
+            let desired = parse_env_file(&fs::read_to_string(&file).map_err(io_error)?)?;
+            let service = serverless.service(&target.service);
+            let environment = service.environment(&target.environment);
+            let changes = environment
+                .variables()
+                .diff(&desired, remove_missing)
+                .await?;
+
+            match output.format {
+                Format::Text => changes.iter().for_each(|change| println!("{}", change)),
+                _ => print(&changes, output)?,
+            }
+            if !dry_run {
+                environment.variables().apply(&changes).await?;
+                eprintln!(
+                    "Applied {} changes. Changes take effect on the next deployment.",
+                    changes.len()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
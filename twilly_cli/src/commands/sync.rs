@@ -0,0 +1,977 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    ops::Bound,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Args, Subcommand};
+use twilly::{
+    jwt::{self, AccessToken, SyncGrant},
+    sync::{
+        copy::CopyParams,
+        expiry::{ExpiringResource, MAX_TTL},
+        listitems::ListParams as ListItemParams,
+        mapitems::{CreateParams as CreateMapItemParams, ListParams as MapItemParams},
+        maps::CreateParams as CreateMapParams,
+        services::CreateOrUpdateParams,
+        snapshot::{RestoreParams, Snapshot},
+        transfer::{read_records, ConflictPolicy, Format, ImportParams},
+    },
+    Client, ErrorKind, TwilioError,
+};
+
+use super::{io_error, parse_variant, print, require_confirmation, Format as OutputFormat, Output};
+use crate::sync::{print_import_report, print_restore_report, write_export_file, DataFilter};
+
+#[derive(Subcommand)]
+pub enum SyncCommand {
+    /// Sync Services.
+    #[command(subcommand)]
+    Services(ServicesCommand),
+    /// Write a snapshot of a Service and all of its objects to a file.
+    Snapshot {
+        #[arg(long)]
+        service: String,
+        /// Defaults to `{service}-{timestamp}.json`.
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Restore a snapshot file into an existing Service.
+    Restore {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        path: PathBuf,
+        #[command(flatten)]
+        restore: RestoreArgs,
+    },
+    /// Create a new Service from a snapshot file.
+    RestoreNew {
+        #[arg(long)]
+        path: PathBuf,
+        /// Only list the changes that would be made.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Report the Documents and items expiring soon, optionally extending their TTL.
+    Expiring {
+        #[arg(long)]
+        service: String,
+        #[arg(long, default_value_t = 24)]
+        within_hours: u64,
+        /// Set the TTL of everything found to this many hours from now.
+        #[arg(long)]
+        extend_hours: Option<u64>,
+    },
+    /// Mint a Sync Access Token for testing front-end clients.
+    Token {
+        #[arg(long)]
+        service: String,
+        /// API Key SID, begins with SK...
+        #[arg(long)]
+        api_key_sid: String,
+        #[arg(long, env = "TWILIO_API_KEY_SECRET", hide_env_values = true)]
+        api_key_secret: String,
+        #[arg(long)]
+        identity: String,
+        #[arg(long, default_value_t = 60)]
+        ttl_minutes: u64,
+    },
+    /// Sync Documents.
+    #[command(subcommand)]
+    Documents(DocumentsCommand),
+    /// Sync Maps.
+    #[command(subcommand)]
+    Maps(MapsCommand),
+    /// Items of a Sync Map.
+    #[command(subcommand)]
+    MapItems(MapItemsCommand),
+    /// Sync Lists.
+    #[command(subcommand)]
+    Lists(ListsCommand),
+    /// Items of a Sync List.
+    #[command(subcommand)]
+    ListItems(ListItemsCommand),
+}
+
+#[derive(Subcommand)]
+pub enum ServicesCommand {
+    List,
+    Get {
+        sid: String,
+    },
+    Create {
+        #[arg(long)]
+        friendly_name: Option<String>,
+        #[arg(long)]
+        acl_enabled: bool,
+    },
+    Delete {
+        sid: String,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DocumentsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        document: String,
+    },
+    /// Copy a Document into another Service on the account.
+    Copy {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        document: String,
+        #[command(flatten)]
+        copy: CopyArgs,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        document: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MapsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+    },
+    /// Find items by key prefix and data.
+    Search {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// A JSON pointer into the item data and the value it should equal, e.g. `/status=active`.
+        #[arg(long)]
+        filter: Option<DataFilter>,
+    },
+    /// Write every item to a file.
+    Export {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[command(flatten)]
+        file: ExportArgs,
+    },
+    /// Create or update items from a file written by `export`.
+    Import {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        /// Files ending in .ndjson or .jsonl are read as NDJSON, otherwise JSON.
+        #[arg(long)]
+        path: PathBuf,
+        #[command(flatten)]
+        import: ImportArgs,
+    },
+    /// Copy a Map and its items into another Service on the account.
+    Copy {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[command(flatten)]
+        copy: CopyArgs,
+    },
+    /// Recreate a Map under a new unique name. TTLs are not preserved.
+    Rename {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[arg(long)]
+        unique_name: String,
+        #[arg(long)]
+        yes: bool,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MapItemsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[arg(long)]
+        key: String,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        map: String,
+        #[arg(long)]
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ListsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+    },
+    /// Find items by index range and data.
+    Search {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        #[arg(long, default_value_t = 0)]
+        from: u32,
+        /// Last index to read, inclusive. Reads to the end of the List if not provided.
+        #[arg(long)]
+        to: Option<u32>,
+        /// A JSON pointer into the item data and the value it should equal, e.g. `/status=active`.
+        #[arg(long)]
+        filter: Option<DataFilter>,
+    },
+    /// Write every item to a file.
+    Export {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        #[command(flatten)]
+        file: ExportArgs,
+    },
+    /// Create or update items from a file written by `export`.
+    Import {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        /// Files ending in .ndjson or .jsonl are read as NDJSON, otherwise JSON.
+        #[arg(long)]
+        path: PathBuf,
+        #[command(flatten)]
+        import: ImportArgs,
+    },
+    /// Copy a List and its items into another Service on the account.
+    Copy {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        #[command(flatten)]
+        copy: CopyArgs,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ListItemsCommand {
+    List {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+    },
+    Get {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        #[arg(long)]
+        index: u32,
+    },
+    Delete {
+        #[arg(long)]
+        service: String,
+        #[arg(long)]
+        list: String,
+        #[arg(long)]
+        index: u32,
+    },
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// What to do with items that already exist: skip, overwrite or fail.
+    #[arg(long, default_value = "fail", value_parser = parse_variant::<ConflictPolicy>)]
+    conflict_policy: ConflictPolicy,
+    /// How many items are written at once.
+    #[arg(long, default_value_t = 5)]
+    concurrency: usize,
+}
+
+impl From<&ImportArgs> for ImportParams {
+    fn from(args: &ImportArgs) -> Self {
+        ImportParams {
+            concurrency: args.concurrency,
+            conflict_policy: args.conflict_policy,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct RestoreArgs {
+    /// Only list the changes that would be made.
+    #[arg(long)]
+    dry_run: bool,
+    #[command(flatten)]
+    import: ImportArgs,
+}
+
+#[derive(Args)]
+pub struct CopyArgs {
+    /// SID of the Service to copy into.
+    #[arg(long)]
+    target_service: String,
+    /// Unique name of the copy. Defaults to the original's.
+    #[arg(long)]
+    unique_name: Option<String>,
+    #[command(flatten)]
+    restore: RestoreArgs,
+}
+
+impl From<CopyArgs> for CopyParams {
+    fn from(args: CopyArgs) -> Self {
+        CopyParams {
+            unique_name: args.unique_name,
+            dry_run: args.restore.dry_run,
+            conflict_policy: args.restore.import.conflict_policy,
+            concurrency: args.restore.import.concurrency,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[arg(long)]
+    path: PathBuf,
+    /// json or ndjson. Taken from the file extension if not provided.
+    #[arg(long, value_parser = parse_variant::<Format>)]
+    format: Option<Format>,
+}
+
+pub async fn run(
+    twilio: &Client,
+    command: SyncCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        SyncCommand::Services(command) => match command {
+            ServicesCommand::List => print(&sync.services().list().await?, output)?,
+            ServicesCommand::Get { sid } => print(&sync.service(&sid).get().await?, output)?,
+            ServicesCommand::Create {
+                friendly_name,
+                acl_enabled,
+            } => print(
+                &sync
+                    .services()
+                    .create(CreateOrUpdateParams {
+                        friendly_name,
+                        acl_enabled: Some(acl_enabled),
+                        reachability_debouncing_enabled: None,
+                        reachability_debouncing_window: None,
+                        reachability_webhooks_enabled: None,
+                        webhooks_from_rest_enabled: None,
+                        webhook_url: None,
+                    })
+                    .await?,
+                output,
+            )?,
+            ServicesCommand::Delete { sid, yes } => {
+                require_confirmation(yes, "deleting the Sync Service")?;
+                sync.service(&sid).delete().await?;
+                eprintln!("Sync Service deleted.");
+            }
+        },
+        SyncCommand::Snapshot { service, path } => {
+            let path = path.unwrap_or_else(|| {
+                PathBuf::from(format!(
+                    "{}-{}.json",
+                    service,
+                    chrono::Utc::now().format("%Y%m%d%H%M%S")
+                ))
+            });
+            let snapshot = sync.service(&service).snapshot().await?;
+            snapshot
+                .write(File::create(&path).map_err(io_error)?)
+                .map_err(io_error)?;
+            eprintln!(
+                "Snapshot written to {}: {} Documents, {} Lists, {} Maps",
+                path.display(),
+                snapshot.objects.documents.len(),
+                snapshot.objects.lists.len(),
+                snapshot.objects.maps.len()
+            );
+        }
+        SyncCommand::Restore {
+            service,
+            path,
+            restore,
+        } => {
+            let snapshot = read_snapshot(&path)?;
+            let params = RestoreParams {
+                dry_run: restore.dry_run,
+                conflict_policy: restore.import.conflict_policy,
+                concurrency: restore.import.concurrency,
+            };
+            let report = sync.service(&service).restore(&snapshot, params).await?;
+            print_restore_report(&report, params.dry_run);
+        }
+        SyncCommand::RestoreNew { path, dry_run } => {
+            let snapshot = read_snapshot(&path)?;
+            let service_params = snapshot.service_params();
+            if dry_run {
+                println!(
+                    "  Create Sync Service '{}'",
+                    service_params.friendly_name.clone().unwrap_or_default()
+                );
+                for action in snapshot.plan_for_new_service() {
+                    println!("  {}", action);
+                }
+                return Ok(());
+            }
+
+            let sync_service = sync.services().create(service_params).await?;
+            eprintln!("Created Sync Service {}", sync_service.sid);
+            let report = sync
+                .service(&sync_service.sid)
+                .restore(&snapshot, RestoreParams::default())
+                .await?;
+            print_restore_report(&report, false);
+            print(&sync_service, output)?;
+        }
+        SyncCommand::Expiring {
+            service,
+            within_hours,
+            extend_hours,
+        } => {
+            let service = sync.service(&service);
+            let expiring = service
+                .expiring(Duration::from_secs(within_hours * 3600))
+                .await?;
+            match output.format {
+                OutputFormat::Text => {
+                    for expiring in &expiring {
+                        println!(
+                            "{} - {}",
+                            expiring.date_expires.format("%Y-%m-%d %H:%M:%S UTC"),
+                            expiring.resource
+                        );
+                    }
+                }
+                _ => print(&expiring, output)?,
+            }
+
+            if let Some(extend_hours) = extend_hours {
+                let ttl = Duration::from_secs(extend_hours * 3600);
+                if ttl.is_zero() || ttl > MAX_TTL {
+                    return Err(TwilioError {
+                        kind: ErrorKind::ValidationError(format!(
+                            "--extend-hours must be between 1 and {}",
+                            MAX_TTL.as_secs() / 3600
+                        )),
+                    });
+                }
+
+                let resources: Vec<ExpiringResource> = expiring
+                    .into_iter()
+                    .map(|expiring| expiring.resource)
+                    .collect();
+                let report = service.extend_ttl(&resources, ttl, 5).await?;
+                eprintln!(
+                    "Extended: {}, Failed: {}",
+                    report.extended,
+                    report.failed.len()
+                );
+                for failure in &report.failed {
+                    eprintln!("  {} - {}", failure.resource, failure.error);
+                }
+            }
+        }
+        SyncCommand::Token {
+            service,
+            api_key_sid,
+            api_key_secret,
+            identity,
+            ttl_minutes,
+        } => {
+            let mut token = AccessToken::new(
+                twilio.account_sid(),
+                &api_key_sid,
+                &api_key_secret,
+                &identity,
+            );
+            token.ttl =
+                Duration::from_secs(ttl_minutes * 60).min(jwt::MAX_TTL + Duration::from_secs(1));
+            token.grants.sync = Some(SyncGrant {
+                service_sid: service,
+                endpoint_id: None,
+            });
+            println!("{}", token.to_jwt()?);
+        }
+        SyncCommand::Documents(command) => run_documents(twilio, command, output).await?,
+        SyncCommand::Maps(command) => run_maps(twilio, command, output).await?,
+        SyncCommand::MapItems(command) => run_map_items(twilio, command, output).await?,
+        SyncCommand::Lists(command) => run_lists(twilio, command, output).await?,
+        SyncCommand::ListItems(command) => run_list_items(twilio, command, output).await?,
+    }
+
+    Ok(())
+}
+
+async fn run_documents(
+    twilio: &Client,
+    command: DocumentsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        DocumentsCommand::List { service } => {
+            print(&sync.service(&service).documents().list().await?, output)?
+        }
+        DocumentsCommand::Get { service, document } => print(
+            &sync.service(&service).document(&document).get().await?,
+            output,
+        )?,
+        DocumentsCommand::Copy {
+            service,
+            document,
+            copy,
+        } => {
+            let target_service = copy.target_service.clone();
+            let params = CopyParams::from(copy);
+            let dry_run = params.dry_run;
+            let report = sync
+                .service(&service)
+                .document(&document)
+                .copy_to(&sync.service(&target_service), params)
+                .await?;
+            print_restore_report(&report, dry_run);
+        }
+        DocumentsCommand::Delete { service, document } => {
+            sync.service(&service).document(&document).delete().await?;
+            eprintln!("Document deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_maps(
+    twilio: &Client,
+    command: MapsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        MapsCommand::List { service } => {
+            print(&sync.service(&service).maps().list().await?, output)?
+        }
+        MapsCommand::Get { service, map } => {
+            print(&sync.service(&service).map(&map).get().await?, output)?
+        }
+        MapsCommand::Search {
+            service,
+            map,
+            prefix,
+            filter,
+        } => {
+            let mut map_items = sync
+                .service(&service)
+                .map(&map)
+                .mapitems()
+                .list_with_prefix(&prefix)
+                .await?;
+            if let Some(filter) = filter {
+                map_items.retain(|map_item| filter.matches(&map_item.data));
+            }
+            print(&map_items, output)?;
+        }
+        MapsCommand::Export { service, map, file } => {
+            let records = sync.service(&service).map(&map).mapitems().export().await?;
+            let format = file
+                .format
+                .unwrap_or_else(|| Format::from_path(&file.path.to_string_lossy()));
+            write_export_file(&records, format, &file.path.to_string_lossy());
+        }
+        MapsCommand::Import {
+            service,
+            map,
+            path,
+            import,
+        } => {
+            let records = read_import_file(&path)?;
+            let report = sync
+                .service(&service)
+                .map(&map)
+                .mapitems()
+                .import(records, ImportParams::from(&import), |_| {})
+                .await?;
+            print_import_report(&report);
+        }
+        MapsCommand::Copy { service, map, copy } => {
+            let target_service = copy.target_service.clone();
+            let params = CopyParams::from(copy);
+            let dry_run = params.dry_run;
+            let report = sync
+                .service(&service)
+                .map(&map)
+                .copy_to(&sync.service(&target_service), params)
+                .await?;
+            print_restore_report(&report, dry_run);
+        }
+        MapsCommand::Rename {
+            service,
+            map,
+            unique_name,
+            yes,
+        } => {
+            require_confirmation(yes, "recreating the Map")?;
+            print(
+                &rename_map(twilio, &service, &map, unique_name).await?,
+                output,
+            )?;
+        }
+        MapsCommand::Delete { service, map } => {
+            sync.service(&service).map(&map).delete().await?;
+            eprintln!("Sync Map deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Recreates a Map under a new name following the same steps as the interactive menu. The
+/// temporary copy is checked against the original before the original is deleted and is
+/// kept once the rename completes.
+async fn rename_map(
+    twilio: &Client,
+    service_sid: &str,
+    map_sid: &str,
+    unique_name: String,
+) -> Result<twilly::sync::maps::SyncMap, TwilioError> {
+    let sync = twilio.sync();
+    let service = sync.service(service_sid);
+    let original = service.map(map_sid).get().await?;
+    let all_items = || MapItemParams {
+        order: None,
+        from: None,
+        bounds: None,
+    };
+
+    eprintln!("(1/6) Creating temporary map");
+    let temp_map = service
+        .maps()
+        .create(CreateMapParams {
+            ttl: None,
+            unique_name: Some(format!("temp-{}", original.unique_name)),
+        })
+        .await?;
+
+    eprintln!("(2/6) Clone items into temporary map");
+    let items = service.map(map_sid).mapitems().list(all_items()).await?;
+    copy_map_items(twilio, service_sid, &temp_map.sid, &items).await?;
+
+    eprintln!("(3/6) Confirm copy was successful");
+    let copied = service
+        .map(&temp_map.sid)
+        .mapitems()
+        .list(all_items())
+        .await?;
+    if copied.len() != items.len() {
+        return Err(TwilioError {
+            kind: ErrorKind::ValidationError(format!(
+                "Temporary map {} holds {} of {} items. The original was left in place",
+                temp_map.sid,
+                copied.len(),
+                items.len()
+            )),
+        });
+    }
+
+    eprintln!("(4/6) Delete original map");
+    service.map(map_sid).delete().await?;
+
+    eprintln!("(5/6) Create new map");
+    let new_map = service
+        .maps()
+        .create(CreateMapParams {
+            ttl: None,
+            unique_name: Some(unique_name),
+        })
+        .await?;
+
+    eprintln!("(6/6) Clone items into new map");
+    copy_map_items(twilio, service_sid, &new_map.sid, &items).await?;
+
+    eprintln!(
+        "Map rename complete. Temporary map {} can be deleted once checked.",
+        temp_map.sid
+    );
+    Ok(new_map)
+}
+
+async fn copy_map_items(
+    twilio: &Client,
+    service_sid: &str,
+    map_sid: &str,
+    items: &[twilly::sync::mapitems::SyncMapItem],
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+    let service = sync.service(service_sid);
+    let map = service.map(map_sid);
+
+    for item in items {
+        map.mapitems()
+            .create(CreateMapItemParams {
+                key: item.key.clone(),
+                data: &item.data,
+                collection_ttl: None,
+                ttl: None,
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn run_map_items(
+    twilio: &Client,
+    command: MapItemsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        MapItemsCommand::List { service, map } => print(
+            &sync
+                .service(&service)
+                .map(&map)
+                .mapitems()
+                .list(MapItemParams {
+                    order: None,
+                    from: None,
+                    bounds: None,
+                })
+                .await?,
+            output,
+        )?,
+        MapItemsCommand::Get { service, map, key } => print(
+            &sync.service(&service).map(&map).mapitem(&key).get().await?,
+            output,
+        )?,
+        MapItemsCommand::Delete { service, map, key } => {
+            sync.service(&service)
+                .map(&map)
+                .mapitem(&key)
+                .delete()
+                .await?;
+            eprintln!("Sync Map item deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_lists(
+    twilio: &Client,
+    command: ListsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        ListsCommand::List { service } => {
+            print(&sync.service(&service).lists().list().await?, output)?
+        }
+        ListsCommand::Get { service, list } => {
+            print(&sync.service(&service).list(&list).get().await?, output)?
+        }
+        ListsCommand::Search {
+            service,
+            list,
+            from,
+            to,
+            filter,
+        } => {
+            let mut list_items = sync
+                .service(&service)
+                .list(&list)
+                .listitems()
+                .list_range((
+                    Bound::Included(from),
+                    to.map_or(Bound::Unbounded, Bound::Included),
+                ))
+                .await?;
+            if let Some(filter) = filter {
+                list_items.retain(|list_item| filter.matches(&list_item.data));
+            }
+            print(&list_items, output)?;
+        }
+        ListsCommand::Export {
+            service,
+            list,
+            file,
+        } => {
+            let records = sync
+                .service(&service)
+                .list(&list)
+                .listitems()
+                .export()
+                .await?;
+            let format = file
+                .format
+                .unwrap_or_else(|| Format::from_path(&file.path.to_string_lossy()));
+            write_export_file(&records, format, &file.path.to_string_lossy());
+        }
+        ListsCommand::Import {
+            service,
+            list,
+            path,
+            import,
+        } => {
+            let records = read_import_file(&path)?;
+            let report = sync
+                .service(&service)
+                .list(&list)
+                .listitems()
+                .import(records, ImportParams::from(&import), |_| {})
+                .await?;
+            print_import_report(&report);
+        }
+        ListsCommand::Copy {
+            service,
+            list,
+            copy,
+        } => {
+            let target_service = copy.target_service.clone();
+            let params = CopyParams::from(copy);
+            let dry_run = params.dry_run;
+            let report = sync
+                .service(&service)
+                .list(&list)
+                .copy_to(&sync.service(&target_service), params)
+                .await?;
+            print_restore_report(&report, dry_run);
+        }
+        ListsCommand::Delete { service, list } => {
+            sync.service(&service).list(&list).delete().await?;
+            eprintln!("Sync List deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_list_items(
+    twilio: &Client,
+    command: ListItemsCommand,
+    output: &Output,
+) -> Result<(), TwilioError> {
+    let sync = twilio.sync();
+
+    match command {
+        ListItemsCommand::List { service, list } => print(
+            &sync
+                .service(&service)
+                .list(&list)
+                .listitems()
+                .list(ListItemParams {
+                    order: None,
+                    from: None,
+                    bounds: None,
+                })
+                .await?,
+            output,
+        )?,
+        ListItemsCommand::Get {
+            service,
+            list,
+            index,
+        } => print(
+            &sync
+                .service(&service)
+                .list(&list)
+                .listitem(&index)
+                .get()
+                .await?,
+            output,
+        )?,
+        ListItemsCommand::Delete {
+            service,
+            list,
+            index,
+        } => {
+            sync.service(&service)
+                .list(&list)
+                .listitem(&index)
+                .delete()
+                .await?;
+            eprintln!("Sync List item deleted.");
+        }
+    }
+
+    Ok(())
+}
+
+fn read_snapshot(path: &Path) -> Result<Snapshot, TwilioError> {
+    let file = File::open(path).map_err(io_error)?;
+    Snapshot::read(BufReader::new(file)).map_err(io_error)
+}
+
+fn read_import_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, TwilioError> {
+    let file = File::open(path).map_err(io_error)?;
+    read_records(
+        BufReader::new(file),
+        Format::from_path(&path.to_string_lossy()),
+    )
+    .map_err(io_error)
+}
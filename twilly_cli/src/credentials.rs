@@ -0,0 +1,471 @@
+use std::{collections::BTreeMap, env, fs, io::Write, path::PathBuf};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use inquire::{validator::Validation, Password, PasswordDisplayMode};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use twilly_cli::prompt_user;
+use zeroize::Zeroizing;
+
+/// Service name auth tokens are stored under in the keyring.
+const KEYRING_SERVICE: &str = "twilly";
+/// Read in place of prompting for the passphrase so scripts can unlock the encrypted file.
+const PASSPHRASE_VAR: &str = "TWILLY_PASSPHRASE";
+const PASSPHRASE_ATTEMPTS: usize = 3;
+
+/// Where auth tokens are kept, keyed by account SID.
+///
+/// The OS keyring (the Secret Service on Linux) is used when it can be reached. Otherwise
+/// tokens are kept in a file encrypted with a key derived from a passphrase. Once the file
+/// exists it is always used so tokens are not split across both.
+pub enum SecretStore {
+    Keyring,
+    EncryptedFile(EncryptedFile),
+    /// Tokens held in memory, `None` standing in for a store that can't be used.
+    #[cfg(test)]
+    Memory(Option<BTreeMap<String, String>>),
+}
+
+impl SecretStore {
+    pub fn open() -> SecretStore {
+        let path = encrypted_file_path();
+        if path.exists() || !keyring_available() {
+            return SecretStore::EncryptedFile(EncryptedFile {
+                path,
+                unlocked: None,
+            });
+        }
+
+        SecretStore::Keyring
+    }
+
+    pub fn get(&mut self, account_sid: &str) -> Result<String, String> {
+        match self {
+            SecretStore::Keyring => keyring_entry(account_sid)?
+                .get_password()
+                .map_err(|error| error.to_string()),
+            SecretStore::EncryptedFile(file) => file
+                .unlock()?
+                .tokens
+                .get(account_sid)
+                .cloned()
+                .ok_or_else(|| format!("No auth token stored for {}", account_sid)),
+            #[cfg(test)]
+            SecretStore::Memory(tokens) => tokens
+                .as_ref()
+                .ok_or_else(|| String::from("Store unavailable"))?
+                .get(account_sid)
+                .cloned()
+                .ok_or_else(|| format!("No auth token stored for {}", account_sid)),
+        }
+    }
+
+    pub fn set(&mut self, account_sid: &str, auth_token: &str) -> Result<(), String> {
+        match self {
+            SecretStore::Keyring => keyring_entry(account_sid)?
+                .set_password(auth_token)
+                .map_err(|error| error.to_string()),
+            SecretStore::EncryptedFile(file) => {
+                file.unlock()?
+                    .tokens
+                    .insert(account_sid.to_string(), auth_token.to_string());
+                file.save()
+            }
+            #[cfg(test)]
+            SecretStore::Memory(tokens) => {
+                tokens
+                    .as_mut()
+                    .ok_or_else(|| String::from("Store unavailable"))?
+                    .insert(account_sid.to_string(), auth_token.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn delete(&mut self, account_sid: &str) -> Result<(), String> {
+        match self {
+            SecretStore::Keyring => match keyring_entry(account_sid)?.delete_password() {
+                Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(error) => Err(error.to_string()),
+            },
+            SecretStore::EncryptedFile(file) => {
+                file.unlock()?.tokens.remove(account_sid);
+                file.save()
+            }
+            #[cfg(test)]
+            SecretStore::Memory(tokens) => {
+                tokens
+                    .as_mut()
+                    .ok_or_else(|| String::from("Store unavailable"))?
+                    .remove(account_sid);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Auth tokens encrypted with AES-256-GCM. The key is derived from the user's passphrase
+/// with Argon2id and a salt stored alongside the ciphertext.
+pub struct EncryptedFile {
+    path: PathBuf,
+    unlocked: Option<Unlocked>,
+}
+
+/// The decrypted contents of the file and the key to write them back with.
+struct Unlocked {
+    salt: [u8; 16],
+    key: Zeroizing<[u8; 32]>,
+    tokens: BTreeMap<String, String>,
+}
+
+/// The file as written to disk, each field base64 encoded.
+#[derive(Serialize, Deserialize)]
+struct Contents {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The file as decoded from disk, before decryption.
+struct Sealed {
+    salt: [u8; 16],
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+impl Unlocked {
+    /// An empty set of tokens under a key derived from a new passphrase and salt.
+    fn new(passphrase: &str) -> Result<Unlocked, String> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Ok(Unlocked {
+            salt,
+            key: derive_key(passphrase, &salt)?,
+            tokens: BTreeMap::new(),
+        })
+    }
+
+    /// Encrypts the tokens under a new nonce, giving the contents of the file.
+    fn seal(&self) -> Result<String, String> {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&self.tokens).expect("Tokens should serialize to JSON"),
+        );
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = Aes256Gcm::new_from_slice(&self.key[..]).expect("Key should be 32 bytes");
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|error| format!("Unable to encrypt credentials: {}", error))?;
+
+        Ok(serde_json::to_string(&Contents {
+            salt: STANDARD.encode(self.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+        .expect("Credentials should serialize to JSON"))
+    }
+}
+
+impl Sealed {
+    fn parse(contents: &str) -> Result<Sealed, String> {
+        let contents: Contents = serde_json::from_str(contents)
+            .map_err(|error| format!("Credentials file is corrupt: {}", error))?;
+        Ok(Sealed {
+            salt: decode(&contents.salt)?
+                .try_into()
+                .map_err(|_| String::from("Credentials file is corrupt: invalid salt"))?,
+            nonce: decode(&contents.nonce)?
+                .try_into()
+                .map_err(|_| String::from("Credentials file is corrupt: invalid nonce"))?,
+            ciphertext: decode(&contents.ciphertext)?,
+        })
+    }
+
+    /// Decrypts the tokens, giving `None` if the passphrase is incorrect.
+    fn open(&self, passphrase: &str) -> Result<Option<Unlocked>, String> {
+        let key = derive_key(passphrase, &self.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(&key[..]).expect("Key should be 32 bytes");
+        let Ok(plaintext) =
+            cipher.decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_ref())
+        else {
+            return Ok(None);
+        };
+
+        let plaintext = Zeroizing::new(plaintext);
+        let tokens = serde_json::from_slice(&plaintext)
+            .map_err(|error| format!("Credentials file is corrupt: {}", error))?;
+        Ok(Some(Unlocked {
+            salt: self.salt,
+            key,
+            tokens,
+        }))
+    }
+}
+
+impl EncryptedFile {
+    /// Decrypts the file, asking for the passphrase the first time. A passphrase is chosen
+    /// if the file doesn't exist yet.
+    fn unlock(&mut self) -> Result<&mut Unlocked, String> {
+        if self.unlocked.is_none() {
+            let unlocked = match fs::read_to_string(&self.path) {
+                Ok(contents) => Self::decrypt(&contents)?,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                    Unlocked::new(&new_passphrase()?)?
+                }
+                Err(error) => {
+                    return Err(format!("Unable to read {}: {}", self.path.display(), error))
+                }
+            };
+            self.unlocked = Some(unlocked);
+        }
+
+        Ok(self
+            .unlocked
+            .as_mut()
+            .expect("Credentials should be unlocked"))
+    }
+
+    /// Decrypts with the passphrase from `TWILLY_PASSPHRASE`, otherwise prompts for it.
+    fn decrypt(contents: &str) -> Result<Unlocked, String> {
+        if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+            return Self::decrypt_with(contents, &Zeroizing::new(passphrase));
+        }
+
+        let sealed = Sealed::parse(contents)?;
+        for attempt in 1..=PASSPHRASE_ATTEMPTS {
+            if let Some(unlocked) = sealed.open(&existing_passphrase()?)? {
+                return Ok(unlocked);
+            }
+            if attempt < PASSPHRASE_ATTEMPTS {
+                eprintln!("Incorrect passphrase, try again.");
+            }
+        }
+
+        Err(String::from("Incorrect passphrase"))
+    }
+
+    fn decrypt_with(contents: &str, passphrase: &str) -> Result<Unlocked, String> {
+        Sealed::parse(contents)?
+            .open(passphrase)?
+            .ok_or_else(|| String::from("Incorrect passphrase"))
+    }
+
+    /// Encrypts the tokens under a new nonce and replaces the file.
+    ///
+    /// The contents are written to a sibling file that is synced and then renamed over the
+    /// original, so an interrupted write never leaves the tokens unreadable.
+    fn save(&self) -> Result<(), String> {
+        let contents = self
+            .unlocked
+            .as_ref()
+            .expect("Credentials should be unlocked before saving")
+            .seal()?;
+
+        let write_error =
+            |error: std::io::Error| format!("Unable to write {}: {}", self.path.display(), error);
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(write_error)?;
+        }
+
+        let temp_path = self.path.with_extension("enc.tmp");
+        // A leftover file from an interrupted save would keep its permissions when reopened.
+        match fs::remove_file(&temp_path) {
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(write_error(error)),
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .map_err(write_error)
+    }
+}
+
+/// Whether the keyring can be reached, e.g. there is a Secret Service running on Linux.
+fn keyring_available() -> bool {
+    match keyring::Entry::new(KEYRING_SERVICE, "availability-check")
+        .and_then(|entry| entry.get_password())
+    {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(_) => false,
+    }
+}
+
+fn keyring_entry(account_sid: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, account_sid).map_err(|error| error.to_string())
+}
+
+fn encrypted_file_path() -> PathBuf {
+    confy::get_configuration_file_path("twilly", "credentials")
+        .map(|path| path.with_extension("enc"))
+        .unwrap_or_else(|_| PathBuf::from("credentials.enc"))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key[..])
+        .map_err(|error| format!("Unable to derive key: {}", error))?;
+    Ok(key)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(value)
+        .map_err(|error| format!("Credentials file is corrupt: {}", error))
+}
+
+fn new_passphrase() -> Result<Zeroizing<String>, String> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_VAR) {
+        if passphrase.is_empty() {
+            return Err(format!("{} must not be empty", PASSPHRASE_VAR));
+        }
+        return Ok(Zeroizing::new(passphrase));
+    }
+
+    eprintln!("No system keyring found. Credentials will be stored in an encrypted file.");
+    let passphrase_prompt = Password::new("Choose a passphrase to encrypt credentials with:")
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .with_custom_confirmation_message("Confirm the passphrase:")
+        .with_custom_confirmation_error_message("The passphrases don't match.")
+        .with_help_message("Set TWILLY_PASSPHRASE to provide this when running commands.")
+        .with_validator(|val: &str| match val.is_empty() {
+            true => Ok(Validation::Invalid("Enter a passphrase".into())),
+            false => Ok(Validation::Valid),
+        });
+    prompt_user(passphrase_prompt)
+        .map(Zeroizing::new)
+        .ok_or_else(|| String::from("A passphrase is required to store credentials"))
+}
+
+fn existing_passphrase() -> Result<Zeroizing<String>, String> {
+    let passphrase_prompt = Password::new("Enter your credentials passphrase:")
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .without_confirmation();
+    prompt_user(passphrase_prompt)
+        .map(Zeroizing::new)
+        .ok_or_else(|| String::from("A passphrase is required to read credentials"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypted_file(name: &str, passphrase: &str) -> EncryptedFile {
+        let mut unlocked = Unlocked::new(passphrase).unwrap();
+        unlocked.tokens.insert(
+            String::from("AC11111111111111111111111111111111"),
+            String::from("auth_token"),
+        );
+        EncryptedFile {
+            path: env::temp_dir()
+                .join(format!("twilly-{}-{}", std::process::id(), name))
+                .join("credentials.enc"),
+            unlocked: Some(unlocked),
+        }
+    }
+
+    #[test]
+    fn round_trips_tokens() {
+        let file = encrypted_file("round-trip", "passphrase");
+        file.save().unwrap();
+
+        // The only test reading the variable, so setting it doesn't race with others.
+        env::set_var(PASSPHRASE_VAR, "passphrase");
+        let contents = fs::read_to_string(&file.path).unwrap();
+        let unlocked = EncryptedFile::decrypt(&contents).unwrap();
+        assert_eq!(
+            Some("auth_token"),
+            unlocked
+                .tokens
+                .get("AC11111111111111111111111111111111")
+                .map(String::as_str)
+        );
+        assert!(!file.path.with_extension("enc.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&file.path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+
+        fs::remove_dir_all(file.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rejects_incorrect_passphrase() {
+        let contents = encrypted_file("unused", "passphrase")
+            .unlocked
+            .unwrap()
+            .seal()
+            .unwrap();
+
+        assert!(EncryptedFile::decrypt_with(&contents, "wrong").is_err());
+    }
+
+    #[test]
+    fn rejects_corrupt_salt_and_nonce() {
+        let contents = encrypted_file("unused", "passphrase")
+            .unlocked
+            .unwrap()
+            .seal()
+            .unwrap();
+        let contents: Contents = serde_json::from_str(&contents).unwrap();
+
+        let short_salt = serde_json::to_string(&Contents {
+            salt: STANDARD.encode([0u8; 8]),
+            nonce: contents.nonce.clone(),
+            ciphertext: contents.ciphertext.clone(),
+        })
+        .unwrap();
+        let short_nonce = serde_json::to_string(&Contents {
+            salt: contents.salt.clone(),
+            nonce: STANDARD.encode([0u8; 8]),
+            ciphertext: contents.ciphertext.clone(),
+        })
+        .unwrap();
+        let not_base64 = serde_json::to_string(&Contents {
+            salt: String::from("not base64!"),
+            ..contents
+        })
+        .unwrap();
+
+        for corrupt in [short_salt, short_nonce, not_base64] {
+            let error = EncryptedFile::decrypt_with(&corrupt, "passphrase")
+                .err()
+                .unwrap();
+            assert!(
+                error.starts_with("Credentials file is corrupt"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn uses_fresh_nonce_on_each_save() {
+        let unlocked = encrypted_file("unused", "passphrase").unlocked.unwrap();
+        let first: Contents = serde_json::from_str(&unlocked.seal().unwrap()).unwrap();
+        let second: Contents = serde_json::from_str(&unlocked.seal().unwrap()).unwrap();
+
+        assert_eq!(first.salt, second.salt);
+        assert_ne!(first.nonce, second.nonce);
+        assert_ne!(first.ciphertext, second.ciphertext);
+    }
+}
@@ -16,7 +16,7 @@ the functionality of the crate.
 - Additional _helpers_ not found in the default Twilio CLI.
 
 */
-use std::{fmt::Display, process};
+use std::{fmt::Display, process, sync::Mutex};
 
 use chrono::Datelike;
 use chrono::NaiveDate;
@@ -60,6 +60,23 @@ pub fn request_credentials() -> TwilioConfig {
     TwilioConfig::build(account_sid, auth_token)
 }
 
+/// Text shown before every prompt, e.g. the profile in use. Empty for none.
+static PROMPT_PREFIX: Mutex<String> = Mutex::new(String::new());
+
+/// Sets the text shown before every prompt from now on.
+pub fn set_prompt_prefix(prefix: String) {
+    *PROMPT_PREFIX.lock().unwrap() = prefix;
+}
+
+// The message of a prompt with the prefix in front of it.
+fn prefixed(message: &str) -> String {
+    let prefix = PROMPT_PREFIX.lock().unwrap();
+    match prefix.is_empty() {
+        true => message.to_string(),
+        false => format!("{} {}", prefix, message),
+    }
+}
+
 /// A wrapper around the Inquire crates various input controls. This is used
 /// to abstract the prompting and handling errors or cancellations.
 pub trait InquireControl<T> {
@@ -68,25 +85,37 @@ pub trait InquireControl<T> {
 
 impl InquireControl<String> for Text<'_> {
     fn prompt_user(&self) -> Result<String, InquireError> {
-        self.clone().prompt()
+        let message = prefixed(self.message);
+        let mut control: Text<'_> = self.clone();
+        control.message = &message;
+        control.prompt()
     }
 }
 
 impl InquireControl<String> for Password<'_> {
     fn prompt_user(&self) -> Result<String, InquireError> {
-        self.clone().prompt()
+        let message = prefixed(self.message);
+        let mut control: Password<'_> = self.clone();
+        control.message = &message;
+        control.prompt()
     }
 }
 
 impl InquireControl<bool> for Confirm<'_> {
     fn prompt_user(&self) -> Result<bool, InquireError> {
-        self.clone().prompt()
+        let message = prefixed(self.message);
+        let mut control: Confirm<'_> = self.clone();
+        control.message = &message;
+        control.prompt()
     }
 }
 
 impl InquireControl<NaiveDate> for DateSelect<'_> {
     fn prompt_user(&self) -> Result<NaiveDate, InquireError> {
-        self.clone().prompt()
+        let message = prefixed(self.message);
+        let mut control: DateSelect<'_> = self.clone();
+        control.message = &message;
+        control.prompt()
     }
 }
 
@@ -140,6 +169,9 @@ pub fn prompt_user<T>(control: impl InquireControl<T>) -> Option<T> {
 ///
 /// This has the same pattern as `prompt_user` for obvious reasons.
 pub fn prompt_user_selection<T: Display>(control: Select<'_, T>) -> Option<T> {
+    let message = prefixed(control.message);
+    let mut control: Select<'_, T> = control;
+    control.message = &message;
     match control.prompt() {
         Ok(result) => Some(result),
         Err(error) => handle_inquire_error(error),
@@ -147,6 +179,9 @@ pub fn prompt_user_selection<T: Display>(control: Select<'_, T>) -> Option<T> {
 }
 
 pub fn prompt_user_multi_selection<T: Display>(control: MultiSelect<'_, T>) -> Option<Vec<T>> {
+    let message = prefixed(control.message);
+    let mut control: MultiSelect<'_, T> = control;
+    control.message = &message;
     match control.prompt() {
         Ok(result) => Some(result),
         Err(error) => handle_inquire_error(error),
@@ -174,8 +209,7 @@ pub fn get_filter_choice_from_user(
     let filter_choice_prompt = Select::new(message, filter_options);
     let filter_choice_opt = prompt_user_selection(filter_choice_prompt);
 
-    if filter_choice_opt.is_some() {
-        let filter_choice = filter_choice_opt.unwrap();
+    if let Some(filter_choice) = filter_choice_opt {
         if filter_choice.as_str() == "Any" {
             Some(FilterChoice::Any)
         } else {
@@ -1,68 +1,74 @@
 mod account;
+mod commands;
 mod conversation;
+mod credentials;
+mod profile;
 mod serverless;
 mod sync;
 
 use std::{process, str::FromStr};
 
-use inquire::{Confirm, Select};
+use clap::Parser;
+use commands::{Cli, Output};
+use inquire::Select;
+use profile::Profiles;
 use strum::IntoEnumIterator;
-use twilly::{self, SubResource, TwilioConfig};
-use twilly_cli::{prompt_user_selection, request_credentials};
+use twilly::{self, SubResource};
+use twilly_cli::prompt_user_selection;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
-    print_welcome_message();
-
-    let mut loaded_config = false;
-    let mut config = confy::load::<TwilioConfig>("twilly", "profile").unwrap_or_else(|err| {
-        eprintln!("Unable to load profile configuration: {}", err);
-        TwilioConfig {
-            ..Default::default()
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(command) => {
+            let output = Output {
+                format: cli.output,
+                columns: cli.columns,
+            };
+            if let Err(error) = commands::run(
+                command,
+                cli.profile.as_deref(),
+                cli.subaccount.as_deref(),
+                &output,
+            )
+            .await
+            {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
         }
-    });
-
-    if config.account_sid.is_empty() | config.auth_token.is_empty() {
-        config = request_credentials();
-    } else if Confirm::new(&format!(
-        "Account ({}) found in memory. Use this profile?",
-        config.account_sid
-    ))
-    .with_default(true)
-    .with_placeholder("Y")
-    .prompt()
-    .unwrap()
-    {
-        loaded_config = true;
-    } else {
-        config = request_credentials();
+        None => run_interactive(cli.profile).await,
     }
+}
 
-    let twilio = twilly::Client::new(&config);
-
-    if !loaded_config {
-        println!("Checking account...");
-        let account = twilio
-            .accounts()
-            .get(None)
-            .await
-            .unwrap_or_else(|error| panic!("{}", error));
+/// Walks the user through the menus until they choose to exit.
+async fn run_interactive(profile_name: Option<String>) {
+    print_welcome_message();
 
-        println!(
-            "✅ Account details good! {} ({} - {})",
-            account.friendly_name, account.type_field, account.status
-        );
+    let mut profiles = Profiles::load();
+    let mut active_name = match profile_name {
+        Some(name) if profiles.profiles.contains_key(&name) => name,
+        Some(name) => {
+            eprintln!("No profile named {}.", name);
+            process::exit(1);
+        }
+        None => match profile::choose_profile(&mut profiles).await {
+            Some(name) => name,
+            None => process::exit(0),
+        },
+    };
 
-        confy::store("twilly", "profile", &config)
-            .unwrap_or_else(|err| eprintln!("Unable to store profile configuration: {}", err));
-    }
+    let Some(mut twilio) = profile::activate_profile(&mut profiles, &active_name) else {
+        process::exit(1);
+    };
 
     loop {
         let mut sub_resource_options: Vec<String> = SubResource::iter()
             .map(|sub_resource| sub_resource.to_string())
             .collect();
-        let mut exit_option = vec![String::from("Exit")];
-        sub_resource_options.append(&mut exit_option);
+        let mut profile_and_exit_options = vec![String::from("Profiles"), String::from("Exit")];
+        sub_resource_options.append(&mut profile_and_exit_options);
         let sub_resource_choice_prompt = Select::new("Select a resource:", sub_resource_options);
         let sub_resource_choice = prompt_user_selection(sub_resource_choice_prompt);
 
@@ -77,10 +83,29 @@ async fn main() {
             process::exit(0);
         }
 
+        if sub_resource == "Profiles" {
+            if let Some(name) = profile::choose_profile_action(&mut profiles, &active_name).await {
+                if let Some(client) = profile::activate_profile(&mut profiles, &name) {
+                    twilio = client;
+                    active_name = name;
+                }
+            }
+            continue;
+        }
+
         let sub_resource = SubResource::from_str(&sub_resource).unwrap();
 
         match sub_resource {
-            twilly::SubResource::Account => account::choose_account_action(&twilio).await,
+            twilly::SubResource::Account => {
+                if let Some(switch) = account::choose_account_action(&twilio).await {
+                    profile::show_active_profile(
+                        &active_name,
+                        &profiles.profiles[&active_name],
+                        switch.subaccount.as_deref(),
+                    );
+                    twilio = switch.client;
+                }
+            }
             twilly::SubResource::Conversations => {
                 conversation::choose_conversation_action(&twilio).await
             }
@@ -0,0 +1,725 @@
+use std::{collections::BTreeMap, process};
+
+use inquire::{validator::Validation, Confirm, Select, Text};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{Client, TwilioConfig};
+use twilly_cli::{prompt_user, prompt_user_selection, request_credentials, set_prompt_prefix};
+
+use crate::credentials::SecretStore;
+
+/// An account stored under a name so accounts can be switched between without retyping
+/// credentials. The auth token is kept in the [`SecretStore`] rather than alongside.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub account_sid: String,
+    /// Friendly name of the account, recorded when the profile was added.
+    #[serde(default)]
+    pub friendly_name: String,
+    /// Auth token saved in plaintext by earlier versions. Moved to the secret store on load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_token: Option<String>,
+}
+
+impl Profile {
+    pub fn new(account_sid: String, friendly_name: String) -> Profile {
+        Profile {
+            account_sid,
+            friendly_name,
+            auth_token: None,
+        }
+    }
+}
+
+/// Every stored profile, saved with confy as `twilly/profiles`.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Profiles {
+    /// Name of the profile used when none is chosen.
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Opened on first use as unlocking it may prompt for a passphrase.
+    #[serde(skip)]
+    secrets: Option<SecretStore>,
+}
+
+impl Profiles {
+    /// Loads the stored profiles. The single profile saved by earlier versions is moved
+    /// across as `default` the first time this runs, and any plaintext auth tokens are
+    /// moved into the secret store.
+    pub fn load() -> Profiles {
+        let mut profiles = confy::load::<Profiles>("twilly", "profiles").unwrap_or_else(|err| {
+            eprintln!("Unable to load profile configuration: {}", err);
+            Profiles::default()
+        });
+
+        let legacy_profile = profiles.profiles.is_empty()
+            && confy::load::<TwilioConfig>("twilly", "profile")
+                .is_ok_and(|config| profiles.adopt_legacy_profile(config));
+
+        // Until its token is in the secret store the previous profile is only held in
+        // memory, so the move is tried again next time rather than losing the token.
+        if profiles.move_plaintext_tokens() {
+            profiles.save();
+            if legacy_profile {
+                confy::store("twilly", "profile", TwilioConfig::default()).unwrap_or_else(|err| {
+                    eprintln!("Unable to clear the previous profile: {}", err)
+                });
+            }
+        }
+
+        profiles
+    }
+
+    /// Adds the single profile saved by earlier versions as `default`. Returns whether there
+    /// was one to add.
+    fn adopt_legacy_profile(&mut self, config: TwilioConfig) -> bool {
+        if config.account_sid.is_empty() || config.auth_token.is_empty() {
+            return false;
+        }
+
+        self.default = Some(String::from("default"));
+        self.profiles.insert(
+            String::from("default"),
+            Profile {
+                account_sid: config.account_sid,
+                friendly_name: String::new(),
+                auth_token: Some(config.auth_token),
+            },
+        );
+        true
+    }
+
+    /// Moves auth tokens saved in plaintext into the secret store. They are left in place
+    /// if the store can't be used. Returns whether any were moved.
+    fn move_plaintext_tokens(&mut self) -> bool {
+        let plaintext: Vec<(String, String)> = self
+            .profiles
+            .iter()
+            .filter_map(|(name, profile)| {
+                profile
+                    .auth_token
+                    .as_ref()
+                    .map(|auth_token| (name.clone(), auth_token.clone()))
+            })
+            .collect();
+        if plaintext.is_empty() {
+            return false;
+        }
+
+        eprintln!("Moving stored auth tokens to secure storage...");
+        for (name, auth_token) in plaintext {
+            let account_sid = self.profiles[&name].account_sid.clone();
+            if let Err(error) = self.secrets().set(&account_sid, &auth_token) {
+                eprintln!(
+                    "Unable to move auth tokens to secure storage, they remain in plaintext: {}",
+                    error
+                );
+                return false;
+            }
+            if let Some(profile) = self.profiles.get_mut(&name) {
+                profile.auth_token = None;
+            }
+        }
+        true
+    }
+
+    pub fn save(&self) {
+        confy::store("twilly", "profiles", self)
+            .unwrap_or_else(|err| eprintln!("Unable to store profile configuration: {}", err));
+    }
+
+    fn secrets(&mut self) -> &mut SecretStore {
+        self.secrets.get_or_insert_with(SecretStore::open)
+    }
+
+    /// The credentials of a profile, with the auth token read from the secret store.
+    pub fn config(&mut self, name: &str) -> Result<TwilioConfig, String> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| format!("No profile named {}", name))?;
+        let account_sid = profile.account_sid.clone();
+        let auth_token = match &profile.auth_token {
+            Some(auth_token) => auth_token.clone(),
+            None => self
+                .secrets()
+                .get(&account_sid)
+                .map_err(|error| format!("Unable to read auth token for {}: {}", name, error))?,
+        };
+
+        Ok(TwilioConfig {
+            account_sid,
+            auth_token,
+        })
+    }
+
+    /// The default profile, falling back to the first by name if the default was removed.
+    pub fn default_profile(&self) -> Option<(&String, &Profile)> {
+        self.default
+            .as_ref()
+            .and_then(|name| self.profiles.get_key_value(name))
+            .or_else(|| self.profiles.iter().next())
+    }
+
+    /// Adds a profile, storing its auth token in the secret store. The first profile added
+    /// becomes the default. Returns the name it was stored under, without surrounding
+    /// whitespace.
+    pub fn insert(
+        &mut self,
+        name: &str,
+        profile: Profile,
+        auth_token: &str,
+    ) -> Result<String, String> {
+        let name = self.check_name(name)?;
+        self.secrets().set(&profile.account_sid, auth_token)?;
+        if self.default.is_none() {
+            self.default = Some(name.clone());
+        }
+        self.profiles.insert(name.clone(), profile);
+        Ok(name)
+    }
+
+    /// Renames a profile, returning the new name without surrounding whitespace.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<String, String> {
+        let to = self.check_name(to)?;
+        let profile = self
+            .profiles
+            .remove(from)
+            .ok_or_else(|| format!("No profile named {}", from))?;
+
+        if self.default.as_deref() == Some(from) {
+            self.default = Some(to.clone());
+        }
+        self.profiles.insert(to.clone(), profile);
+        Ok(to)
+    }
+
+    /// Trims a name for a new or renamed profile, checking it isn't empty or taken.
+    pub fn check_name(&self, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(String::from("Profile name cannot be empty"));
+        }
+        if self.profiles.contains_key(name) {
+            return Err(format!("A profile named {} already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Removes a profile. Its auth token is deleted unless another profile uses the same
+    /// account.
+    pub fn remove(&mut self, name: &str) -> Option<Profile> {
+        if self.default.as_deref() == Some(name) {
+            self.default = None;
+        }
+        let profile = self.profiles.remove(name)?;
+
+        let shared = self
+            .profiles
+            .values()
+            .any(|other| other.account_sid == profile.account_sid);
+        if !shared {
+            if let Err(error) = self.secrets().delete(&profile.account_sid) {
+                eprintln!("Unable to delete stored auth token: {}", error);
+            }
+        }
+        Some(profile)
+    }
+
+    /// Replaces the auth token stored for an account, e.g. after it was rotated. Every
+    /// profile for the account uses the new token.
+    pub fn set_auth_token(&mut self, account_sid: &str, auth_token: &str) -> Result<(), String> {
+        self.secrets().set(account_sid, auth_token)?;
+        for profile in self.profiles.values_mut() {
+            if profile.account_sid == account_sid {
+                profile.auth_token = None;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_default(&mut self, name: &str) -> Result<(), String> {
+        match self.profiles.contains_key(name) {
+            true => {
+                self.default = Some(name.to_string());
+                Ok(())
+            }
+            false => Err(format!("No profile named {}", name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum Action {
+    #[strum(to_string = "Switch profile")]
+    SwitchProfile,
+    #[strum(to_string = "Add profile")]
+    AddProfile,
+    #[strum(to_string = "List profiles")]
+    ListProfiles,
+    #[strum(to_string = "Rename profile")]
+    RenameProfile,
+    #[strum(to_string = "Remove profile")]
+    RemoveProfile,
+    #[strum(to_string = "Set default profile")]
+    SetDefaultProfile,
+    #[strum(to_string = "Rotate auth token")]
+    RotateAuthToken,
+    Back,
+    Exit,
+}
+
+/// Reads the credentials of a profile and creates a client for it, marking it as the
+/// profile in use.
+pub fn activate_profile(profiles: &mut Profiles, name: &str) -> Option<Client> {
+    match profiles.config(name) {
+        Ok(config) => {
+            show_active_profile(name, &profiles.profiles[name], None);
+            Some(Client::new(&config))
+        }
+        Err(error) => {
+            eprintln!("Unable to use profile. Action aborted: {}", error);
+            println!();
+            None
+        }
+    }
+}
+
+/// Prefixes every prompt with the profile in use, and the subaccount if one was switched
+/// into, so it's clear which account an action applies to.
+pub fn show_active_profile(name: &str, profile: &Profile, subaccount: Option<&str>) {
+    let mut prefix = match profile.friendly_name.is_empty() {
+        true => name.to_string(),
+        false => format!("{} - {}", name, profile.friendly_name),
+    };
+    if let Some(subaccount) = subaccount {
+        prefix = format!("{} > {}", prefix, subaccount);
+    }
+    set_prompt_prefix(format!("[{}]", prefix));
+}
+
+/// Asks which profile to use at startup, adding one if none exist.
+pub async fn choose_profile(profiles: &mut Profiles) -> Option<String> {
+    if profiles.profiles.is_empty() {
+        println!("No profiles found. Let's add one.");
+        return add_profile(profiles).await;
+    }
+
+    let default_name = profiles.default_profile().map(|(name, _)| name.clone());
+    let mut names: Vec<String> = profiles.profiles.keys().cloned().collect();
+    let starting_cursor = names
+        .iter()
+        .position(|name| Some(name) == default_name.as_ref())
+        .unwrap_or_default();
+
+    let mut options: Vec<String> = names
+        .iter()
+        .map(|name| describe_profile(name, &profiles.profiles[name]))
+        .collect();
+    options.push(String::from("Add profile"));
+
+    let choice = prompt_user_selection(
+        Select::new("Choose a profile:", options.clone()).with_starting_cursor(starting_cursor),
+    )?;
+    match options.iter().position(|option| option == &choice) {
+        Some(position) if position < names.len() => Some(names.swap_remove(position)),
+        _ => add_profile(profiles).await,
+    }
+}
+
+/// Manages the stored profiles. Returns the profile now in use if it changed.
+pub async fn choose_profile_action(profiles: &mut Profiles, active_name: &str) -> Option<String> {
+    let options: Vec<Action> = Action::iter().collect();
+    let mut active_name = active_name.to_string();
+    let mut changed = None;
+
+    loop {
+        let action_selection_prompt = Select::new("Select an action:", options.clone());
+        let Some(action) = prompt_user_selection(action_selection_prompt) else {
+            break;
+        };
+
+        match action {
+            Action::SwitchProfile => {
+                if let Some(name) = choose_profile_name(profiles, "Switch to:") {
+                    println!("Switched to {}.", name);
+                    println!();
+                    return Some(name);
+                }
+            }
+            Action::AddProfile => {
+                if let Some(name) = add_profile(profiles).await {
+                    println!("Profile {} added.", name);
+                    println!();
+                }
+            }
+            Action::ListProfiles => {
+                for (name, profile) in &profiles.profiles {
+                    let mut description = describe_profile(name, profile);
+                    if name == &active_name {
+                        description.push_str(" [active]");
+                    }
+                    println!("{}", description);
+                }
+                println!();
+            }
+            Action::RenameProfile => {
+                if let Some(name) = choose_profile_name(profiles, "Choose a profile to rename:") {
+                    let Some(new_name) = prompt_user(profile_name_prompt(profiles)) else {
+                        continue;
+                    };
+
+                    match profiles.rename(&name, &new_name) {
+                        Ok(new_name) => {
+                            profiles.save();
+                            if name == active_name {
+                                active_name = new_name.clone();
+                                changed = Some(new_name.clone());
+                            }
+                            println!("Profile renamed to {}.", new_name);
+                            println!();
+                        }
+                        Err(error) => {
+                            eprintln!("Unable to rename profile. Action aborted: {}", error);
+                            println!();
+                        }
+                    }
+                }
+            }
+            Action::RemoveProfile => {
+                if let Some(name) = choose_profile_name(profiles, "Choose a profile to remove:") {
+                    if name == active_name {
+                        println!("Switch to another profile before removing {}.", name);
+                        println!();
+                        continue;
+                    }
+
+                    let confirm_message = format!("Are you sure you wish to remove {}?", name);
+                    let confirm_prompt = Confirm::new(&confirm_message)
+                        .with_placeholder("N")
+                        .with_default(false);
+                    if prompt_user(confirm_prompt) == Some(true) {
+                        profiles.remove(&name);
+                        profiles.save();
+                        println!("Profile removed.");
+                        println!();
+                    }
+                }
+            }
+            Action::SetDefaultProfile => {
+                if let Some(name) = choose_profile_name(profiles, "Use by default:") {
+                    profiles
+                        .set_default(&name)
+                        .expect("Chosen profile should exist");
+                    profiles.save();
+                    println!("{} is now the default profile.", name);
+                    println!();
+                }
+            }
+            Action::RotateAuthToken => {
+                let Some(name) = choose_profile_name(profiles, "Rotate the auth token of:") else {
+                    continue;
+                };
+
+                let confirm_message = format!(
+                    "Replace the auth token of {}? The current token stops working once the new one is promoted.",
+                    name
+                );
+                let confirm_prompt = Confirm::new(&confirm_message)
+                    .with_placeholder("N")
+                    .with_default(false);
+                if prompt_user(confirm_prompt) != Some(true) {
+                    continue;
+                }
+
+                match rotate_auth_token(profiles, &name).await {
+                    Ok(_) => {
+                        // The client in use holds the old token so is recreated.
+                        changed = Some(active_name.clone());
+                        println!("Auth token rotated and stored.");
+                        println!();
+                    }
+                    Err(error) => {
+                        eprintln!("Unable to rotate auth token. Action aborted: {}", error);
+                        println!();
+                    }
+                }
+            }
+            Action::Back => break,
+            Action::Exit => process::exit(0),
+        }
+    }
+
+    changed
+}
+
+/// Replaces the auth token of a profile's account. A secondary token is created and checked
+/// before being promoted, then stored in place of the old token. Progress is written to
+/// stderr.
+pub async fn rotate_auth_token(profiles: &mut Profiles, name: &str) -> Result<(), String> {
+    let config = profiles.config(name)?;
+    let twilio = Client::new(&config);
+    let accounts = twilio.accounts();
+    let auth_tokens = accounts.auth_tokens();
+
+    eprintln!("Creating secondary auth token...");
+    let secondary = auth_tokens
+        .create_secondary()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    eprintln!("Checking secondary auth token...");
+    let secondary_config = TwilioConfig {
+        account_sid: config.account_sid.clone(),
+        auth_token: secondary.secondary_auth_token,
+    };
+    if let Err(error) = Client::new(&secondary_config).accounts().get(None).await {
+        if let Err(error) = auth_tokens.delete_secondary().await {
+            eprintln!("Unable to delete the secondary auth token: {}", error);
+        }
+        return Err(format!("Secondary auth token was not accepted: {}", error));
+    }
+
+    eprintln!("Promoting secondary auth token...");
+    let promotion = auth_tokens
+        .promote_secondary()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    profiles
+        .set_auth_token(&config.account_sid, &promotion.auth_token)
+        .map_err(|error| {
+            format!(
+                "The new auth token could not be stored, keep it safe and add the profile again: {} ({})",
+                promotion.auth_token, error
+            )
+        })?;
+    profiles.save();
+    Ok(())
+}
+
+/// Requests credentials, checks them against Twilio and stores them under a name chosen
+/// by the user.
+async fn add_profile(profiles: &mut Profiles) -> Option<String> {
+    let config = request_credentials();
+
+    println!("Checking account...");
+    let account = match Client::new(&config).accounts().get(None).await {
+        Ok(account) => account,
+        Err(error) => {
+            eprintln!("Unable to check account. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+    println!(
+        "✅ Account details good! {} ({} - {})",
+        account.friendly_name, account.type_field, account.status
+    );
+
+    let name = prompt_user(profile_name_prompt(profiles))?;
+    let profile = Profile::new(config.account_sid, account.friendly_name);
+    let name = match profiles.insert(&name, profile, &config.auth_token) {
+        Ok(name) => name,
+        Err(error) => {
+            eprintln!("Unable to store credentials. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+    profiles.save();
+
+    Some(name)
+}
+
+fn profile_name_prompt(profiles: &Profiles) -> Text<'static> {
+    let existing: Vec<String> = profiles.profiles.keys().cloned().collect();
+    Text::new("Enter a profile name:")
+        .with_placeholder("prod")
+        .with_validator(move |val: &str| {
+            if val.trim().is_empty() {
+                Ok(Validation::Invalid("Profile name cannot be empty".into()))
+            } else if existing.iter().any(|name| name == val.trim()) {
+                Ok(Validation::Invalid(
+                    "A profile with this name already exists".into(),
+                ))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })
+}
+
+/// Asks the user to choose one of the stored profiles by name.
+fn choose_profile_name(profiles: &Profiles, message: &str) -> Option<String> {
+    if profiles.profiles.is_empty() {
+        println!("No profiles found.");
+        println!();
+        return None;
+    }
+
+    prompt_user_selection(Select::new(
+        message,
+        profiles.profiles.keys().cloned().collect(),
+    ))
+}
+
+fn describe_profile(name: &str, profile: &Profile) -> String {
+    match profile.friendly_name.is_empty() {
+        true => format!("{} ({})", name, profile.account_sid),
+        false => format!(
+            "{} ({} - {})",
+            name, profile.friendly_name, profile.account_sid
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT_SID: &str = "AC11111111111111111111111111111111";
+    const AUTH_TOKEN: &str = "11111111111111111111111111111111";
+
+    fn profiles(secrets: SecretStore) -> Profiles {
+        Profiles {
+            secrets: Some(secrets),
+            ..Default::default()
+        }
+    }
+
+    fn stored_token(profiles: &mut Profiles, account_sid: &str) -> Option<String> {
+        profiles.secrets().get(account_sid).ok()
+    }
+
+    #[test]
+    fn loads_profiles_with_plaintext_tokens() {
+        let mut profiles: Profiles = serde_json::from_value(serde_json::json!({
+            "default": "prod",
+            "profiles": {
+                "prod": {
+                    "account_sid": ACCOUNT_SID,
+                    "friendly_name": "Production",
+                    "auth_token": AUTH_TOKEN,
+                },
+            },
+        }))
+        .unwrap();
+        profiles.secrets = Some(SecretStore::Memory(Some(BTreeMap::new())));
+
+        assert!(profiles.move_plaintext_tokens());
+        assert_eq!(None, profiles.profiles["prod"].auth_token);
+        assert_eq!(
+            Some(AUTH_TOKEN.to_string()),
+            stored_token(&mut profiles, ACCOUNT_SID)
+        );
+        let saved = serde_json::to_value(&profiles).unwrap();
+        assert!(saved["profiles"]["prod"].get("auth_token").is_none());
+        assert!(!profiles.move_plaintext_tokens());
+    }
+
+    #[test]
+    fn migrates_legacy_profile() {
+        let mut profiles = profiles(SecretStore::Memory(Some(BTreeMap::new())));
+        assert!(!profiles.adopt_legacy_profile(TwilioConfig::default()));
+
+        assert!(profiles.adopt_legacy_profile(TwilioConfig {
+            account_sid: ACCOUNT_SID.to_string(),
+            auth_token: AUTH_TOKEN.to_string(),
+        }));
+        assert_eq!(Some("default"), profiles.default.as_deref());
+        assert!(profiles.move_plaintext_tokens());
+        assert_eq!(
+            AUTH_TOKEN,
+            profiles.config("default").unwrap().auth_token.as_str()
+        );
+    }
+
+    #[test]
+    fn keeps_plaintext_tokens_when_store_unavailable() {
+        let mut profiles = profiles(SecretStore::Memory(None));
+        profiles.adopt_legacy_profile(TwilioConfig {
+            account_sid: ACCOUNT_SID.to_string(),
+            auth_token: AUTH_TOKEN.to_string(),
+        });
+
+        assert!(!profiles.move_plaintext_tokens());
+        assert_eq!(
+            AUTH_TOKEN,
+            profiles.config("default").unwrap().auth_token.as_str()
+        );
+    }
+
+    #[test]
+    fn inserts_profiles_with_checked_names() {
+        let mut profiles = profiles(SecretStore::Memory(Some(BTreeMap::new())));
+
+        let name = profiles
+            .insert(
+                " prod ",
+                Profile::new(ACCOUNT_SID.to_string(), String::new()),
+                AUTH_TOKEN,
+            )
+            .unwrap();
+        assert_eq!("prod", name);
+        assert_eq!(Some("prod"), profiles.default.as_deref());
+        assert_eq!(
+            Some(AUTH_TOKEN.to_string()),
+            stored_token(&mut profiles, ACCOUNT_SID)
+        );
+
+        let profile = Profile::new(ACCOUNT_SID.to_string(), String::new());
+        assert!(profiles
+            .insert("prod", profile.clone(), AUTH_TOKEN)
+            .is_err());
+        assert!(profiles.insert("  ", profile, AUTH_TOKEN).is_err());
+        assert_eq!(1, profiles.profiles.len());
+    }
+
+    #[test]
+    fn renames_profiles() {
+        let mut profiles = profiles(SecretStore::Memory(Some(BTreeMap::new())));
+        for name in ["prod", "staging"] {
+            profiles
+                .insert(
+                    name,
+                    Profile::new(ACCOUNT_SID.to_string(), String::new()),
+                    AUTH_TOKEN,
+                )
+                .unwrap();
+        }
+
+        assert_eq!("live", profiles.rename("prod", " live ").unwrap());
+        assert_eq!(Some("live"), profiles.default.as_deref());
+        assert!(profiles.rename("live", "staging").is_err());
+        assert!(profiles.rename("live", "").is_err());
+        assert!(profiles.rename("missing", "other").is_err());
+
+        let names: Vec<&String> = profiles.profiles.keys().collect();
+        assert_eq!(vec!["live", "staging"], names);
+    }
+
+    #[test]
+    fn removes_token_once_unused() {
+        let mut profiles = profiles(SecretStore::Memory(Some(BTreeMap::new())));
+        for name in ["prod", "prod-copy"] {
+            profiles
+                .insert(
+                    name,
+                    Profile::new(ACCOUNT_SID.to_string(), String::new()),
+                    AUTH_TOKEN,
+                )
+                .unwrap();
+        }
+
+        assert!(profiles.remove("prod").is_some());
+        assert_eq!(None, profiles.default);
+        assert!(stored_token(&mut profiles, ACCOUNT_SID).is_some());
+
+        assert!(profiles.remove("prod-copy").is_some());
+        assert!(stored_token(&mut profiles, ACCOUNT_SID).is_none());
+        assert!(profiles.remove("prod-copy").is_none());
+    }
+}
@@ -1,4 +1,7 @@
-mod environments;
+mod assets;
+pub(crate) mod environments;
+mod functions;
+pub(crate) mod project;
 
 use std::process;
 
@@ -14,6 +17,8 @@ pub enum Action {
     #[strum(to_string = "List Details")]
     ListDetails,
     Environments,
+    Functions,
+    Assets,
     Delete,
     Back,
     Exit,
@@ -29,11 +34,11 @@ pub async fn choose_serverless_resource(twilio: &Client) {
 
     if serverless_services.is_empty() {
         println!("No Serverless Services found.");
-        return;
+    } else {
+        println!("Found {} Serverless Services.", serverless_services.len());
     }
 
-    println!("Found {} Serverless Services.", serverless_services.len());
-
+    let unique_name_chars = Regex::new(r"^[a-zA-Z0-9]+(?:-[a-zA-Z0-9]+)*$").unwrap();
     let mut selected_serverless_service_index: Option<usize> = None;
     loop {
         let selected_serverless_service = if let Some(index) = selected_serverless_service_index {
@@ -44,6 +49,7 @@ pub async fn choose_serverless_resource(twilio: &Client) {
                 .map(|service| format!("({}) {}", service.sid, service.unique_name))
                 .collect::<Vec<String>>();
             existing_services.push("Create Serverless Service".into());
+            existing_services.push("Deploy Local Project".into());
             if let Some(action_choice) =
                 get_action_choice_from_user(existing_services, "Choose a Serverless Service: ")
             {
@@ -53,7 +59,17 @@ pub async fn choose_serverless_resource(twilio: &Client) {
                     }
                     ActionChoice::Exit => process::exit(0),
                     ActionChoice::Other(choice) => {
-                        if choice == "Create Serverless Service" {
+                        if choice == "Deploy Local Project" {
+                            if let Some(deployed_service) = project::deploy_project(twilio).await {
+                                if !serverless_services
+                                    .iter()
+                                    .any(|service| service.sid == deployed_service.sid)
+                                {
+                                    serverless_services.push(deployed_service);
+                                }
+                            }
+                            continue;
+                        } else if choice == "Create Serverless Service" {
                             let unique_name_prompt = Text::new("Enter a unique name:")
                                 .with_validator(|val: &str| {
                                     if val.len() <= 50 {
@@ -64,18 +80,19 @@ pub async fn choose_serverless_resource(twilio: &Client) {
                                         ))
                                     }
                                 })
-                                .with_validator(|val: &str| {
-                                    let allowed_chars =
-                                        Regex::new(r"^[a-zA-Z0-9]+(?:-[a-zA-Z0-9]+)*$").unwrap();
-                                    let trimmed_name = val.trim();
-                                    if !allowed_chars.is_match(trimmed_name) {
-                                        return Ok(Validation::Invalid(
+                                .with_validator({
+                                    let allowed_chars = unique_name_chars.clone();
+                                    move |val: &str| {
+                                        let trimmed_name = val.trim();
+                                        if !allowed_chars.is_match(trimmed_name) {
+                                            return Ok(Validation::Invalid(
                                             "Name doesn't match required filter '^[a-zA-Z0-9-_]+$'"
                                                 .into(),
                                         ));
-                                    }
+                                        }
 
-                                    Ok(Validation::Valid)
+                                        Ok(Validation::Valid)
+                                    }
                                 });
 
                             if let Some(unique_name) = prompt_user(unique_name_prompt) {
@@ -167,6 +184,12 @@ pub async fn choose_serverless_resource(twilio: &Client) {
                     environments::choose_environment_action(twilio, selected_serverless_service)
                         .await
                 }
+                Action::Functions => {
+                    functions::choose_function_action(twilio, selected_serverless_service).await
+                }
+                Action::Assets => {
+                    assets::choose_asset_action(twilio, selected_serverless_service).await
+                }
                 Action::Delete => {
                     let confirm_prompt =
                         Confirm::new("Are you sure you wish to delete the Serverless Service?")
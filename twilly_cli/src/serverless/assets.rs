@@ -0,0 +1,198 @@
+use std::{fs, path::Path, process};
+
+use inquire::{Confirm, Select, Text};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{
+    serverless::{
+        assets::ServerlessAsset, services::ServerlessService, upload::Manifest, Visibility,
+    },
+    Client,
+};
+use twilly_cli::{prompt_user, prompt_user_selection};
+
+/// Actions general to Assets.
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum AssetsAction {
+    #[strum(to_string = "List Assets")]
+    ListAssets,
+    #[strum(to_string = "List Versions")]
+    ListVersions,
+    #[strum(to_string = "Upload Directory")]
+    UploadDirectory,
+    #[strum(to_string = "Delete Asset")]
+    DeleteAsset,
+    Back,
+    Exit,
+}
+
+pub async fn choose_asset_action(twilio: &Client, serverless_service: &ServerlessService) {
+    let options: Vec<AssetsAction> = AssetsAction::iter().collect();
+
+    loop {
+        let resource_selection_prompt = Select::new("Select an action:", options.clone());
+        if let Some(resource) = prompt_user_selection(resource_selection_prompt) {
+            let serverless = twilio.serverless();
+            let service = serverless.service(&serverless_service.sid);
+
+            match resource {
+                AssetsAction::ListAssets => {
+                    let assets = service
+                        .assets()
+                        .list()
+                        .await
+                        .unwrap_or_else(|error| panic!("{}", error));
+
+                    if assets.is_empty() {
+                        println!("No Assets found.");
+                    }
+                    for asset in &assets {
+                        println!("({}) {}", asset.sid, asset.friendly_name);
+                    }
+                    println!();
+                }
+                AssetsAction::ListVersions => {
+                    if let Some(asset) = choose_asset(twilio, serverless_service).await {
+                        let versions = service
+                            .asset(&asset.sid)
+                            .versions()
+                            .list()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+
+                        if versions.is_empty() {
+                            println!("No Versions found.");
+                        }
+                        for version in &versions {
+                            println!(
+                                "({}) {} [{}] {}",
+                                version.sid, version.path, version.visibility, version.date_created
+                            );
+                        }
+                        println!();
+                    }
+                }
+                AssetsAction::UploadDirectory => upload_directory(twilio, serverless_service).await,
+                AssetsAction::DeleteAsset => {
+                    if let Some(asset) = choose_asset(twilio, serverless_service).await {
+                        let confirm_message =
+                            format!("Are you sure you wish to delete {}?", asset.friendly_name);
+                        let confirm_prompt = Confirm::new(&confirm_message)
+                            .with_placeholder("N")
+                            .with_default(false);
+
+                        if prompt_user(confirm_prompt) == Some(true) {
+                            match service.asset(&asset.sid).delete().await {
+                                Ok(_) => {
+                                    println!("Asset deleted.");
+                                    println!();
+                                }
+                                Err(error) => {
+                                    eprintln!("Unable to delete Asset. Action aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                AssetsAction::Back => break,
+                AssetsAction::Exit => process::exit(0),
+            }
+        }
+    }
+}
+
+/// Uploads a local directory as Assets. A manifest of what was uploaded is kept in the
+/// directory so unchanged files are skipped next time.
+async fn upload_directory(twilio: &Client, serverless_service: &ServerlessService) {
+    let Some(directory) = prompt_user(Text::new("Enter the directory to upload:")) else {
+        return;
+    };
+    let directory = Path::new(&directory);
+    if !directory.is_dir() {
+        eprintln!("{} is not a directory.", directory.display());
+        println!();
+        return;
+    }
+
+    let visibility_prompt = Select::new(
+        "Choose the visibility:",
+        Visibility::iter().collect::<Vec<Visibility>>(),
+    )
+    .with_starting_cursor(1);
+    let Some(visibility) = prompt_user_selection(visibility_prompt) else {
+        return;
+    };
+
+    let manifest_path = directory.join(format!(".twilly-assets-{}.json", serverless_service.sid));
+    let previous: Manifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+
+    println!("Uploading...");
+    // A failed upload still saves the Versions created so far so the next run skips them.
+    let (upload, error) = match twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .assets()
+        .upload_directory(directory, visibility, &previous)
+        .await
+    {
+        Ok(upload) => (upload, None),
+        Err(failed) => (failed.partial, Some(failed.error)),
+    };
+
+    for version in &upload.uploaded {
+        println!("Uploaded {} ({})", version.path, version.sid);
+    }
+    println!(
+        "{} uploaded, {} unchanged.",
+        upload.uploaded.len(),
+        upload.unchanged.len()
+    );
+
+    let manifest =
+        serde_json::to_string_pretty(&upload.manifest).expect("Manifest should serialize to JSON");
+    if let Err(error) = fs::write(&manifest_path, manifest) {
+        eprintln!("Unable to save the upload manifest: {}", error);
+    }
+    if let Some(error) = error {
+        eprintln!("Unable to upload directory. Action aborted: {}", error);
+    }
+    println!();
+}
+
+/// Lists the Service's Assets and asks the user to choose one.
+async fn choose_asset(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+) -> Option<ServerlessAsset> {
+    let mut assets = twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .assets()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    if assets.is_empty() {
+        println!("No Assets found.");
+        println!();
+        return None;
+    }
+
+    let choice = prompt_user_selection(Select::new(
+        "Choose an Asset:",
+        assets
+            .iter()
+            .map(|asset| format!("({}) {}", asset.sid, asset.friendly_name))
+            .collect(),
+    ))?;
+    let position = assets
+        .iter()
+        .position(|asset| asset.sid == choice[1..35])
+        .expect("Could not find Asset in existing Asset list");
+
+    Some(assets.remove(position))
+}
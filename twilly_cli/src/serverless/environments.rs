@@ -1,4 +1,7 @@
-mod logs;
+pub(crate) mod deploy;
+pub(crate) mod deployments;
+pub(crate) mod logs;
+mod variables;
 
 use std::process;
 
@@ -12,7 +15,13 @@ use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection
 pub enum Action {
     #[strum(to_string = "List Details")]
     ListDetails,
+    Deploy,
+    #[strum(to_string = "Deployment History")]
+    DeploymentHistory,
+    Promote,
+    Rollback,
     Logs,
+    Variables,
     Delete,
     Back,
     Exit,
@@ -77,6 +86,37 @@ pub async fn choose_environment_action(twilio: &Client, serverless_service: &Ser
                     println!("{:#?}", selected_serverless_environment);
                     println!();
                 }
+                Action::Deploy => {
+                    deploy::deploy(twilio, serverless_service, selected_serverless_environment)
+                        .await
+                }
+                Action::DeploymentHistory => {
+                    deployments::deployment_history(
+                        twilio,
+                        serverless_service,
+                        selected_serverless_environment,
+                    )
+                    .await
+                }
+                Action::Promote => {
+                    let index = selected_serverless_environment_index
+                        .expect("Could not find Serverless Environment in existing Serverless Environment list");
+                    deployments::promote(
+                        twilio,
+                        serverless_service,
+                        &serverless_environments[index],
+                        &serverless_environments,
+                    )
+                    .await
+                }
+                Action::Rollback => {
+                    deployments::rollback(
+                        twilio,
+                        serverless_service,
+                        selected_serverless_environment,
+                    )
+                    .await
+                }
                 Action::Logs => {
                     logs::choose_log_action(
                         twilio,
@@ -85,6 +125,14 @@ pub async fn choose_environment_action(twilio: &Client, serverless_service: &Ser
                     )
                     .await
                 }
+                Action::Variables => {
+                    variables::choose_variable_action(
+                        twilio,
+                        serverless_service,
+                        selected_serverless_environment,
+                    )
+                    .await
+                }
                 Action::Delete => {
                     let confirm_prompt =
                         Confirm::new("Are you sure you wish to delete the Serverless Environment?")
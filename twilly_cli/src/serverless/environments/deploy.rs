@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use inquire::{validator::Validation, Select, Text};
+use twilly::{
+    serverless::{
+        builds::{BuildStatus, CreateParams, Dependency, ServerlessBuild},
+        environments::{deployments, ServerlessEnvironment},
+        services::ServerlessService,
+    },
+    Client, TwilioError,
+};
+use twilly_cli::{prompt_user, prompt_user_selection};
+
+const BUILD_LATEST: &str = "Build latest Function & Asset Versions";
+const EXISTING_BUILD: &str = "Deploy an existing Build";
+
+/// Builds (or picks an existing Build) and deploys it to the Environment, printing progress
+/// as it goes.
+pub async fn deploy(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let Some(source) = prompt_user_selection(Select::new(
+        "What would you like to deploy?",
+        vec![BUILD_LATEST, EXISTING_BUILD],
+    )) else {
+        return;
+    };
+
+    let build = if source == BUILD_LATEST {
+        match build_latest(twilio, serverless_service).await {
+            Some(build) => build,
+            None => return,
+        }
+    } else {
+        match choose_completed_build(twilio, serverless_service).await {
+            Some(build) => build,
+            None => return,
+        }
+    };
+
+    println!("Deploying {}...", build.sid);
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+    let environment = service.environment(&serverless_environment.sid);
+    match environment
+        .deployments()
+        .create(deployments::CreateParams {
+            build_sid: Some(build.sid.clone()),
+        })
+        .await
+    {
+        Ok(deployment) => {
+            println!("Deployment {} created.", deployment.sid);
+            let domain_name = environment
+                .get()
+                .await
+                .map(|environment| environment.domain_name)
+                .unwrap_or_else(|_| serverless_environment.domain_name.clone());
+
+            for version in build
+                .function_versions
+                .iter()
+                .chain(build.asset_versions.iter())
+                .flatten()
+            {
+                println!("  https://{}{}", domain_name, version.path);
+            }
+            println!();
+        }
+        Err(error) => {
+            eprintln!("Unable to deploy Build. Action aborted: {}", error);
+            println!();
+        }
+    }
+}
+
+/// Creates a Build from the newest Version of every Function and Asset in the Service and
+/// waits for it to complete.
+async fn build_latest(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+) -> Option<ServerlessBuild> {
+    let dependencies_prompt = Text::new("Enter npm dependencies (optional):")
+        .with_placeholder("axios@1.6.0, twilio@4.19.0")
+        .with_validator(|val: &str| match parse_dependencies(val) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(error) => Ok(Validation::Invalid(error.to_string().into())),
+        });
+    let dependencies = parse_dependencies(&prompt_user(dependencies_prompt)?).ok()?;
+
+    println!("Collecting latest Versions...");
+    let params = match latest_versions(twilio, serverless_service).await {
+        Ok((function_versions, asset_versions)) => CreateParams {
+            function_versions,
+            asset_versions,
+            dependencies,
+            runtime: None,
+        },
+        Err(error) => {
+            eprintln!("Unable to collect Versions. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+
+    if params.function_versions.is_empty() && params.asset_versions.is_empty() {
+        println!("No Function or Asset Versions found to build.");
+        println!();
+        return None;
+    }
+
+    println!(
+        "Building {} Function and {} Asset Versions...",
+        params.function_versions.len(),
+        params.asset_versions.len()
+    );
+
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+    let build = match service.builds().create(params).await {
+        Ok(build) => build,
+        Err(error) => {
+            eprintln!("Unable to create Build. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+
+    let result = service
+        .build(&build.sid)
+        .wait(
+            Duration::from_secs(2),
+            Duration::from_secs(300),
+            |status, elapsed| println!("  {} {} ({}s)", build.sid, status, elapsed.as_secs()),
+        )
+        .await;
+
+    match result {
+        Ok(build) if build.status == BuildStatus::Completed => Some(build),
+        Ok(build) => {
+            eprintln!("Build {} failed. Action aborted.", build.sid);
+            println!();
+            None
+        }
+        Err(error) => {
+            eprintln!("Unable to complete Build. Action aborted: {}", error);
+            println!();
+            None
+        }
+    }
+}
+
+/// The SIDs of the newest Version of each Function and Asset.
+pub(crate) async fn latest_versions(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+) -> Result<(Vec<String>, Vec<String>), TwilioError> {
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+
+    let mut function_versions = Vec::new();
+    for function in service.functions().list().await? {
+        let versions = service.function(&function.sid).versions().list().await?;
+        if let Some(latest) = versions
+            .into_iter()
+            .max_by(|a, b| a.date_created.cmp(&b.date_created))
+        {
+            function_versions.push(latest.sid);
+        }
+    }
+
+    let mut asset_versions = Vec::new();
+    for asset in service.assets().list().await? {
+        let versions = service.asset(&asset.sid).versions().list().await?;
+        if let Some(latest) = versions
+            .into_iter()
+            .max_by(|a, b| a.date_created.cmp(&b.date_created))
+        {
+            asset_versions.push(latest.sid);
+        }
+    }
+
+    Ok((function_versions, asset_versions))
+}
+
+/// Lists the Service's completed Builds and asks the user to choose one.
+async fn choose_completed_build(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+) -> Option<ServerlessBuild> {
+    let mut builds = twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .builds()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+    builds.retain(|build| build.status == BuildStatus::Completed);
+
+    if builds.is_empty() {
+        println!("No completed Builds found.");
+        println!();
+        return None;
+    }
+
+    let choice = prompt_user_selection(Select::new(
+        "Choose a Build:",
+        builds
+            .iter()
+            .map(|build| format!("({}) {}", build.sid, build.date_created))
+            .collect(),
+    ))?;
+    let position = builds
+        .iter()
+        .position(|build| build.sid == choice[1..35])
+        .expect("Could not find Build in existing Build list");
+
+    Some(builds.remove(position))
+}
+
+pub(crate) fn parse_dependencies(input: &str) -> Result<Vec<Dependency>, TwilioError> {
+    input
+        .split(',')
+        .filter(|dependency| !dependency.trim().is_empty())
+        .map(|dependency| dependency.parse())
+        .collect()
+}
@@ -0,0 +1,247 @@
+use inquire::{Confirm, Select};
+use twilly::{
+    serverless::{
+        builds::BuildDiff, environments::ServerlessEnvironment, services::ServerlessService,
+    },
+    Client,
+};
+use twilly_cli::{prompt_user, prompt_user_selection};
+
+/// Prints the Environment's deployments, newest first, marking the one currently running.
+pub async fn deployment_history(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+    let environment = service.environment(&serverless_environment.sid);
+
+    // Fetched again as the Environment may have been redeployed since it was listed.
+    let current_build_sid = environment
+        .get()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error))
+        .build_sid;
+    let mut deployments = environment
+        .deployments()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+    deployments.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+
+    if deployments.is_empty() {
+        println!("No Deployments found.");
+    }
+    let mut current_marked = false;
+    for deployment in &deployments {
+        let is_current = !current_marked && deployment.build_sid == current_build_sid;
+        current_marked |= is_current;
+        println!(
+            "({}) {} {}{}",
+            deployment.sid,
+            deployment.build_sid.as_deref().unwrap_or("No Build"),
+            deployment.date_created,
+            if is_current { " [current]" } else { "" }
+        );
+    }
+    println!();
+}
+
+/// Deploys the Build running in this Environment to another Environment of the Service.
+pub async fn promote(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+    serverless_environments: &[ServerlessEnvironment],
+) {
+    let Some(build_sid) = &serverless_environment.build_sid else {
+        println!(
+            "Nothing is deployed to {}.",
+            serverless_environment.unique_name
+        );
+        println!();
+        return;
+    };
+
+    let targets: Vec<String> = serverless_environments
+        .iter()
+        .filter(|environment| environment.sid != serverless_environment.sid)
+        .map(|environment| format!("({}) {}", environment.sid, environment.unique_name))
+        .collect();
+    if targets.is_empty() {
+        println!("No other Environments to promote to.");
+        println!();
+        return;
+    }
+
+    let Some(choice) = prompt_user_selection(Select::new("Promote to:", targets)) else {
+        return;
+    };
+    let target = serverless_environments
+        .iter()
+        .find(|environment| environment.sid == choice[1..35])
+        .expect("Could not find Serverless Environment in existing Serverless Environment list");
+
+    if !confirm_build_change(
+        twilio,
+        serverless_service,
+        target.build_sid.as_deref(),
+        build_sid,
+        &target.unique_name,
+    )
+    .await
+    {
+        return;
+    }
+
+    match twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .environment(&target.sid)
+        .redeploy(build_sid)
+        .await
+    {
+        Ok(deployment) => {
+            println!(
+                "Promoted {} to {} ({}).",
+                build_sid, target.unique_name, deployment.sid
+            );
+            println!();
+        }
+        Err(error) => {
+            eprintln!("Unable to promote Build. Action aborted: {}", error);
+            println!();
+        }
+    }
+}
+
+/// Redeploys a Build chosen from the Environment's deployment history.
+pub async fn rollback(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+    let environment = service.environment(&serverless_environment.sid);
+
+    let current_build_sid = environment
+        .get()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error))
+        .build_sid;
+    let mut deployments = environment
+        .deployments()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+    deployments.sort_by(|a, b| b.date_created.cmp(&a.date_created));
+    deployments.retain(|deployment| {
+        deployment.build_sid.is_some() && deployment.build_sid != current_build_sid
+    });
+
+    if deployments.is_empty() {
+        println!("No previous Deployments to roll back to.");
+        println!();
+        return;
+    }
+
+    let Some(choice) = prompt_user_selection(Select::new(
+        "Roll back to:",
+        deployments
+            .iter()
+            .map(|deployment| {
+                format!(
+                    "({}) {} {}",
+                    deployment.sid,
+                    deployment.build_sid.as_deref().unwrap_or_default(),
+                    deployment.date_created
+                )
+            })
+            .collect(),
+    )) else {
+        return;
+    };
+    let build_sid = deployments
+        .iter()
+        .find(|deployment| deployment.sid == choice[1..35])
+        .and_then(|deployment| deployment.build_sid.clone())
+        .expect("Could not find Deployment in existing Deployment list");
+
+    if !confirm_build_change(
+        twilio,
+        serverless_service,
+        current_build_sid.as_deref(),
+        &build_sid,
+        &serverless_environment.unique_name,
+    )
+    .await
+    {
+        return;
+    }
+
+    match environment.redeploy(&build_sid).await {
+        Ok(deployment) => {
+            println!("Rolled back to {} ({}).", build_sid, deployment.sid);
+            println!();
+        }
+        Err(error) => {
+            eprintln!("Unable to roll back. Action aborted: {}", error);
+            println!();
+        }
+    }
+}
+
+/// Shows what will change when moving an Environment from one Build to another and asks
+/// the user to confirm.
+async fn confirm_build_change(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    from_build_sid: Option<&str>,
+    to_build_sid: &str,
+    environment_name: &str,
+) -> bool {
+    if let Some(from_build_sid) = from_build_sid {
+        let diff = twilio
+            .serverless()
+            .service(&serverless_service.sid)
+            .diff_builds(from_build_sid, to_build_sid)
+            .await;
+
+        match diff {
+            Ok(diff) => print_diff(&diff),
+            Err(error) => eprintln!("Unable to compare Builds: {}", error),
+        }
+    }
+
+    let confirm_message = format!("Deploy {} to {}?", to_build_sid, environment_name);
+    let confirm_prompt = Confirm::new(&confirm_message)
+        .with_placeholder("N")
+        .with_default(false);
+
+    prompt_user(confirm_prompt) == Some(true)
+}
+
+pub(crate) fn print_diff(diff: &BuildDiff) {
+    if diff.is_empty() {
+        println!("The Builds are identical.");
+        return;
+    }
+
+    if !diff.functions.is_empty() {
+        println!("Functions:");
+        for change in &diff.functions {
+            println!("  {}", change);
+        }
+    }
+    if !diff.assets.is_empty() {
+        println!("Assets:");
+        for change in &diff.assets {
+            println!("  {}", change);
+        }
+    }
+    if diff.dependencies_changed {
+        println!("Dependencies differ.");
+    }
+}
@@ -1,15 +1,28 @@
 use chrono::{Datelike, Duration};
-use std::{fs::File, io::Write, process};
+use futures::{Stream, StreamExt};
+use regex::Regex;
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process,
+};
 
 use inquire::{validator::Validation, Confirm, MultiSelect, Select, Text};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
 use twilly::{
     serverless::{
-        environments::{logs::Level, ServerlessEnvironment},
+        environments::{
+            logs::{
+                export::{export_file_name, ExportFormat, LogExport},
+                group_by_request, Level, LogFilter, ServerlessLog,
+            },
+            ServerlessEnvironment,
+        },
         services::ServerlessService,
     },
-    Client, ErrorKind,
+    Client, ErrorKind, TwilioError,
 };
 use twilly_cli::{
     get_action_choice_from_user, get_date_from_user, prompt_user, prompt_user_multi_selection,
@@ -23,6 +36,10 @@ pub enum LogsAction {
     GetLog,
     #[strum(to_string = "List Logs")]
     ListLogs,
+    #[strum(to_string = "Tail Logs")]
+    TailLogs,
+    #[strum(to_string = "Export Logs")]
+    ExportLogs,
     Back,
     Exit,
 }
@@ -258,190 +275,144 @@ pub async fn choose_log_action(
                     // Only continue if the user filtered by dates *and* provided both options.
                     // If they didn't then they must of cancelled the operation.
                     if !user_selected_time_range || (start_date.is_some() && end_date.is_some()) {
-                        let filter_function =
-                            Confirm::new("Would you like to filter by a specific function?")
-                                .with_placeholder("N")
-                                .with_default(false);
-
-                        if let Some(filter_decision) = prompt_user(filter_function) {
-                            let mut function_sid: Option<String> = None;
-                            if filter_decision {
-                                let function_sid_prompt =
-                                    Text::new("Please provide a function SID:")
-                                        .with_placeholder("ZH...")
-                                        .with_validator(|val: &str| {
-                                            if val.starts_with("ZH") && val.len() == 34 {
-                                                Ok(Validation::Valid)
-                                            } else {
-                                                Ok(Validation::Invalid(
-                                        "Function SID should be 34 characters in length".into(),
-                                    ))
-                                            }
-                                        });
+                        if let Some(filter) = get_log_filter(twilio, serverless_service).await {
+                            println!("Fetching logs...");
+                            let mut serverless_logs = twilio
+                                .serverless()
+                                .service(&serverless_service.sid)
+                                .environment(&serverless_environment.sid)
+                                .logs()
+                                .query(&filter, start_date, end_date)
+                                .await
+                                .unwrap_or_else(|error| panic!("{}", error));
 
-                                if let Some(user_function_sid) = prompt_user(function_sid_prompt) {
-                                    function_sid = Some(user_function_sid);
-                                }
-                            }
+                            let number_of_logs = serverless_logs.len();
 
-                            let options: Vec<Level> = Level::iter().collect();
-                            let log_level_prompt = MultiSelect::new(
-                                "Select the log levels you would like to view:",
-                                options,
-                            )
-                            .with_default(&[0_usize, 1, 2]);
+                            if number_of_logs == 0 {
+                                println!("No logs found.");
+                                println!();
+                            } else {
+                                println!("Found {} logs.", number_of_logs);
+                                println!();
 
-                            if let Some(log_levels) = prompt_user_multi_selection(log_level_prompt)
-                            {
-                                println!("Fetching logs...");
-                                let mut serverless_logs = twilio
-                                    .serverless()
-                                    .service(&serverless_service.sid)
-                                    .environment(&serverless_environment.sid)
-                                    .logs()
-                                    .list(function_sid, start_date, end_date)
-                                    .await
-                                    .unwrap_or_else(|error| panic!("{}", error));
-
-                                println!("Filtering...");
-                                serverless_logs.retain(|log| log_levels.contains(&log.level));
-
-                                let number_of_logs = serverless_logs.len();
-
-                                if number_of_logs == 0 {
-                                    println!("No logs found.");
-                                    println!();
-                                } else {
-                                    println!("Found {} logs.", number_of_logs);
-                                    println!();
-
-                                    if let Some(output_decision) = get_action_choice_from_user(
-                                        vec![String::from("Write to file"), String::from("View")],
-                                        "Select an output: ",
-                                    ) {
-                                        match output_decision {
-                                            ActionChoice::Back => {
-                                                break;
-                                            }
-                                            ActionChoice::Exit => process::exit(0),
-                                            ActionChoice::Other(choice) => match choice.as_str() {
-                                                "Write to file" => {
-                                                    match File::create(format!(
-                                                        "{}.json",
-                                                        &serverless_environment.sid
-                                                    )) {
-                                                        Ok(mut file_buffer) => {
-                                                            match file_buffer
-                                                                .write_all(
-                                                                    serde_json::to_string_pretty(
-                                                                        &serverless_logs,
-                                                                    )
-                                                                    .unwrap()
-                                                                    .as_bytes(),
-                                                                ) {
-																	Ok(_) => {
-																		println!("Log file created: {}.json", &serverless_environment.sid); 
-																		println!();
-																	},
-																	Err(error) => eprintln!("Failed to fully write to log file. Action aborted: {}", error)
-																}
-                                                        }
-                                                        Err(error) => eprintln!(
-                                                            "Unable to create log file. Action aborted: {}", error
+                                if let Some(output_decision) = get_action_choice_from_user(
+                                    vec![
+                                        String::from("Write to file"),
+                                        String::from("View"),
+                                        String::from("View by request"),
+                                    ],
+                                    "Select an output: ",
+                                ) {
+                                    match output_decision {
+                                        ActionChoice::Back => {
+                                            break;
+                                        }
+                                        ActionChoice::Exit => process::exit(0),
+                                        ActionChoice::Other(choice) => match choice.as_str() {
+                                            "Write to file" => {
+                                                if let Some(destination) =
+                                                    get_export_destination(serverless_environment)
+                                                {
+                                                    write_logs(
+                                                        destination,
+                                                        futures::stream::iter(
+                                                            serverless_logs.iter().cloned().map(Ok),
                                                         ),
-                                                    }
+                                                    )
+                                                    .await;
                                                 }
-                                                "View" => {
-                                                    // Sort date descending (latest first)
-                                                    serverless_logs.sort_by(|a, b| {
-                                                        b.date_created.cmp(&a.date_created)
-                                                    });
-
-                                                    let mut selected_serverless_log_index: Option<
-                                                        usize,
-                                                    > = None;
-                                                    loop {
-                                                        let selected_serverless_log = if let Some(
-                                                            index,
-                                                        ) =
-                                                            selected_serverless_log_index
-                                                        {
-                                                            &mut serverless_logs[index]
-                                                        } else if let Some(action_choice) =
-                                                            get_action_choice_from_user(
-                                                                serverless_logs
-                                                                    .iter()
-                                                                    .map(|log| {
-                                                                        format!(
-                                                                            "({}) {} - {}",
-                                                                            log.sid,
-                                                                            log.date_created,
-                                                                            log.message
-                                                                        )
-                                                                    })
-                                                                    .collect::<Vec<String>>(),
-                                                                "Choose a Serverless Log: ",
-                                                            )
-                                                        {
-                                                            match action_choice {
-                                                                ActionChoice::Back => {
-                                                                    break;
-                                                                }
-                                                                ActionChoice::Exit => {
-                                                                    process::exit(0)
-                                                                }
-                                                                ActionChoice::Other(choice) => {
-                                                                    let serverless_log_position = serverless_logs
+                                            }
+                                            "View" => {
+                                                // Sort date descending (latest first)
+                                                serverless_logs.sort_by(|a, b| {
+                                                    b.date_created.cmp(&a.date_created)
+                                                });
+
+                                                let mut selected_serverless_log_index: Option<
+                                                    usize,
+                                                > = None;
+                                                loop {
+                                                    let selected_serverless_log = if let Some(
+                                                        index,
+                                                    ) =
+                                                        selected_serverless_log_index
+                                                    {
+                                                        &mut serverless_logs[index]
+                                                    } else if let Some(action_choice) =
+                                                        get_action_choice_from_user(
+                                                            serverless_logs
+                                                                .iter()
+                                                                .map(|log| {
+                                                                    format!(
+                                                                        "({}) {} - {}",
+                                                                        log.sid,
+                                                                        log.date_created,
+                                                                        log.message
+                                                                    )
+                                                                })
+                                                                .collect::<Vec<String>>(),
+                                                            "Choose a Serverless Log: ",
+                                                        )
+                                                    {
+                                                        match action_choice {
+                                                            ActionChoice::Back => {
+                                                                break;
+                                                            }
+                                                            ActionChoice::Exit => process::exit(0),
+                                                            ActionChoice::Other(choice) => {
+                                                                let serverless_log_position = serverless_logs
 									.iter()
 									.position(|list| list.sid == choice[1..35])
 									.expect("Could not find Serverless Log in existing Serverless Log list");
 
-                                                                    selected_serverless_log_index =
-                                                                        Some(
-                                                                            serverless_log_position,
-                                                                        );
-                                                                    &mut serverless_logs
-                                                                        [serverless_log_position]
-                                                                }
+                                                                selected_serverless_log_index =
+                                                                    Some(serverless_log_position);
+                                                                &mut serverless_logs
+                                                                    [serverless_log_position]
+                                                            }
+                                                        }
+                                                    } else {
+                                                        break;
+                                                    };
+
+                                                    let options: Vec<LogAction> =
+                                                        LogAction::iter().collect();
+                                                    let action_selection_prompt =
+                                                        Select::new("Select an action:", options);
+                                                    if let Some(action) = prompt_user_selection(
+                                                        action_selection_prompt,
+                                                    ) {
+                                                        match action {
+                                                            LogAction::ListDetails => {
+                                                                println!(
+                                                                    "{:#?}",
+                                                                    selected_serverless_log
+                                                                );
+                                                                println!();
                                                             }
-                                                        } else {
-                                                            break;
-                                                        };
-
-                                                        let options: Vec<LogAction> =
-                                                            LogAction::iter().collect();
-                                                        let action_selection_prompt = Select::new(
-                                                            "Select an action:",
-                                                            options,
-                                                        );
-                                                        if let Some(action) = prompt_user_selection(
-                                                            action_selection_prompt,
-                                                        ) {
-                                                            match action {
-                                                                LogAction::ListDetails => {
-                                                                    println!(
-                                                                        "{:#?}",
-                                                                        selected_serverless_log
-                                                                    );
-                                                                    println!();
-                                                                }
-                                                                LogAction::Back => {
-                                                                    break;
-                                                                }
-                                                                LogAction::Exit => process::exit(0),
+                                                            LogAction::Back => {
+                                                                break;
                                                             }
+                                                            LogAction::Exit => process::exit(0),
                                                         }
                                                     }
                                                 }
-                                                _ => println!("Unknown action '{}'", choice),
-                                            },
-                                        }
+                                            }
+                                            "View by request" => print_requests(serverless_logs),
+                                            _ => println!("Unknown action '{}'", choice),
+                                        },
                                     }
                                 }
                             }
                         }
                     }
                 }
+                LogsAction::TailLogs => {
+                    tail_logs(twilio, serverless_service, serverless_environment).await
+                }
+                LogsAction::ExportLogs => {
+                    export_logs(twilio, serverless_service, serverless_environment).await
+                }
                 LogsAction::Back => {
                     break;
                 }
@@ -450,3 +421,273 @@ pub async fn choose_log_action(
         }
     }
 }
+
+/// Asks the user how to narrow down the logs. Functions are chosen by friendly name.
+async fn get_log_filter(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+) -> Option<LogFilter> {
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+
+    let function_names: Vec<String> = service
+        .functions()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error))
+        .into_iter()
+        .map(|function| function.friendly_name)
+        .collect();
+    let function_sids = if function_names.is_empty() {
+        Vec::new()
+    } else {
+        let functions_prompt = MultiSelect::new(
+            "Select the functions to view (none for all):",
+            function_names,
+        );
+        let names = prompt_user_multi_selection(functions_prompt)?;
+        match service.functions().sids_by_name(&names).await {
+            Ok(sids) => sids,
+            Err(error) => {
+                eprintln!("Unable to find functions. Action aborted: {}", error);
+                println!();
+                return None;
+            }
+        }
+    };
+
+    let log_level_prompt = MultiSelect::new(
+        "Select the log levels you would like to view:",
+        Level::iter().collect(),
+    )
+    .with_default(&[0_usize, 1, 2]);
+    let levels = prompt_user_multi_selection(log_level_prompt)?;
+
+    let request_sid_prompt = Text::new("Filter by request SID (optional):")
+        .with_placeholder("RQ...")
+        .with_validator(|val: &str| {
+            if val.is_empty() || (val.starts_with("RQ") && val.len() == 34) {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "Request SID should start with RQ and be 34 characters in length".into(),
+                ))
+            }
+        });
+    let request_sid = prompt_user(request_sid_prompt)?;
+
+    let message_prompt = Text::new("Filter messages by regular expression (optional):")
+        .with_validator(|val: &str| match Regex::new(val) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(error) => Ok(Validation::Invalid(error.to_string().into())),
+        });
+    let message = prompt_user(message_prompt)?;
+
+    Some(LogFilter {
+        levels,
+        function_sids,
+        request_sid: (!request_sid.is_empty()).then_some(request_sid),
+        message: (!message.is_empty())
+            .then(|| Regex::new(&message).expect("Message filter was validated")),
+    })
+}
+
+/// Where and how logs should be written.
+struct ExportDestination {
+    path: PathBuf,
+    format: ExportFormat,
+    compress: bool,
+}
+
+/// Asks the user for the format and directory of an export. The file is named after the
+/// Environment and the current time so earlier exports are not overwritten.
+fn get_export_destination(
+    serverless_environment: &ServerlessEnvironment,
+) -> Option<ExportDestination> {
+    let format_prompt = Select::new("Select an export format:", ExportFormat::iter().collect());
+    let format = prompt_user_selection(format_prompt)?;
+
+    let compress_prompt = Confirm::new("Compress with gzip?")
+        .with_placeholder("N")
+        .with_default(false);
+    let compress = prompt_user(compress_prompt)?;
+
+    let directory_prompt = Text::new("Enter the directory to write to:")
+        .with_default(".")
+        .with_validator(|val: &str| match Path::new(val).is_dir() {
+            true => Ok(Validation::Valid),
+            false => Ok(Validation::Invalid("Directory does not exist".into())),
+        });
+    let directory = prompt_user(directory_prompt)?;
+
+    Some(ExportDestination {
+        path: Path::new(&directory).join(export_file_name(
+            &serverless_environment.sid,
+            format,
+            compress,
+            chrono::Utc::now(),
+        )),
+        format,
+        compress,
+    })
+}
+
+/// Writes the logs to the destination as they arrive from the stream.
+async fn write_logs<S>(destination: ExportDestination, mut logs: S)
+where
+    S: Stream<Item = Result<ServerlessLog, TwilioError>> + Unpin,
+{
+    let mut export = match File::create(&destination.path)
+        .map_err(|error| error.to_string())
+        .and_then(|file| {
+            LogExport::new(
+                BufWriter::new(file),
+                destination.format,
+                destination.compress,
+            )
+            .map_err(|error| error.to_string())
+        }) {
+        Ok(export) => export,
+        Err(error) => {
+            eprintln!("Unable to create log file. Action aborted: {}", error);
+            println!();
+            return;
+        }
+    };
+
+    while let Some(log) = logs.next().await {
+        if let Err(error) = log.and_then(|log| export.write(&log)) {
+            eprintln!(
+                "Failed to fully write to log file. Action aborted after {} logs: {}",
+                export.written(),
+                error
+            );
+            println!();
+            return;
+        }
+    }
+
+    let written = export.written();
+    match export.finish() {
+        Ok(_) => {
+            println!("Wrote {} logs to {}", written, destination.path.display());
+            println!();
+        }
+        Err(error) => {
+            eprintln!(
+                "Failed to fully write to log file. Action aborted: {}",
+                error
+            );
+            println!();
+        }
+    }
+}
+
+/// Exports logs between two dates straight to a file, fetching a page at a time.
+async fn export_logs(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let utc_now = chrono::Utc::now();
+    let today = utc_now.date_naive();
+
+    let Some(start_day) = get_date_from_user(
+        "Choose a start date:",
+        Some(DateRange {
+            minimum_date: today - Duration::days(30),
+            maximum_date: today,
+        }),
+    ) else {
+        return;
+    };
+    let Some(end_day) = get_date_from_user(
+        "Choose an end date:",
+        Some(DateRange {
+            minimum_date: start_day,
+            maximum_date: today,
+        }),
+    ) else {
+        return;
+    };
+
+    let start_date = start_day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let end_date = match end_day == today {
+        true => utc_now,
+        false => end_day.and_hms_opt(23, 59, 59).unwrap().and_utc(),
+    };
+
+    let Some(filter) = get_log_filter(twilio, serverless_service).await else {
+        return;
+    };
+    let Some(destination) = get_export_destination(serverless_environment) else {
+        return;
+    };
+
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+    let environment = service.environment(&serverless_environment.sid);
+    let logs = environment.logs();
+
+    println!("Exporting logs...");
+    write_logs(
+        destination,
+        logs.stream(filter, Some(start_date), Some(end_date)),
+    )
+    .await;
+}
+
+/// Prints logs grouped by the request that wrote them.
+fn print_requests(serverless_logs: Vec<ServerlessLog>) {
+    for request in group_by_request(serverless_logs) {
+        println!("Request {} ({})", request.request_sid, request.function_sid);
+        for log in &request.logs {
+            print_log(log);
+        }
+        println!();
+    }
+}
+
+/// Streams new logs to the terminal until the user presses Ctrl+C.
+async fn tail_logs(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let serverless = twilio.serverless();
+    let service = serverless.service(&serverless_service.sid);
+
+    let Some(filter) = get_log_filter(twilio, serverless_service).await else {
+        return;
+    };
+
+    let environment = service.environment(&serverless_environment.sid);
+    let logs = environment.logs();
+    let mut tail = logs.tail(filter, std::time::Duration::from_secs(2));
+
+    println!("Tailing logs. Press Ctrl+C to stop.");
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            log = tail.next() => match log {
+                Some(Ok(log)) => print_log(&log),
+                Some(Err(error)) => eprintln!("Unable to fetch logs, retrying: {}", error),
+                None => break,
+            },
+        }
+    }
+    println!();
+}
+
+/// Prints a log on one line with its level colour-coded.
+pub(crate) fn print_log(log: &ServerlessLog) {
+    let colour = match log.level {
+        Level::Info => "\x1b[36m",
+        Level::Warn => "\x1b[33m",
+        Level::Error => "\x1b[31m",
+    };
+    println!(
+        "{} {}{:<5}\x1b[0m {} {}",
+        log.date_created, colour, log.level, log.function_sid, log.message
+    );
+}
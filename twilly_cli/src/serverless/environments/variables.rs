@@ -0,0 +1,251 @@
+use std::{fs, process};
+
+use inquire::{validator::Validation, Confirm, Select, Text};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{
+    serverless::{
+        environments::{
+            variables::{parse_env_file, CreateParams, ServerlessVariable, UpdateParams},
+            ServerlessEnvironment,
+        },
+        services::ServerlessService,
+    },
+    Client,
+};
+use twilly_cli::{prompt_user, prompt_user_selection};
+
+/// Actions general to Variables.
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum VariablesAction {
+    #[strum(to_string = "List Variables")]
+    ListVariables,
+    #[strum(to_string = "Add Variable")]
+    AddVariable,
+    #[strum(to_string = "Edit Variable")]
+    EditVariable,
+    #[strum(to_string = "Delete Variable")]
+    DeleteVariable,
+    #[strum(to_string = "Apply .env File")]
+    ApplyEnvFile,
+    Back,
+    Exit,
+}
+
+pub async fn choose_variable_action(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) {
+    let options: Vec<VariablesAction> = VariablesAction::iter().collect();
+
+    loop {
+        let resource_selection_prompt = Select::new("Select an action:", options.clone());
+        if let Some(resource) = prompt_user_selection(resource_selection_prompt) {
+            let serverless = twilio.serverless();
+            let service = serverless.service(&serverless_service.sid);
+            let environment = service.environment(&serverless_environment.sid);
+
+            match resource {
+                VariablesAction::ListVariables => {
+                    let variables = environment
+                        .variables()
+                        .list()
+                        .await
+                        .unwrap_or_else(|error| panic!("{}", error));
+
+                    if variables.is_empty() {
+                        println!("No Variables found.");
+                    }
+                    for variable in &variables {
+                        println!("{}={}", variable.key, variable.value);
+                    }
+                    println!();
+                }
+                VariablesAction::AddVariable => {
+                    let key_prompt =
+                        Text::new("Enter the Variable key:").with_validator(|val: &str| {
+                            match val.is_empty() || val.contains('=') {
+                                true => Ok(Validation::Invalid(
+                                    "Key cannot be empty or contain '='".into(),
+                                )),
+                                false => Ok(Validation::Valid),
+                            }
+                        });
+
+                    if let Some(key) = prompt_user(key_prompt) {
+                        if let Some(value) = prompt_user(Text::new("Enter the Variable value:")) {
+                            match environment
+                                .variables()
+                                .create(CreateParams { key, value })
+                                .await
+                            {
+                                Ok(variable) => {
+                                    println!("Variable {} added.", variable.key);
+                                    println!();
+                                }
+                                Err(error) => {
+                                    eprintln!("Unable to add Variable. Action aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                VariablesAction::EditVariable => {
+                    if let Some(variable) =
+                        choose_variable(twilio, serverless_service, serverless_environment).await
+                    {
+                        let value_prompt =
+                            Text::new("Enter the new value:").with_initial_value(&variable.value);
+
+                        if let Some(value) = prompt_user(value_prompt) {
+                            match environment
+                                .variable(&variable.sid)
+                                .update(UpdateParams {
+                                    key: None,
+                                    value: Some(value),
+                                })
+                                .await
+                            {
+                                Ok(variable) => {
+                                    println!("Variable {} updated.", variable.key);
+                                    println!();
+                                }
+                                Err(error) => {
+                                    eprintln!(
+                                        "Unable to update Variable. Action aborted: {}",
+                                        error
+                                    );
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                VariablesAction::DeleteVariable => {
+                    if let Some(variable) =
+                        choose_variable(twilio, serverless_service, serverless_environment).await
+                    {
+                        let confirm_message =
+                            format!("Are you sure you wish to delete {}?", variable.key);
+                        let confirm_prompt = Confirm::new(&confirm_message)
+                            .with_placeholder("N")
+                            .with_default(false);
+
+                        if prompt_user(confirm_prompt) == Some(true) {
+                            environment
+                                .variable(&variable.sid)
+                                .delete()
+                                .await
+                                .unwrap_or_else(|error| panic!("{}", error));
+                            println!("Variable deleted.");
+                            println!();
+                        }
+                    }
+                }
+                VariablesAction::ApplyEnvFile => {
+                    let path_prompt =
+                        Text::new("Enter the path of the .env file:").with_default(".env");
+
+                    if let Some(path) = prompt_user(path_prompt) {
+                        let desired = match fs::read_to_string(&path)
+                            .map_err(|error| error.to_string())
+                            .and_then(|contents| {
+                                parse_env_file(&contents).map_err(|error| error.to_string())
+                            }) {
+                            Ok(desired) => desired,
+                            Err(error) => {
+                                eprintln!("Unable to read .env file. Action aborted: {}", error);
+                                println!();
+                                continue;
+                            }
+                        };
+
+                        let remove_prompt =
+                            Confirm::new("Remove Variables that are not in the file?")
+                                .with_placeholder("N")
+                                .with_default(false);
+
+                        if let Some(remove_missing) = prompt_user(remove_prompt) {
+                            let changes = environment
+                                .variables()
+                                .diff(&desired, remove_missing)
+                                .await
+                                .unwrap_or_else(|error| panic!("{}", error));
+
+                            if changes.is_empty() {
+                                println!("Variables already match the file.");
+                                println!();
+                                continue;
+                            }
+
+                            for change in &changes {
+                                println!("{}", change);
+                            }
+
+                            let apply_message = format!("Apply {} changes?", changes.len());
+                            let apply_prompt = Confirm::new(&apply_message)
+                                .with_placeholder("N")
+                                .with_default(false);
+
+                            if prompt_user(apply_prompt) == Some(true) {
+                                match environment.variables().apply(&changes).await {
+                                    Ok(_) => {
+                                        println!("Variables updated. Changes take effect on the next deployment.");
+                                        println!();
+                                    }
+                                    Err(error) => {
+                                        eprintln!(
+                                            "Unable to apply all changes. Action aborted: {}",
+                                            error
+                                        );
+                                        println!();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                VariablesAction::Back => break,
+                VariablesAction::Exit => process::exit(0),
+            }
+        }
+    }
+}
+
+/// Lists the Environment's Variables and asks the user to choose one.
+async fn choose_variable(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_environment: &ServerlessEnvironment,
+) -> Option<ServerlessVariable> {
+    let mut variables = twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .environment(&serverless_environment.sid)
+        .variables()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    if variables.is_empty() {
+        println!("No Variables found.");
+        println!();
+        return None;
+    }
+
+    let choice = prompt_user_selection(Select::new(
+        "Choose a Variable:",
+        variables
+            .iter()
+            .map(|variable| format!("({}) {}", variable.sid, variable.key))
+            .collect(),
+    ))?;
+    let position = variables
+        .iter()
+        .position(|variable| variable.sid == choice[1..35])
+        .expect("Could not find Variable in existing Variable list");
+
+    Some(variables.remove(position))
+}
@@ -0,0 +1,282 @@
+use std::{fs, process};
+
+use inquire::{validator::Validation, Confirm, Select, Text};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use twilly::{
+    serverless::{
+        functions::{
+            versions::{CreateParams, FunctionVersion},
+            CreateOrUpdateParams, ServerlessFunction,
+        },
+        services::ServerlessService,
+        Visibility,
+    },
+    Client,
+};
+use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection, ActionChoice};
+
+#[derive(Debug, Clone, Display, EnumIter, EnumString)]
+pub enum Action {
+    #[strum(to_string = "List Details")]
+    ListDetails,
+    #[strum(to_string = "List Versions")]
+    ListVersions,
+    #[strum(to_string = "View Version Content")]
+    ViewVersionContent,
+    #[strum(to_string = "Upload Version")]
+    UploadVersion,
+    Delete,
+    Back,
+    Exit,
+}
+
+pub async fn choose_function_action(twilio: &Client, serverless_service: &ServerlessService) {
+    let mut serverless_functions = twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .functions()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    println!("Found {} Functions.", serverless_functions.len());
+
+    let mut selected_serverless_function_index: Option<usize> = None;
+    loop {
+        let selected_serverless_function = if let Some(index) = selected_serverless_function_index {
+            &mut serverless_functions[index]
+        } else {
+            let mut existing_functions = serverless_functions
+                .iter()
+                .map(|function| format!("({}) {}", function.sid, function.friendly_name))
+                .collect::<Vec<String>>();
+            existing_functions.push("Create Function".into());
+
+            if let Some(action_choice) =
+                get_action_choice_from_user(existing_functions, "Choose a Function: ")
+            {
+                match action_choice {
+                    ActionChoice::Back => {
+                        break;
+                    }
+                    ActionChoice::Exit => process::exit(0),
+                    ActionChoice::Other(choice) => {
+                        if choice == "Create Function" {
+                            let friendly_name_prompt = Text::new("Enter a friendly name:")
+                                .with_validator(|val: &str| match val.is_empty() {
+                                    true => Ok(Validation::Invalid(
+                                        "Friendly name cannot be empty".into(),
+                                    )),
+                                    false => Ok(Validation::Valid),
+                                });
+
+                            if let Some(friendly_name) = prompt_user(friendly_name_prompt) {
+                                let serverless_function = twilio
+                                    .serverless()
+                                    .service(&serverless_service.sid)
+                                    .functions()
+                                    .create(CreateOrUpdateParams { friendly_name })
+                                    .await
+                                    .unwrap_or_else(|error| panic!("{}", error));
+                                serverless_functions.push(serverless_function);
+                                selected_serverless_function_index =
+                                    Some(serverless_functions.len() - 1);
+                                &mut serverless_functions
+                                    [selected_serverless_function_index.unwrap()]
+                            } else {
+                                break;
+                            }
+                        } else {
+                            let serverless_function_position = serverless_functions
+                                .iter()
+                                .position(|function| function.sid == choice[1..35])
+                                .expect("Could not find Function in existing Function list");
+
+                            selected_serverless_function_index = Some(serverless_function_position);
+                            &mut serverless_functions[serverless_function_position]
+                        }
+                    }
+                }
+            } else {
+                break;
+            }
+        };
+
+        let options: Vec<Action> = Action::iter().collect();
+        let resource_selection_prompt = Select::new("Select an action:", options.clone());
+        if let Some(resource) = prompt_user_selection(resource_selection_prompt) {
+            let serverless = twilio.serverless();
+            let service = serverless.service(&serverless_service.sid);
+            let function = service.function(&selected_serverless_function.sid);
+
+            match resource {
+                Action::ListDetails => {
+                    println!("{:#?}", selected_serverless_function);
+                    println!();
+                }
+                Action::ListVersions => {
+                    let versions = function
+                        .versions()
+                        .list()
+                        .await
+                        .unwrap_or_else(|error| panic!("{}", error));
+
+                    if versions.is_empty() {
+                        println!("No Versions found.");
+                    }
+                    for version in &versions {
+                        println!(
+                            "({}) {} [{}] {}",
+                            version.sid, version.path, version.visibility, version.date_created
+                        );
+                    }
+                    println!();
+                }
+                Action::ViewVersionContent => {
+                    if let Some(version) =
+                        choose_version(twilio, serverless_service, selected_serverless_function)
+                            .await
+                    {
+                        let content = function
+                            .version(&version.sid)
+                            .content()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+                        println!("{}", content.content);
+                        println!();
+                    }
+                }
+                Action::UploadVersion => {
+                    upload_version(twilio, serverless_service, selected_serverless_function).await
+                }
+                Action::Delete => {
+                    let confirm_prompt =
+                        Confirm::new("Are you sure you wish to delete the Function?")
+                            .with_placeholder("N")
+                            .with_default(false);
+                    if prompt_user(confirm_prompt) == Some(true) {
+                        println!("Deleting Function...");
+                        function
+                            .delete()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+                        serverless_functions.remove(
+                            selected_serverless_function_index
+                                .expect("Could not find Function in existing Function list"),
+                        );
+                        println!("Function deleted.");
+                        println!();
+                        break;
+                    }
+                }
+                Action::Back => {
+                    break;
+                }
+                Action::Exit => process::exit(0),
+            }
+        }
+    }
+}
+
+/// Reads a local file and uploads it as a new Version of the Function.
+async fn upload_version(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_function: &ServerlessFunction,
+) {
+    let Some(file_path) = prompt_user(Text::new("Enter the path of the file to upload:")) else {
+        return;
+    };
+    let content = match fs::read(&file_path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Unable to read file. Action aborted: {}", error);
+            println!();
+            return;
+        }
+    };
+
+    let path_prompt = Text::new("Enter the URL path (e.g. /hello):").with_validator(|val: &str| {
+        match val.starts_with('/') {
+            true => Ok(Validation::Valid),
+            false => Ok(Validation::Invalid("Path must begin with '/'".into())),
+        }
+    });
+    let Some(path) = prompt_user(path_prompt) else {
+        return;
+    };
+
+    let visibility_prompt = Select::new(
+        "Choose the visibility:",
+        Visibility::iter().collect::<Vec<Visibility>>(),
+    )
+    .with_starting_cursor(1);
+    let Some(visibility) = prompt_user_selection(visibility_prompt) else {
+        return;
+    };
+
+    match twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .function(&serverless_function.sid)
+        .versions()
+        .create(CreateParams {
+            path,
+            visibility,
+            content,
+            content_type: String::from("application/javascript"),
+        })
+        .await
+    {
+        Ok(version) => {
+            println!("Version {} uploaded.", version.sid);
+            println!();
+        }
+        Err(error) => {
+            eprintln!("Unable to upload Version. Action aborted: {}", error);
+            println!();
+        }
+    }
+}
+
+/// Lists the Function's Versions and asks the user to choose one.
+async fn choose_version(
+    twilio: &Client,
+    serverless_service: &ServerlessService,
+    serverless_function: &ServerlessFunction,
+) -> Option<FunctionVersion> {
+    let mut versions = twilio
+        .serverless()
+        .service(&serverless_service.sid)
+        .function(&serverless_function.sid)
+        .versions()
+        .list()
+        .await
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    if versions.is_empty() {
+        println!("No Versions found.");
+        println!();
+        return None;
+    }
+
+    let choice = prompt_user_selection(Select::new(
+        "Choose a Version:",
+        versions
+            .iter()
+            .map(|version| {
+                format!(
+                    "({}) {} {}",
+                    version.sid, version.path, version.date_created
+                )
+            })
+            .collect(),
+    ))?;
+    let position = versions
+        .iter()
+        .position(|version| version.sid == choice[1..35])
+        .expect("Could not find Version in existing Version list");
+
+    Some(versions.remove(position))
+}
@@ -0,0 +1,128 @@
+use std::{fs, path::Path, time::Duration};
+
+use inquire::{validator::Validation, Text};
+use twilly::{
+    serverless::{
+        project::{DeployEvent, DeployOptions, Project, ProjectManifest},
+        services::ServerlessService,
+    },
+    Client,
+};
+use twilly_cli::prompt_user;
+
+/// Name of the file within the project recording what was last uploaded.
+pub(crate) const MANIFEST_FILE: &str = ".twilly-deploy.json";
+
+/// Deploys a local Serverless project, returning the Service it was deployed to.
+pub async fn deploy_project(twilio: &Client) -> Option<ServerlessService> {
+    let directory_prompt = Text::new("Enter the project directory:")
+        .with_default(".")
+        .with_validator(|val: &str| match Path::new(val).is_dir() {
+            true => Ok(Validation::Valid),
+            false => Ok(Validation::Invalid("Directory not found".into())),
+        });
+    let directory = prompt_user(directory_prompt)?;
+    let directory = Path::new(&directory);
+
+    let project = match Project::load(directory) {
+        Ok(project) => project,
+        Err(error) => {
+            eprintln!("Unable to read project. Action aborted: {}", error);
+            println!();
+            return None;
+        }
+    };
+    println!(
+        "Found {} Functions, {} Assets, {} dependencies and {} variables.",
+        project.functions.len(),
+        project.assets.len(),
+        project.dependencies.len(),
+        project.variables.len()
+    );
+
+    let mut service_prompt =
+        Text::new("Enter the Service unique name:").with_validator(|val: &str| {
+            match val.is_empty() {
+                true => Ok(Validation::Invalid("Service name cannot be empty".into())),
+                false => Ok(Validation::Valid),
+            }
+        });
+    if let Some(name) = &project.name {
+        service_prompt = service_prompt.with_default(name);
+    }
+    let service_name = prompt_user(service_prompt)?;
+
+    let environment_prompt = Text::new("Enter the Environment unique name:").with_default("dev");
+    let environment_name = prompt_user(environment_prompt)?;
+    let domain_suffix_prompt =
+        Text::new("Enter a domain suffix (empty for none):").with_initial_value(&environment_name);
+    let domain_suffix = prompt_user(domain_suffix_prompt)?;
+
+    let manifest_path = directory.join(MANIFEST_FILE);
+    let previous: ProjectManifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+
+    let result = twilio
+        .serverless()
+        .deploy(
+            &project,
+            DeployOptions {
+                service_name,
+                environment_name,
+                domain_suffix: Some(domain_suffix).filter(|suffix| !suffix.is_empty()),
+                previous,
+                build_timeout: Duration::from_secs(300),
+            },
+            print_event,
+        )
+        .await;
+
+    match result {
+        Ok(outcome) => {
+            let manifest = serde_json::to_string_pretty(&outcome.manifest)
+                .expect("Manifest should serialize to JSON");
+            if let Err(error) = fs::write(&manifest_path, manifest) {
+                eprintln!("Unable to save the deploy manifest: {}", error);
+            }
+
+            println!("Deployed to {}:", outcome.environment.domain_name);
+            for url in &outcome.urls {
+                println!("  {}", url);
+            }
+            println!();
+            Some(outcome.service)
+        }
+        Err(error) => {
+            eprintln!("Unable to deploy project. Action aborted: {}", error);
+            println!();
+            None
+        }
+    }
+}
+
+pub(crate) fn print_event(event: DeployEvent) {
+    match event {
+        DeployEvent::ServiceReady { sid, created } => match created {
+            true => println!("Created Service {}", sid),
+            false => println!("Using Service {}", sid),
+        },
+        DeployEvent::EnvironmentReady { sid, created } => match created {
+            true => println!("Created Environment {}", sid),
+            false => println!("Using Environment {}", sid),
+        },
+        DeployEvent::Uploaded {
+            source,
+            version_sid,
+        } => println!("  Uploaded {} ({})", source, version_sid),
+        DeployEvent::Unchanged { source } => println!("  Unchanged {}", source),
+        DeployEvent::Building { status, elapsed } => {
+            println!("Build {} ({}s)", status, elapsed.as_secs())
+        }
+        DeployEvent::VariablesUpdated { changes } => println!("Updated {} variables", changes),
+        DeployEvent::Deployed { deployment_sid } => {
+            println!("Created Deployment {}", deployment_sid)
+        }
+    }
+}
@@ -4,13 +4,37 @@ mod lists;
 mod mapitems;
 mod maps;
 
-use std::process;
+use std::{
+    fs::File,
+    io::{self, BufReader, Write},
+    process,
+    str::FromStr,
+    time::Duration,
+};
 
-use inquire::{Confirm, Select, Text};
+use inquire::{validator::Validation, Confirm, Password, PasswordDisplayMode, Select, Text};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
-use twilly::{sync::services::CreateOrUpdateParams, Client};
-use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection, ActionChoice};
+use twilly::{
+    jwt::{self, AccessToken, SyncGrant},
+    sync::{
+        copy::CopyParams,
+        expiry::{ExpiringResource, MAX_TTL},
+        services::{CreateOrUpdateParams, SyncService},
+        snapshot::{RestoreParams, RestoreReport, Snapshot},
+        transfer::{
+            read_records, write_records, ConflictPolicy, Format, ImportParams, ImportProgress,
+            ImportReport,
+        },
+    },
+    Client,
+};
+use twilly_cli::{
+    get_action_choice_from_user, prompt_user, prompt_user_selection, request_credentials,
+    ActionChoice,
+};
 
 #[derive(Debug, Clone, Display, EnumIter, EnumString)]
 pub enum Action {
@@ -22,6 +46,14 @@ pub enum Action {
     List,
     #[strum(to_string = "List Details")]
     ListDetails,
+    #[strum(to_string = "Take Snapshot")]
+    TakeSnapshot,
+    #[strum(to_string = "Restore Snapshot")]
+    RestoreSnapshot,
+    #[strum(to_string = "Expiry Report")]
+    ExpiryReport,
+    #[strum(to_string = "Mint Access Token")]
+    MintAccessToken,
     Delete,
     Back,
     Exit,
@@ -58,6 +90,7 @@ pub async fn choose_sync_resource(twilio: &Client) {
                 })
                 .collect::<Vec<String>>();
             existing_services.push("Create Sync Service".into());
+            existing_services.push("Restore Sync Service from Snapshot".into());
             if let Some(action_choice) =
                 get_action_choice_from_user(existing_services, "Choose a Sync Service: ")
             {
@@ -102,6 +135,15 @@ pub async fn choose_sync_resource(twilio: &Client) {
                             } else {
                                 break;
                             }
+                        } else if choice == "Restore Sync Service from Snapshot" {
+                            match restore_new_service(twilio).await {
+                                Some(sync_service) => {
+                                    sync_services.push(sync_service);
+                                    selected_sync_service_index = Some(sync_services.len() - 1);
+                                    &mut sync_services[selected_sync_service_index.unwrap()]
+                                }
+                                None => continue,
+                            }
                         } else {
                             let sync_service_position = sync_services
                                 .iter()
@@ -133,6 +175,68 @@ pub async fn choose_sync_resource(twilio: &Client) {
                     println!("{:#?}", selected_sync_service);
                     println!();
                 }
+                Action::TakeSnapshot => {
+                    let default_path = format!(
+                        "{}-{}.json",
+                        selected_sync_service.sid,
+                        chrono::Utc::now().format("%Y%m%d%H%M%S")
+                    );
+                    let path_prompt = Text::new("Enter a file path:").with_default(&default_path);
+
+                    if let Some(path) = prompt_user(path_prompt) {
+                        println!("Taking snapshot...");
+                        let snapshot = twilio
+                            .sync()
+                            .service(&selected_sync_service.sid)
+                            .snapshot()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+
+                        match File::create(&path) {
+                            Ok(file) => match snapshot.write(file) {
+                                Ok(_) => {
+                                    println!(
+                                        "Snapshot written to {}: {} Documents, {} Lists, {} Maps",
+                                        path,
+                                        snapshot.objects.documents.len(),
+                                        snapshot.objects.lists.len(),
+                                        snapshot.objects.maps.len()
+                                    );
+                                    println!();
+                                }
+                                Err(error) => eprintln!(
+                                    "Failed to fully write to snapshot file. Action aborted: {}",
+                                    error
+                                ),
+                            },
+                            Err(error) => eprintln!(
+                                "Unable to create snapshot file. Action aborted: {}",
+                                error
+                            ),
+                        }
+                    }
+                }
+                Action::RestoreSnapshot => {
+                    if let Some(snapshot) = read_snapshot_file() {
+                        if let Some(restore_params) = get_restore_params() {
+                            println!("Restoring snapshot...");
+                            match twilio
+                                .sync()
+                                .service(&selected_sync_service.sid)
+                                .restore(&snapshot, restore_params)
+                                .await
+                            {
+                                Ok(report) => print_restore_report(&report, restore_params.dry_run),
+                                Err(error) => {
+                                    eprintln!("Restore aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::ExpiryReport => expiry_report(twilio, selected_sync_service).await,
+                Action::MintAccessToken => mint_access_token(twilio, selected_sync_service),
                 Action::Delete => {
                     let confirm_prompt =
                         Confirm::new("Are you sure you wish to delete the Sync Service?")
@@ -165,3 +269,533 @@ pub async fn choose_sync_resource(twilio: &Client) {
         }
     }
 }
+
+/// Reports the Documents, Map Items and List Items expiring within a window chosen by
+/// the user and offers to extend their TTL in bulk.
+async fn expiry_report(twilio: &Client, sync_service: &SyncService) {
+    let window_prompt = Text::new("Report resources expiring within how many hours?")
+        .with_default("24")
+        .with_validator(|val: &str| match val.parse::<u64>() {
+            Ok(hours) if hours > 0 => Ok(Validation::Valid),
+            _ => Ok(Validation::Invalid(
+                "Enter a whole number greater than 0".into(),
+            )),
+        });
+
+    if let Some(hours) = prompt_user(window_prompt) {
+        let hours = hours
+            .parse::<u64>()
+            .expect("Window should have been validated as a number");
+
+        println!("Scanning Sync Service...");
+        let sync = twilio.sync();
+        let service = sync.service(&sync_service.sid);
+        let expiring = service
+            .expiring(Duration::from_secs(hours * 3600))
+            .await
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        if expiring.is_empty() {
+            println!("Nothing expires within {} hours.", hours);
+            println!();
+            return;
+        }
+
+        println!(
+            "Found {} resources expiring within {} hours:",
+            expiring.len(),
+            hours
+        );
+        for expiring in &expiring {
+            println!(
+                "  {} - {}",
+                expiring.date_expires.format("%Y-%m-%d %H:%M:%S UTC"),
+                expiring.resource
+            );
+        }
+        println!();
+
+        let extend_prompt = Confirm::new("Would you like to extend their TTL?")
+            .with_placeholder("N")
+            .with_default(false);
+        if prompt_user(extend_prompt) != Some(true) {
+            return;
+        }
+
+        let max_hours = MAX_TTL.as_secs() / 3600;
+        let ttl_prompt = Text::new("Expire them in how many hours from now?")
+            .with_default("24")
+            .with_validator(move |val: &str| match val.parse::<u64>() {
+                Ok(hours) if hours > 0 && hours <= max_hours => Ok(Validation::Valid),
+                _ => Ok(Validation::Invalid(
+                    format!("Enter a whole number between 1 and {}", max_hours).into(),
+                )),
+            });
+
+        if let Some(ttl_hours) = prompt_user(ttl_prompt) {
+            let ttl_hours = ttl_hours
+                .parse::<u64>()
+                .expect("TTL should have been validated as a number");
+            let resources: Vec<ExpiringResource> = expiring
+                .into_iter()
+                .map(|expiring| expiring.resource)
+                .collect();
+
+            println!("Extending TTLs...");
+            match service
+                .extend_ttl(&resources, Duration::from_secs(ttl_hours * 3600), 5)
+                .await
+            {
+                Ok(report) => {
+                    println!(
+                        "Extended: {}, Failed: {}",
+                        report.extended,
+                        report.failed.len()
+                    );
+                    for failure in &report.failed {
+                        eprintln!("  {} - {}", failure.resource, failure.error);
+                    }
+                    println!();
+                }
+                Err(error) => {
+                    eprintln!("TTL extension aborted: {}", error);
+                    println!();
+                }
+            }
+        }
+    }
+}
+
+/// Mints a Sync Access Token for the Service from an API Key provided by the user,
+/// for testing front-end clients.
+fn mint_access_token(twilio: &Client, sync_service: &SyncService) {
+    let api_key_sid_prompt = Text::new("Please provide an API Key SID:")
+        .with_placeholder("SK...")
+        .with_validator(|val: &str| match val.starts_with("SK") && val.len() == 34 {
+            true => Ok(Validation::Valid),
+            false => Ok(Validation::Invalid(
+                "API Key SID must start with SK and be 34 characters in length".into(),
+            )),
+        });
+
+    if let Some(api_key_sid) = prompt_user(api_key_sid_prompt) {
+        let api_key_secret_prompt = Password::new("Provide the API Key secret (input hidden):")
+            .with_validator(|val: &str| match val.is_empty() {
+                true => Ok(Validation::Invalid("API Key secret cannot be empty".into())),
+                false => Ok(Validation::Valid),
+            })
+            .with_display_mode(PasswordDisplayMode::Masked)
+            .with_display_toggle_enabled()
+            .without_confirmation()
+            .with_help_message("Input is masked. Use Ctrl + R to toggle visibility.");
+
+        if let Some(api_key_secret) = prompt_user(api_key_secret_prompt) {
+            let identity_prompt = Text::new("Enter the identity for the token:").with_validator(
+                |val: &str| match val.is_empty() {
+                    true => Ok(Validation::Invalid("Identity cannot be empty".into())),
+                    false => Ok(Validation::Valid),
+                },
+            );
+
+            if let Some(identity) = prompt_user(identity_prompt) {
+                let max_minutes = jwt::MAX_TTL.as_secs() / 60;
+                let ttl_prompt = Text::new("How many minutes should the token be valid for?")
+                    .with_default("60")
+                    .with_validator(move |val: &str| match val.parse::<u64>() {
+                        Ok(minutes) if minutes > 0 && minutes <= max_minutes => {
+                            Ok(Validation::Valid)
+                        }
+                        _ => Ok(Validation::Invalid(
+                            format!("Enter a whole number between 1 and {}", max_minutes).into(),
+                        )),
+                    });
+
+                if let Some(minutes) = prompt_user(ttl_prompt) {
+                    let mut token = AccessToken::new(
+                        twilio.account_sid(),
+                        &api_key_sid,
+                        &api_key_secret,
+                        &identity,
+                    );
+                    token.ttl = Duration::from_secs(
+                        minutes
+                            .parse::<u64>()
+                            .expect("TTL should have been validated as a number")
+                            * 60,
+                    );
+                    token.grants.sync = Some(SyncGrant {
+                        service_sid: sync_service.sid.clone(),
+                        endpoint_id: None,
+                    });
+
+                    match token.to_jwt() {
+                        Ok(jwt) => {
+                            println!("{}", jwt);
+                            println!();
+                        }
+                        Err(error) => {
+                            eprintln!("Unable to mint token. Action aborted: {}", error);
+                            println!();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Prompts the user for an export format and file path. The path defaults to the
+/// provided name with the extension of the chosen format.
+fn get_export_destination(default_name: &str) -> Option<(Format, String)> {
+    let formats: Vec<Format> = Format::iter().collect();
+    let format = prompt_user_selection(Select::new("Select an export format:", formats))?;
+
+    let default_path = format!("{}.{}", default_name, format.extension());
+    let path_prompt = Text::new("Enter a file path:").with_default(&default_path);
+    let path = prompt_user(path_prompt)?;
+
+    Some((format, path))
+}
+
+/// Writes the records to the file path, reporting the outcome to the user.
+pub(crate) fn write_export_file<T: Serialize>(records: &[T], format: Format, path: &str) {
+    match File::create(path) {
+        Ok(file) => match write_records(file, records, format) {
+            Ok(_) => {
+                println!("Exported {} items to {}", records.len(), path);
+                println!();
+            }
+            Err(error) => eprintln!(
+                "Failed to fully write to export file. Action aborted: {}",
+                error
+            ),
+        },
+        Err(error) => eprintln!("Unable to create export file. Action aborted: {}", error),
+    }
+}
+
+/// Prompts the user for a file to import and reads the records from it. The format
+/// is determined by the file extension.
+fn read_import_file<T: DeserializeOwned>() -> Option<Vec<T>> {
+    let path_prompt = Text::new("Enter the path of the file to import:")
+        .with_help_message("Files ending in .ndjson or .jsonl are read as NDJSON, otherwise JSON.");
+    let path = prompt_user(path_prompt)?;
+
+    match File::open(&path) {
+        Ok(file) => match read_records(BufReader::new(file), Format::from_path(&path)) {
+            Ok(records) => Some(records),
+            Err(error) => {
+                eprintln!("Unable to read import file. Action aborted: {}", error);
+                None
+            }
+        },
+        Err(error) => {
+            eprintln!("Unable to open import file. Action aborted: {}", error);
+            None
+        }
+    }
+}
+
+/// Prompts the user for how existing items should be handled and how many
+/// items to import at once. `conflict_message` is shown when asking for the
+/// conflict policy.
+fn get_import_params(conflict_message: &str) -> Option<ImportParams> {
+    let conflict_policies: Vec<ConflictPolicy> = ConflictPolicy::iter().collect();
+    let conflict_policy = prompt_user_selection(
+        Select::new(conflict_message, conflict_policies)
+            .with_starting_cursor(ConflictPolicy::iter().count() - 1),
+    )?;
+
+    let concurrency_prompt = Text::new("How many items should be imported at once?")
+        .with_default("5")
+        .with_validator(|val: &str| match val.parse::<usize>() {
+            Ok(concurrency) if concurrency > 0 => Ok(Validation::Valid),
+            _ => Ok(Validation::Invalid(
+                "Enter a whole number greater than 0".into(),
+            )),
+        });
+    let concurrency = prompt_user(concurrency_prompt)?
+        .parse::<usize>()
+        .expect("Concurrency should have been validated as a number");
+
+    Some(ImportParams {
+        concurrency,
+        conflict_policy,
+    })
+}
+
+/// Prints the progress of an import on a single, updating line.
+fn print_import_progress(progress: ImportProgress) {
+    print!("\rImported {}/{} items", progress.processed, progress.total);
+    io::stdout().flush().unwrap_or_default();
+}
+
+/// Prints the summary of a completed import including any failed items.
+pub(crate) fn print_import_report(report: &ImportReport) {
+    println!();
+    println!(
+        "Import complete. Created: {}, Updated: {}, Skipped: {}, Failed: {}",
+        report.created,
+        report.updated,
+        report.skipped,
+        report.failed.len()
+    );
+    for failure in &report.failed {
+        eprintln!("  {} - {}", failure.key, failure.error);
+    }
+    println!();
+}
+
+/// Prompts the user for a snapshot file and reads it.
+fn read_snapshot_file() -> Option<Snapshot> {
+    let path = prompt_user(Text::new("Enter the path of the snapshot file:"))?;
+
+    match File::open(&path) {
+        Ok(file) => match Snapshot::read(BufReader::new(file)) {
+            Ok(snapshot) => {
+                println!(
+                    "Snapshot of {} taken {}: {} Documents, {} Lists, {} Maps",
+                    snapshot.service_sid,
+                    snapshot.date_created,
+                    snapshot.objects.documents.len(),
+                    snapshot.objects.lists.len(),
+                    snapshot.objects.maps.len()
+                );
+                Some(snapshot)
+            }
+            Err(error) => {
+                eprintln!("Unable to read snapshot file. Action aborted: {}", error);
+                None
+            }
+        },
+        Err(error) => {
+            eprintln!("Unable to open snapshot file. Action aborted: {}", error);
+            None
+        }
+    }
+}
+
+/// Prompts the user for whether a restore should be a dry run, how existing
+/// objects should be handled and how many items to write at once.
+fn get_restore_params() -> Option<RestoreParams> {
+    let dry_run_prompt = Confirm::new("Dry run? Changes will be listed but not made.")
+        .with_placeholder("Y")
+        .with_default(true);
+    let dry_run = prompt_user(dry_run_prompt)?;
+    let import_params = get_import_params("When a Document, List or Map already exists:")?;
+
+    Some(RestoreParams {
+        dry_run,
+        conflict_policy: import_params.conflict_policy,
+        concurrency: import_params.concurrency,
+    })
+}
+
+/// Prints the planned changes of a restore and, unless it was a dry run, the outcome.
+pub(crate) fn print_restore_report(report: &RestoreReport, dry_run: bool) {
+    for action in &report.plan {
+        println!("  {}", action);
+    }
+
+    if dry_run {
+        println!("Dry run complete. {} changes planned.", report.plan.len());
+        println!();
+        return;
+    }
+
+    for (label, tally) in [("Objects", &report.objects), ("Items", &report.items)] {
+        println!(
+            "{} - Created: {}, Updated: {}, Skipped: {}, Failed: {}",
+            label,
+            tally.created,
+            tally.updated,
+            tally.skipped,
+            tally.failed.len()
+        );
+        for failure in &tally.failed {
+            eprintln!("  {} - {}", failure.key, failure.error);
+        }
+    }
+    println!();
+}
+
+/// Creates a new Sync Service from a snapshot file and restores its contents.
+/// Returns `None` if cancelled, the restore was a dry run or the Service could not
+/// be created.
+async fn restore_new_service(twilio: &Client) -> Option<SyncService> {
+    let snapshot = read_snapshot_file()?;
+    let dry_run_prompt = Confirm::new("Dry run? Changes will be listed but not made.")
+        .with_placeholder("Y")
+        .with_default(true);
+    let dry_run = prompt_user(dry_run_prompt)?;
+
+    let service_params = snapshot.service_params();
+    let service_name = service_params.friendly_name.clone().unwrap_or_default();
+
+    if dry_run {
+        println!("  Create Sync Service '{}'", service_name);
+        for action in snapshot.plan_for_new_service() {
+            println!("  {}", action);
+        }
+        println!("Dry run complete.");
+        println!();
+        return None;
+    }
+
+    println!("Creating Sync Service '{}'...", service_name);
+    let sync_service = match twilio.sync().services().create(service_params).await {
+        Ok(sync_service) => sync_service,
+        Err(error) => {
+            eprintln!("Unable to create Sync Service. Action aborted: {}", error);
+            return None;
+        }
+    };
+
+    println!("Restoring snapshot...");
+    match twilio
+        .sync()
+        .service(&sync_service.sid)
+        .restore(
+            &snapshot,
+            RestoreParams {
+                dry_run: false,
+                conflict_policy: ConflictPolicy::Fail,
+                concurrency: ImportParams::default().concurrency,
+            },
+        )
+        .await
+    {
+        Ok(report) => print_restore_report(&report, false),
+        Err(error) => {
+            eprintln!("Restore aborted: {}", error);
+            println!();
+        }
+    }
+
+    Some(sync_service)
+}
+
+/// Prompts the user for the Sync Service to copy into, on either the current
+/// account or another account. Returns a client for the chosen account along
+/// with the SID of the target Service.
+async fn get_copy_destination(twilio: &Client) -> Option<(Client, String)> {
+    let account_options = vec![
+        String::from("This account"),
+        String::from("Another account"),
+    ];
+    let account_choice = prompt_user_selection(Select::new("Copy to:", account_options))?;
+    let target_client = if account_choice == "Another account" {
+        Client::new(&request_credentials())
+    } else {
+        Client::new(&twilio.config)
+    };
+
+    let target_services = match target_client.sync().services().list().await {
+        Ok(target_services) => target_services,
+        Err(error) => {
+            eprintln!("Unable to list Sync Services. Action aborted: {}", error);
+            return None;
+        }
+    };
+
+    if target_services.is_empty() {
+        println!("No Sync Services found.");
+        return None;
+    }
+
+    let target_options = target_services
+        .iter()
+        .map(|service| {
+            format!(
+                "({}) {}",
+                service.sid,
+                service
+                    .unique_name
+                    .as_ref()
+                    .or(service.friendly_name.as_ref())
+                    .unwrap_or(&service.sid)
+            )
+        })
+        .collect::<Vec<String>>();
+    let target_choice = prompt_user_selection(Select::new(
+        "Choose the target Sync Service:",
+        target_options,
+    ))?;
+
+    Some((target_client, target_choice[1..35].to_string()))
+}
+
+/// Prompts the user for the unique name of a copy, defaulting to the original,
+/// along with the restore options to use.
+fn get_copy_params(unique_name: &str) -> Option<CopyParams> {
+    let unique_name_prompt = Text::new("Unique name of the copy:").with_default(unique_name);
+    let unique_name = prompt_user(unique_name_prompt)?;
+    let restore_params = get_restore_params()?;
+
+    Some(CopyParams {
+        unique_name: Some(unique_name),
+        dry_run: restore_params.dry_run,
+        conflict_policy: restore_params.conflict_policy,
+        concurrency: restore_params.concurrency,
+    })
+}
+
+/// A filter on a single field of an item's data, matched client-side.
+#[derive(Clone)]
+pub(crate) struct DataFilter {
+    /// JSON pointer to the field, e.g. `/status`.
+    pointer: String,
+    value: String,
+}
+
+impl DataFilter {
+    /// Whether the field in `data` equals the filter value. String fields are compared
+    /// without their quotes, anything else by parsing the filter value as JSON.
+    pub(crate) fn matches(&self, data: &Value) -> bool {
+        match data.pointer(&self.pointer) {
+            Some(Value::String(field)) => field == &self.value,
+            Some(field) => {
+                serde_json::from_str::<Value>(&self.value).is_ok_and(|value| &value == field)
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for DataFilter {
+    type Err = String;
+
+    /// Parses a filter in the form `/field=value`.
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        match filter.split_once('=') {
+            Some((pointer, value)) if pointer.starts_with('/') => Ok(DataFilter {
+                pointer: pointer.to_string(),
+                value: value.to_string(),
+            }),
+            _ => Err(String::from("Enter a filter in the form /field=value")),
+        }
+    }
+}
+
+/// Prompts the user for an optional data filter. Returns `Some(None)` if the user
+/// does not want to filter and `None` if they cancelled.
+fn get_data_filter() -> Option<Option<DataFilter>> {
+    let filter_prompt = Text::new("Filter on a data field (optional):")
+        .with_placeholder("/status=active")
+        .with_help_message(
+            "A JSON pointer into the item data and the value it should equal. Leave blank to match every item.",
+        )
+        .with_validator(|val: &str| match val.is_empty() {
+            true => Ok(Validation::Valid),
+            false => match val.parse::<DataFilter>() {
+                Ok(_) => Ok(Validation::Valid),
+                Err(error) => Ok(Validation::Invalid(error.into())),
+            },
+        });
+    let filter = prompt_user(filter_prompt)?;
+
+    match filter.is_empty() {
+        true => Some(None),
+        false => Some(filter.parse().ok()),
+    }
+}
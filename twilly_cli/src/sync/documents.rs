@@ -3,9 +3,14 @@ use std::process;
 use inquire::{validator::Validation, Confirm, Select, Text};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
-use twilly::{sync::services::SyncService, Client, ErrorKind};
+use twilly::{
+    sync::{documents::SyncDocument, services::SyncService},
+    Client, ErrorKind,
+};
 use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection, ActionChoice};
 
+use crate::sync::{get_copy_destination, get_copy_params, print_restore_report};
+
 #[derive(Debug, Clone, Display, EnumIter, EnumString)]
 pub enum Action {
     #[strum(to_string = "Get Document")]
@@ -51,7 +56,11 @@ pub async fn choose_document_action(twilio: &Client, sync_service: &SyncService)
                         {
                             Ok(document) => loop {
                                 if let Some(action_choice) = get_action_choice_from_user(
-                                    vec![String::from("List Details"), String::from("Delete")],
+                                    vec![
+                                        String::from("List Details"),
+                                        String::from("Copy to Service"),
+                                        String::from("Delete"),
+                                    ],
                                     "Select an action: ",
                                 ) {
                                     match action_choice {
@@ -64,6 +73,10 @@ pub async fn choose_document_action(twilio: &Client, sync_service: &SyncService)
                                                 println!("{:#?}", document);
                                                 println!();
                                             }
+                                            "Copy to Service" => {
+                                                copy_document(twilio, sync_service, &document)
+                                                    .await;
+                                            }
                                             "Delete" => {
                                                 let confirm_prompt = Confirm::new(
                                                     "Are you sure you wish to delete the Document?",
@@ -160,7 +173,11 @@ pub async fn choose_document_action(twilio: &Client, sync_service: &SyncService)
 
                             loop {
                                 if let Some(action_choice) = get_action_choice_from_user(
-                                    vec![String::from("List Details"), String::from("Delete")],
+                                    vec![
+                                        String::from("List Details"),
+                                        String::from("Copy to Service"),
+                                        String::from("Delete"),
+                                    ],
                                     "Select an action: ",
                                 ) {
                                     match action_choice {
@@ -174,6 +191,14 @@ pub async fn choose_document_action(twilio: &Client, sync_service: &SyncService)
                                                 println!("{:#?}", selected_document);
                                                 println!();
                                             }
+                                            "Copy to Service" => {
+                                                copy_document(
+                                                    twilio,
+                                                    sync_service,
+                                                    selected_document,
+                                                )
+                                                .await;
+                                            }
                                             "Delete" => {
                                                 let confirm_prompt = Confirm::new(
                                                 "Are you sure you wish to delete the Document? ",
@@ -221,3 +246,29 @@ pub async fn choose_document_action(twilio: &Client, sync_service: &SyncService)
         }
     }
 }
+
+/// Copies a Document into a Sync Service chosen by the user.
+async fn copy_document(twilio: &Client, sync_service: &SyncService, document: &SyncDocument) {
+    if let Some((target_client, target_service_sid)) = get_copy_destination(twilio).await {
+        if let Some(copy_params) = get_copy_params(&document.unique_name) {
+            let dry_run = copy_params.dry_run;
+            println!("Copying Document...");
+            match twilio
+                .sync()
+                .service(&sync_service.sid)
+                .document(&document.sid)
+                .copy_to(
+                    &target_client.sync().service(&target_service_sid),
+                    copy_params,
+                )
+                .await
+            {
+                Ok(report) => print_restore_report(&report, dry_run),
+                Err(error) => {
+                    eprintln!("Copy aborted: {}", error);
+                    println!();
+                }
+            }
+        }
+    }
+}
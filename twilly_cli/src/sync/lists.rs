@@ -1,17 +1,29 @@
-use std::process;
+use std::{ops::Bound, process};
 
-use inquire::{Confirm, Select};
+use inquire::{validator::Validation, Confirm, Select, Text};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
 use twilly::{sync::services::SyncService, Client};
 use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection, ActionChoice};
 
-use crate::sync::listitems;
+use crate::sync::{
+    get_copy_destination, get_copy_params, get_data_filter, get_export_destination,
+    get_import_params, listitems, print_import_progress, print_import_report, print_restore_report,
+    read_import_file, write_export_file,
+};
 
 #[derive(Debug, Clone, Display, EnumIter, EnumString)]
 pub enum Action {
     #[strum(to_string = "List Items")]
     ListItem,
+    #[strum(to_string = "Search Items")]
+    SearchItems,
+    #[strum(to_string = "Export Items")]
+    ExportItems,
+    #[strum(to_string = "Import Items")]
+    ImportItems,
+    #[strum(to_string = "Copy to Service")]
+    CopyToService,
     #[strum(to_string = "List Details")]
     ListDetails,
     Delete,
@@ -74,6 +86,101 @@ pub async fn choose_list_action(twilio: &Client, sync_service: &SyncService) {
                         .await;
                 }
 
+                Action::SearchItems => {
+                    if let Some((start, end)) = get_index_range() {
+                        if let Some(data_filter) = get_data_filter() {
+                            println!("Searching Sync List items...");
+                            let mut list_items = twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .list(&selected_sync_list.sid)
+                                .listitems()
+                                .list_range((
+                                    Bound::Included(start),
+                                    end.map_or(Bound::Unbounded, Bound::Included),
+                                ))
+                                .await
+                                .unwrap_or_else(|error| panic!("{}", error));
+
+                            if let Some(data_filter) = data_filter {
+                                list_items.retain(|list_item| data_filter.matches(&list_item.data));
+                            }
+
+                            println!("Found {} matching items.", list_items.len());
+                            for list_item in &list_items {
+                                println!("  {}: {}", list_item.index, list_item.data);
+                            }
+                            println!();
+                        }
+                    }
+                }
+                Action::ExportItems => {
+                    if let Some((format, path)) =
+                        get_export_destination(&selected_sync_list.unique_name)
+                    {
+                        println!("Fetching Sync List items...");
+                        let records = twilio
+                            .sync()
+                            .service(&sync_service.sid)
+                            .list(&selected_sync_list.sid)
+                            .listitems()
+                            .export()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+                        write_export_file(&records, format, &path);
+                    }
+                }
+                Action::ImportItems => {
+                    if let Some(records) = read_import_file() {
+                        println!("Found {} items to import.", records.len());
+                        if let Some(import_params) =
+                            get_import_params("When an item already exists:")
+                        {
+                            let report = twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .list(&selected_sync_list.sid)
+                                .listitems()
+                                .import(records, import_params, print_import_progress)
+                                .await;
+
+                            match report {
+                                Ok(report) => print_import_report(&report),
+                                Err(error) => {
+                                    eprintln!("Import aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::CopyToService => {
+                    if let Some((target_client, target_service_sid)) =
+                        get_copy_destination(twilio).await
+                    {
+                        if let Some(copy_params) = get_copy_params(&selected_sync_list.unique_name)
+                        {
+                            let dry_run = copy_params.dry_run;
+                            println!("Copying Sync List...");
+                            match twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .list(&selected_sync_list.sid)
+                                .copy_to(
+                                    &target_client.sync().service(&target_service_sid),
+                                    copy_params,
+                                )
+                                .await
+                            {
+                                Ok(report) => print_restore_report(&report, dry_run),
+                                Err(error) => {
+                                    eprintln!("Copy aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
                 Action::ListDetails => {
                     println!("{:#?}", selected_sync_list);
                     println!();
@@ -110,3 +217,40 @@ pub async fn choose_list_action(twilio: &Client, sync_service: &SyncService) {
         }
     }
 }
+
+/// Prompts the user for an inclusive range of List Item indexes. The end of the
+/// range is optional.
+fn get_index_range() -> Option<(u32, Option<u32>)> {
+    let start_prompt = Text::new("First index:")
+        .with_default("0")
+        .with_validator(|val: &str| match val.parse::<u32>() {
+            Ok(_) => Ok(Validation::Valid),
+            Err(_) => Ok(Validation::Invalid("Enter a whole number".into())),
+        });
+    let start = prompt_user(start_prompt)?
+        .parse::<u32>()
+        .expect("Start index should have been validated as a number");
+
+    let end_prompt = Text::new("Last index (optional):")
+        .with_help_message("Leave blank to read to the end of the List.")
+        .with_validator(move |val: &str| {
+            if val.is_empty() {
+                return Ok(Validation::Valid);
+            }
+            match val.parse::<u32>() {
+                Ok(end) if end >= start => Ok(Validation::Valid),
+                _ => Ok(Validation::Invalid(
+                    "Enter a whole number no lower than the first index".into(),
+                )),
+            }
+        });
+    let end = prompt_user(end_prompt)?;
+
+    Some((
+        start,
+        (!end.is_empty()).then(|| {
+            end.parse::<u32>()
+                .expect("End index should have been validated as a number")
+        }),
+    ))
+}
@@ -14,12 +14,24 @@ use twilly::{
 };
 use twilly_cli::{get_action_choice_from_user, prompt_user, prompt_user_selection, ActionChoice};
 
-use crate::sync::mapitems;
+use crate::sync::{
+    get_copy_destination, get_copy_params, get_data_filter, get_export_destination,
+    get_import_params, mapitems, print_import_progress, print_import_report, print_restore_report,
+    read_import_file, write_export_file,
+};
 
 #[derive(Debug, Clone, Display, EnumIter, EnumString)]
 pub enum Action {
     #[strum(to_string = "Map Items")]
     MapItem,
+    #[strum(to_string = "Search Items")]
+    SearchItems,
+    #[strum(to_string = "Export Items")]
+    ExportItems,
+    #[strum(to_string = "Import Items")]
+    ImportItems,
+    #[strum(to_string = "Copy to Service")]
+    CopyToService,
     #[strum(to_string = "List Details")]
     ListDetails,
     Rename,
@@ -44,6 +56,7 @@ pub async fn choose_map_action(twilio: &Client, sync_service: &SyncService) {
 
     println!("Found {} Sync Maps.", sync_maps.len());
 
+    let map_name_chars = Regex::new(r"^[a-zA-Z0-9-_]+$").unwrap();
     let mut selected_sync_map_index: Option<usize> = None;
     loop {
         let selected_sync_map = if let Some(index) = selected_sync_map_index {
@@ -82,6 +95,99 @@ pub async fn choose_map_action(twilio: &Client, sync_service: &SyncService) {
                     mapitems::choose_map_item_action(twilio, sync_service, selected_sync_map).await;
                 }
 
+                Action::SearchItems => {
+                    let prefix_prompt = Text::new("Key prefix (optional):")
+                        .with_help_message("Leave blank to search every item.");
+                    if let Some(prefix) = prompt_user(prefix_prompt) {
+                        if let Some(data_filter) = get_data_filter() {
+                            println!("Searching Sync Map items...");
+                            let mut map_items = twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .map(&selected_sync_map.sid)
+                                .mapitems()
+                                .list_with_prefix(&prefix)
+                                .await
+                                .unwrap_or_else(|error| panic!("{}", error));
+
+                            if let Some(data_filter) = data_filter {
+                                map_items.retain(|map_item| data_filter.matches(&map_item.data));
+                            }
+
+                            println!("Found {} matching items.", map_items.len());
+                            for map_item in &map_items {
+                                println!("  {}: {}", map_item.key, map_item.data);
+                            }
+                            println!();
+                        }
+                    }
+                }
+                Action::ExportItems => {
+                    if let Some((format, path)) =
+                        get_export_destination(&selected_sync_map.unique_name)
+                    {
+                        println!("Fetching Sync Map items...");
+                        let records = twilio
+                            .sync()
+                            .service(&sync_service.sid)
+                            .map(&selected_sync_map.sid)
+                            .mapitems()
+                            .export()
+                            .await
+                            .unwrap_or_else(|error| panic!("{}", error));
+                        write_export_file(&records, format, &path);
+                    }
+                }
+                Action::ImportItems => {
+                    if let Some(records) = read_import_file() {
+                        println!("Found {} items to import.", records.len());
+                        if let Some(import_params) =
+                            get_import_params("When an item already exists:")
+                        {
+                            let report = twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .map(&selected_sync_map.sid)
+                                .mapitems()
+                                .import(records, import_params, print_import_progress)
+                                .await;
+
+                            match report {
+                                Ok(report) => print_import_report(&report),
+                                Err(error) => {
+                                    eprintln!("Import aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::CopyToService => {
+                    if let Some((target_client, target_service_sid)) =
+                        get_copy_destination(twilio).await
+                    {
+                        if let Some(copy_params) = get_copy_params(&selected_sync_map.unique_name) {
+                            let dry_run = copy_params.dry_run;
+                            println!("Copying Sync Map...");
+                            match twilio
+                                .sync()
+                                .service(&sync_service.sid)
+                                .map(&selected_sync_map.sid)
+                                .copy_to(
+                                    &target_client.sync().service(&target_service_sid),
+                                    copy_params,
+                                )
+                                .await
+                            {
+                                Ok(report) => print_restore_report(&report, dry_run),
+                                Err(error) => {
+                                    eprintln!("Copy aborted: {}", error);
+                                    println!();
+                                }
+                            }
+                        }
+                    }
+                }
                 Action::ListDetails => {
                     println!("{:#?}", selected_sync_map);
                     println!();
@@ -89,15 +195,16 @@ pub async fn choose_map_action(twilio: &Client, sync_service: &SyncService) {
                 Action::Rename => {
                     let get_name_prompt = Text::new(
                         "What would you like to rename this map to? Must be supported characters '^[a-zA-Z0-9-_]+$'"
-                    ).with_validator(|val: &str| {
-                        let allowed_chars = Regex::new(r"^[a-zA-Z0-9-_]+$").unwrap();
+                    ).with_validator({
+                        let allowed_chars = map_name_chars.clone();
+                        move |val: &str| {
                         let trimmed_name = val.trim();
                         if !allowed_chars.is_match(trimmed_name) {
                             return Ok(Validation::Invalid("Name doesn't match required filter '^[a-zA-Z0-9-_]+$'".into()));
                         }
 
                         Ok(Validation::Valid)
-                    });
+                    }});
                     let get_name_result = prompt_user(get_name_prompt);
 
                     if get_name_result.is_none() {